@@ -1,12 +1,56 @@
-use std::{fs, str::FromStr};
+use std::{env, fs, path::Path, str::FromStr};
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use srx::{Rules, SRX};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use srx::{Prefilter, Rules, SRX};
+
+/// Approximate size of the synthetic corpus per language.
+const CORPUS_SIZE: usize = 2 * 1024 * 1024;
+
+/// Seed paragraphs which are repeated to build a synthetic corpus if no real corpus is given.
+/// Each one contains some abbreviations, numbers and quotes to exercise the exception rules.
+const SEEDS: &[(&str, &str)] = &[
+    (
+        "en",
+        "The U.K. Prime Minister, Mr. Blair, was seen out with his family today. He is well. \
+         Dr. Smith arrived at 5 p.m. and left with approx. 3.5 kg of documents, e.g. letters. \
+         \"Is this the end?\" she asked. Nobody answered! The meeting continued until Jan. 3rd, \
+         when the committee (led by Prof. J. R. Miller) published its report on p. 42.\n",
+    ),
+    (
+        "de",
+        "Die Sitzung fand am 3. Oktober statt. Herr Dr. Müller, u.a. Vorsitzender, sprach z.B. \
+         über die Kosten von ca. 4,5 Mio. Euro. \"Ist das wirklich nötig?\", fragte Frau Prof. \
+         Schmidt. Niemand antwortete! Die Ergebnisse werden bis Ende Jan. veröffentlicht, \
+         vgl. Anhang S. 12 ff.\n",
+    ),
+    (
+        "fr",
+        "M. Dupont est arrivé à Paris le 3 janv. avec Mme Martin. Il a dit : « Est-ce vraiment \
+         la fin ? » Personne n'a répondu ! La réunion, présidée par le Dr. Bernard, a duré env. \
+         3 h. Les résultats, cf. p. 12, seront publiés etc. avant la fin du mois.\n",
+    ),
+    (
+        "es",
+        "El Sr. García llegó a Madrid el 3 de ene. con la Sra. López. ¿Es realmente el final? \
+         ¡Nadie respondió! La reunión, presidida por el Dr. Fernández, duró aprox. 3 horas. \
+         Los resultados, véase pág. 12, se publicarán etc. antes de fin de mes.\n",
+    ),
+];
 
 fn split<'a>(string: &'a str, rules: &Rules) -> Vec<&'a str> {
     rules.split(string).collect::<Vec<_>>()
 }
 
+/// Loads the corpus for a language. If the `SRX_BENCH_CORPUS` environment variable is set,
+/// reads `<lang>.txt` from that directory (e.g. a Gutenberg book or WMT news crawl).
+/// Otherwise repeats the seed paragraph until the corpus is approximately [CORPUS_SIZE] bytes large.
+fn corpus(lang: &str, seed: &str) -> Option<String> {
+    match env::var("SRX_BENCH_CORPUS") {
+        Ok(dir) => fs::read_to_string(Path::new(&dir).join(format!("{}.txt", lang))).ok(),
+        Err(_) => Some(seed.repeat(CORPUS_SIZE / seed.len() + 1)),
+    }
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     let rules =
         SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
@@ -23,7 +67,63 @@ fn criterion_benchmark(c: &mut Criterion) {
             );
         })
     });
+
+    let mut splitter = rules.splitter();
+    c.bench_function("split string with splitter", |b| {
+        b.iter(|| {
+            let _ = splitter
+                .split(black_box(
+                    "The U.K. Prime Minister, Mr. Blair, was seen out with his family today.",
+                ))
+                .collect::<Vec<_>>();
+        })
+    });
+}
+
+fn corpus_benchmark(c: &mut Criterion) {
+    let srx = SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+        .expect("segment file is valid");
+
+    let corpora: Vec<_> = SEEDS
+        .iter()
+        .filter_map(|(lang, seed)| {
+            corpus(lang, seed).map(|text| (*lang, srx.language_rules(lang), text))
+        })
+        .collect();
+
+    // criterion can only report one throughput per group, so bytes and sentences get separate groups
+    let mut group = c.benchmark_group("corpus bytes");
+    group.sample_size(10);
+    for (lang, rules, text) in &corpora {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(lang), text, |b, text| {
+            b.iter(|| split(black_box(text), rules))
+        });
+
+        let prefiltered = rules.clone().with_prefilter(Prefilter::default());
+        group.bench_with_input(BenchmarkId::new("prefilter", lang), text, |b, text| {
+            b.iter(|| split(black_box(text), &prefiltered))
+        });
+
+        if text.is_ascii() {
+            let ascii = rules.clone().with_ascii();
+            group.bench_with_input(BenchmarkId::new("ascii", lang), text, |b, text| {
+                b.iter(|| split(black_box(text), &ascii))
+            });
+        }
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("corpus sentences");
+    group.sample_size(10);
+    for (lang, rules, text) in &corpora {
+        group.throughput(Throughput::Elements(split(text, rules).len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(lang), text, |b, text| {
+            b.iter(|| split(black_box(text), rules))
+        });
+    }
+    group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark);
+criterion_group!(benches, criterion_benchmark, corpus_benchmark);
 criterion_main!(benches);
@@ -0,0 +1,161 @@
+//! Compares segmentation with `srx` against UAX #29 sentence boundaries and optionally an external segmenter
+//! (e. g. [loomchild/segment](https://github.com/loomchild/segment)) on the same corpus.
+//!
+//! Usage:
+//!
+//! ```text
+//! cargo run --release --example compare --features from_xml -- <corpus.txt> <lang> [srx file] [external command...]
+//! ```
+//!
+//! The external command receives the corpus on stdin and must print one segment per line to stdout, e. g.
+//! `segment -s data/segment.srx -l en`. The report is printed as a Markdown table.
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    io::Write,
+    process::{Command, Stdio},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use srx::SRX;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Byte offsets at which segments start, excluding the start of the text.
+type Boundaries = BTreeSet<usize>;
+
+struct Run {
+    name: String,
+    boundaries: Boundaries,
+    elapsed: Duration,
+}
+
+fn boundaries_from_ranges<I: IntoIterator<Item = usize>>(starts: I) -> Boundaries {
+    starts.into_iter().filter(|&start| start > 0).collect()
+}
+
+/// Aligns segments which may have lost whitespace (e. g. by trimming) back to the text.
+fn align(text: &str, segments: &[&str]) -> Boundaries {
+    let mut boundaries = Boundaries::new();
+    let mut cursor = 0;
+
+    for segment in segments {
+        let segment = segment.trim();
+        if segment.is_empty() {
+            continue;
+        }
+
+        if let Some(offset) = text[cursor..].find(segment) {
+            boundaries.insert(cursor + offset);
+            cursor += offset + segment.len();
+        }
+    }
+
+    boundaries.remove(&0);
+    boundaries
+}
+
+/// Normalizes boundaries by moving them past leading whitespace of the segment so that segmenters
+/// which attach whitespace to different segments still agree.
+fn normalize(text: &str, boundaries: &Boundaries) -> Boundaries {
+    boundaries
+        .iter()
+        .map(|&boundary| boundary + (text[boundary..].len() - text[boundary..].trim_start().len()))
+        .filter(|&boundary| boundary < text.len())
+        .collect()
+}
+
+fn run_external(command: &[String], text: &str) -> Option<Run> {
+    let start = Instant::now();
+    let mut child = Command::new(&command[0])
+        .args(&command[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|error| eprintln!("could not run {:?}: {}", command, error))
+        .ok()?;
+
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(text.as_bytes())
+        .ok()?;
+    let output = child.wait_with_output().ok()?;
+    let elapsed = start.elapsed();
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    let segments: Vec<_> = output.lines().collect();
+
+    Some(Run {
+        name: command[0].clone(),
+        boundaries: align(text, &segments),
+        elapsed,
+    })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 2 {
+        eprintln!("usage: compare <corpus.txt> <lang> [srx file] [external command...]");
+        std::process::exit(1);
+    }
+
+    let text = fs::read_to_string(&args[0]).expect("corpus is readable");
+    let lang = &args[1];
+    let srx_path = args.get(2).map_or("data/segment.srx", |x| x.as_str());
+
+    let srx = SRX::from_str(&fs::read_to_string(srx_path).expect("SRX file is readable"))
+        .expect("SRX file is valid");
+    let rules = srx.language_rules(lang);
+
+    let mut runs = Vec::new();
+
+    let start = Instant::now();
+    let ranges = rules.split_ranges(&text);
+    runs.push(Run {
+        name: "srx".into(),
+        boundaries: boundaries_from_ranges(ranges.iter().map(|range| range.start)),
+        elapsed: start.elapsed(),
+    });
+
+    let start = Instant::now();
+    let starts: Vec<_> = text
+        .split_sentence_bound_indices()
+        .map(|(i, _)| i)
+        .collect();
+    runs.push(Run {
+        name: "UAX #29".into(),
+        boundaries: boundaries_from_ranges(starts),
+        elapsed: start.elapsed(),
+    });
+
+    if args.len() > 3 {
+        runs.extend(run_external(&args[3..], &text));
+    }
+
+    let reference = normalize(&text, &runs[0].boundaries);
+    let megabytes = text.len() as f64 / 1_000_000.0;
+
+    println!("| segmenter | segments | MB/s | segments/s | agreement with srx |");
+    println!("|---|---|---|---|---|");
+    for run in &runs {
+        let boundaries = normalize(&text, &run.boundaries);
+        let common = boundaries.intersection(&reference).count();
+        let union = boundaries.union(&reference).count();
+        let seconds = run.elapsed.as_secs_f64();
+
+        println!(
+            "| {} | {} | {:.2} | {:.0} | {:.2}% |",
+            run.name,
+            run.boundaries.len() + 1,
+            megabytes / seconds,
+            (run.boundaries.len() + 1) as f64 / seconds,
+            if union == 0 {
+                100.0
+            } else {
+                common as f64 / union as f64 * 100.0
+            }
+        );
+    }
+}
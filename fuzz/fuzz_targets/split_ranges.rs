@@ -0,0 +1,84 @@
+//! Splits arbitrary text with arbitrary rules, checking that splitting never panics and that the segments
+//! reproduce the text byte for byte.
+//!
+//! The input is read as lines: the first line is the text, each following pair of lines is the `before_break`
+//! and `after_break` of a rule. Rules break if their `before_break` starts with a space.
+#![no_main]
+
+use std::str::FromStr;
+
+use libfuzzer_sys::fuzz_target;
+use srx::{Prefilter, SRX};
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn check(text: &str, rules: &srx::Rules) {
+    let ranges = rules.split_ranges(text);
+
+    let mut end = 0;
+    for range in &ranges {
+        assert_eq!(range.start, end);
+        assert!(text.is_char_boundary(range.end));
+        end = range.end;
+    }
+    assert_eq!(end, text.len());
+    assert_eq!(rules.split(text).collect::<String>(), text);
+}
+
+fuzz_target!(|data: &[u8]| {
+    let input = match std::str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+
+    let mut lines = input.split('\n');
+    let text = lines.next().unwrap_or_default();
+    let lines: Vec<_> = lines.collect();
+
+    let rules: String = lines
+        .chunks(2)
+        .map(|rule| {
+            let before = rule[0];
+            let after = rule.get(1).copied().unwrap_or_default();
+            format!(
+                "<rule break=\"{}\"><beforebreak>{}</beforebreak><afterbreak>{}</afterbreak></rule>",
+                if before.starts_with(' ') { "yes" } else { "no" },
+                escape(before),
+                escape(after)
+            )
+        })
+        .collect();
+
+    let xml = format!(
+        "<?xml version=\"1.0\"?>
+<srx version=\"2.0\" xmlns=\"http://www.lisa.org/srx20\">
+    <header segmentsubflows=\"yes\" cascade=\"yes\"/>
+    <body>
+        <languagerules>
+            <languagerule languagerulename=\"Fuzz\">{}</languagerule>
+        </languagerules>
+        <maprules>
+            <languagemap languagepattern=\".*\" languagerulename=\"Fuzz\"/>
+        </maprules>
+    </body>
+</srx>",
+        rules
+    );
+
+    // invalid rules are skipped, so this only fails for XML the fuzzer broke out of
+    let srx = match SRX::from_str(&xml) {
+        Ok(srx) => srx,
+        Err(_) => return,
+    };
+    let rules = srx.language_rules("fuzz");
+
+    check(text, &rules);
+    check(text, &rules.clone().with_prefilter(Prefilter::default()));
+    if text.is_ascii() {
+        check(text, &rules.with_ascii());
+    }
+});
@@ -0,0 +1,77 @@
+#ifndef SRX_H
+#define SRX_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit. */
+
+#include <stddef.h>
+
+// An ordered set of rules.
+// Rules are executed in order.
+// Once a rule matches on an index, no other rule can match at the same index.
+// Each rule either breaks (i. e. splits the text at this index) or prevents breaking.
+typedef struct SrxRules SrxRules;
+
+// The SRX root.
+// Does not execute rules on is own.
+typedef struct Srx Srx;
+
+// A segment of the text as byte offsets.
+typedef struct SrxRange {
+  size_t start;
+  size_t end;
+} SrxRange;
+
+// Loads rules from an XML string in SRX format.
+// Returns `NULL` if the string is not valid UTF-8 or not a valid SRX file.
+//
+// # Safety
+//
+// `xml` must point to a NUL-terminated string.
+struct Srx *srx_load(const char *xml);
+
+// Frees rules returned by [srx_load]. Does nothing if `srx` is `NULL`.
+//
+// # Safety
+//
+// `srx` must have been returned by [srx_load] and must not be used afterwards.
+void srx_free(struct Srx *srx);
+
+// Gets the rules for a language code, see [SRX::language_rules].
+// Returns `NULL` if `srx` is `NULL` or the language code is not valid UTF-8.
+//
+// # Safety
+//
+// `srx` must have been returned by [srx_load] and `lang_code` must point to a NUL-terminated string.
+struct SrxRules *srx_language_rules(const struct Srx *srx,
+                                    const char *lang_code);
+
+// Frees rules returned by [srx_language_rules]. Does nothing if `rules` is `NULL`.
+//
+// # Safety
+//
+// `rules` must have been returned by [srx_language_rules] and must not be used afterwards.
+void srx_rules_free(struct SrxRules *rules);
+
+// Splits the text of `length` bytes into segments and stores their number in `count`.
+// Returns `NULL` (and a count of 0) if the text is not valid UTF-8 or has no segments.
+// The ranges must be freed with [srx_ranges_free].
+//
+// # Safety
+//
+// `rules` must have been returned by [srx_language_rules], `text` must point to `length` readable bytes
+// and `count` must point to writable memory.
+struct SrxRange *srx_split(const struct SrxRules *rules,
+                           const char *text,
+                           size_t length,
+                           size_t *count);
+
+// Frees ranges returned by [srx_split]. Does nothing if `ranges` is `NULL`.
+//
+// # Safety
+//
+// `ranges` and `count` must have been returned by the same call of [srx_split] and the ranges must not be
+// used afterwards.
+void srx_ranges_free(struct SrxRange *ranges,
+                     size_t count);
+
+#endif  /* SRX_H */
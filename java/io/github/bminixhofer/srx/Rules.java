@@ -0,0 +1,38 @@
+package io.github.bminixhofer.srx;
+
+/** The rules of one language, created by {@link SRX#languageRules}. Independent of the {@link SRX} once created. */
+public final class Rules implements AutoCloseable {
+    private long handle;
+
+    Rules(long handle) {
+        this.handle = handle;
+    }
+
+    /** Splits the text into segments. */
+    public String[] split(String text) {
+        int[] spans = splitSpans(text);
+        String[] segments = new String[spans.length / 2];
+        for (int i = 0; i < segments.length; i++) {
+            segments[i] = text.substring(spans[2 * i], spans[2 * i + 1]);
+        }
+        return segments;
+    }
+
+    /** Splits the text and returns the offsets of the segments as {@code [start, end, start, end, ...]}. */
+    public int[] splitSpans(String text) {
+        if (handle == 0) {
+            throw new IllegalStateException("Rules are closed");
+        }
+        return splitSpans(handle, text);
+    }
+
+    @Override
+    public void close() {
+        free(handle);
+        handle = 0;
+    }
+
+    private static native void free(long handle);
+
+    private static native int[] splitSpans(long handle, String text);
+}
@@ -0,0 +1,46 @@
+package io.github.bminixhofer.srx;
+
+/**
+ * Rules loaded from an XML string in SRX format, backed by the native library {@code srx}
+ * built with {@code cargo rustc --release --lib --features jni --crate-type cdylib}.
+ */
+public final class SRX implements AutoCloseable {
+    static {
+        System.loadLibrary("srx");
+    }
+
+    private long handle;
+
+    /**
+     * Loads the rules from an XML string in SRX format.
+     *
+     * @throws IllegalArgumentException if the string is not a valid SRX file
+     */
+    public SRX(String xml) {
+        handle = load(xml);
+    }
+
+    /** Gets the rules for a language code. */
+    public Rules languageRules(String langCode) {
+        return new Rules(languageRules(handle(), langCode));
+    }
+
+    private long handle() {
+        if (handle == 0) {
+            throw new IllegalStateException("SRX is closed");
+        }
+        return handle;
+    }
+
+    @Override
+    public void close() {
+        free(handle);
+        handle = 0;
+    }
+
+    private static native long load(String xml);
+
+    private static native void free(long handle);
+
+    private static native long languageRules(long handle, String langCode);
+}
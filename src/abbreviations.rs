@@ -0,0 +1,185 @@
+use super::{Error, Language, Rule, SRX};
+
+/// Start of an abbreviation: the start of the text or a character which is not part of a word.
+const WORD_START: &str = "(?:^|[^\\p{L}\\p{N}])";
+
+/// Creates a rule preventing breaks after any of the abbreviations, or `None` if there are none.
+/// Abbreviations match as written (usually including their final period) when they start a word and are
+/// followed by `after_break`.
+fn abbreviation_rule<I, S>(abbreviations: I, after_break: &str) -> Result<Option<Rule>, Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut abbreviations: Vec<String> = abbreviations
+        .into_iter()
+        .map(|abbreviation| abbreviation.as_ref().trim().to_owned())
+        .filter(|abbreviation| !abbreviation.is_empty())
+        .collect();
+    if abbreviations.is_empty() {
+        return Ok(None);
+    }
+
+    // sorted (longer ones first) so that the generated rule does not depend on the order of the list
+    abbreviations.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    abbreviations.dedup();
+
+    let alternatives: Vec<String> = abbreviations
+        .iter()
+        .map(|abbreviation| regex::escape(abbreviation))
+        .collect();
+    // without capture groups, the start of the only group is the break position
+    let before_break = format!("{}(?:{})", WORD_START, alternatives.join("|"));
+
+    Rule::new(Some(before_break), Some(after_break), false).map(Some)
+}
+
+impl SRX {
+    /// Adds a rule which prevents breaks after any of the abbreviations (e. g. `Mr.`, `z.B.`, `approx.`) to the
+    /// rules of the [Language], so abbreviations can be maintained as plain word lists instead of regexes.
+    ///
+    /// Abbreviations are escaped and match as written (usually including their final period) when they start
+    /// a word and are followed by whitespace. The rule is placed before all other rules of the language so that
+    /// it takes precedence over rules breaking after periods. If there is no `<languagerule>` for the language,
+    /// one is created; it only applies once a `<languagemap>` refers to it.
+    ///
+    /// # Errors
+    ///
+    /// If the generated regex exceeds the size limit of the `regex` crate.
+    #[cfg_attr(docsrs, doc(cfg(feature = "from_xml")))]
+    pub fn with_abbreviations<I, S>(
+        self,
+        language: Language,
+        abbreviations: I,
+    ) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let rules = abbreviation_rule(abbreviations, "\\s")?;
+        Ok(self.with_leading_rules(language, rules))
+    }
+
+    /// Adds rules which prevent breaks after the prefixes of a Moses `nonbreaking_prefixes` file (as used by
+    /// `split-sentences.perl`) to the rules of the [Language], see [SRX::with_abbreviations].
+    ///
+    /// Every line of the file is a prefix which is followed by a period in the text, lines starting with `#` and
+    /// empty lines are ignored. Prefixes marked with `#NUMERIC_ONLY#` (e. g. `No #NUMERIC_ONLY#`) only prevent
+    /// breaks if the next word starts with a digit.
+    ///
+    /// # Errors
+    ///
+    /// If the generated regexes exceed the size limit of the `regex` crate.
+    #[cfg_attr(docsrs, doc(cfg(feature = "from_xml")))]
+    pub fn with_moses_prefixes(self, language: Language, content: &str) -> Result<Self, Error> {
+        let mut prefixes = Vec::new();
+        let mut numeric_prefixes = Vec::new();
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once('#') {
+                Some((prefix, marker)) if marker.trim_end_matches('#') == "NUMERIC_ONLY" => {
+                    numeric_prefixes.push(format!("{}.", prefix.trim()))
+                }
+                // other comments after the prefix
+                Some((prefix, _)) => prefixes.push(format!("{}.", prefix.trim())),
+                None => prefixes.push(format!("{}.", line)),
+            }
+        }
+
+        let rules = abbreviation_rule(prefixes, "\\s")?
+            .into_iter()
+            .chain(abbreviation_rule(numeric_prefixes, "\\s+\\p{N}")?);
+        Ok(self.with_leading_rules(language, rules))
+    }
+
+    /// Inserts the rules before all other rules of the language, creating its rules if there are none.
+    fn with_leading_rules<I: IntoIterator<Item = Rule>>(
+        mut self,
+        language: Language,
+        rules: I,
+    ) -> Self {
+        let rules: Vec<Rule> = rules.into_iter().collect();
+        if rules.is_empty() {
+            return self;
+        }
+
+        self.errors.entry(language.clone()).or_default();
+        self.rules.entry(language).or_default().splice(0..0, rules);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+
+    #[test]
+    fn abbreviations_prevent_breaks() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .with_abbreviations(
+                    Language("Default".into()),
+                    vec!["z.B.", "approx.", "Dr.", ""],
+                )
+                .expect("abbreviations are valid");
+        let rules = srx.language_rules("de");
+
+        assert_eq!(
+            rules.rule_source(0).and_then(|(before, _)| before),
+            Some("(?:^|[^\\p{L}\\p{N}])(?:approx\\.|z\\.B\\.|Dr\\.)")
+        );
+        assert_eq!(
+            rules
+                .split("Dr. Weber kommt z.B. morgen. Es sind approx. 5. Gut.")
+                .collect::<Vec<_>>(),
+            vec![
+                "Dr. Weber kommt z.B. morgen.",
+                " Es sind approx. 5.",
+                " Gut."
+            ]
+        );
+        // abbreviations only match at the start of a word
+        assert_eq!(
+            rules.split("Ein Gedr. Text.").collect::<Vec<_>>(),
+            vec!["Ein Gedr.", " Text."]
+        );
+        assert!(abbreviation_rule(Vec::<&str>::new(), "\\s")
+            .expect("no abbreviations are valid")
+            .is_none());
+    }
+
+    #[test]
+    fn moses_prefixes_prevent_breaks() {
+        let content = "#Anything in this file, followed by a period, does NOT indicate an end-of-sentence marker.\n\
+                       \n\
+                       Mr\n\
+                       Prof # title\n\
+                       #Numbers only. These should only induce breaks when followed by a numeric sequence\n\
+                       No #NUMERIC_ONLY#\n";
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .with_moses_prefixes(Language("Default".into()), content)
+                .expect("prefixes are valid");
+        let rules = srx.language_rules("de");
+
+        assert_eq!(
+            rules
+                .split("Prof. Smith owns No. 5. No. Really.")
+                .collect::<Vec<_>>(),
+            vec!["Prof. Smith owns No. 5.", " No.", " Really."]
+        );
+        assert_eq!(
+            srx.rules_for(&Language("Default".into()))
+                .map(|rules| rules.len()),
+            Some(5)
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::{HashSet, VecDeque};
+
+use regex_automata::{
+    dfa::{dense, Automaton, StartKind},
+    nfa::thompson,
+    util::{start, syntax},
+    Anchored, MatchKind,
+};
+use regex_syntax::hir::{Dot, Hir, HirKind, Look, LookSet, Repetition};
+
+use super::{Rule, Rules};
+
+/// Maximum number of state pairs explored to decide whether one pattern contains another.
+const MAX_STATES: usize = 100_000;
+
+/// A rule which can never decide because an earlier rule always decides at the same positions.
+/// Found by [Rules::shadowed_rules].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shadowing {
+    /// The index of the shadowed rule.
+    pub rule: usize,
+    /// The index of the first earlier rule shadowing it.
+    pub by: usize,
+    /// Whether the rules have a different break decision, i. e. the author likely expected the shadowed rule to apply.
+    pub conflicting: bool,
+}
+
+type Dfa = dense::DFA<Vec<u32>>;
+
+/// Compiles the pattern to a DFA matching exactly the strings of the pattern.
+/// `None` if the pattern is not supported by DFAs (e. g. Unicode word boundaries).
+fn exact_dfa(hir: &Hir) -> Option<Dfa> {
+    let nfa = thompson::Compiler::new().build_from_hir(hir).ok()?;
+    dense::Builder::new()
+        .configure(
+            dense::Config::new()
+                .match_kind(MatchKind::All)
+                .start_kind(StartKind::Anchored),
+        )
+        .build_from_nfa(&nfa)
+        .ok()
+}
+
+/// Matches any string.
+fn any() -> Hir {
+    Hir::repetition(Repetition {
+        min: 0,
+        max: None,
+        greedy: true,
+        sub: Box::new(Hir::dot(Dot::AnyChar)),
+    })
+}
+
+/// The patterns of a rule as DFAs matching the text before and after the positions the rule decides
+/// (i. e. `.*before_break` from the start of the text and `after_break.*` up to the end of the text).
+struct RuleDfas {
+    before_context: Dfa,
+    after_context: Dfa,
+    // whether an assertion looks across the break (e. g. `$` in `before_break` or `\b` in `after_break`),
+    // which the DFAs can not evaluate since each of them only sees one side of it
+    looks_across: bool,
+}
+
+impl RuleDfas {
+    fn new(rule: &Rule) -> Option<Self> {
+        let hir = syntax::parse(rule.regex.as_str()).ok()?;
+        // rules are compiled to `before_break(after_break)`
+        let (before, after) = match hir.kind() {
+            HirKind::Capture(capture) => (Hir::empty(), (*capture.sub).clone()),
+            HirKind::Concat(hirs) => match hirs.split_last() {
+                Some((last, before)) => match last.kind() {
+                    HirKind::Capture(capture) => {
+                        (Hir::concat(before.to_vec()), (*capture.sub).clone())
+                    }
+                    _ => return None,
+                },
+                None => return None,
+            },
+            _ => return None,
+        };
+
+        // assertions at the start of `before_break` only look back and at the end of `after_break` only look ahead
+        let behind = LookSet::empty()
+            .insert(Look::Start)
+            .insert(Look::StartLF)
+            .insert(Look::StartCRLF);
+        let ahead = LookSet::empty()
+            .insert(Look::End)
+            .insert(Look::EndLF)
+            .insert(Look::EndCRLF);
+        let looks_across = !before.properties().look_set().subtract(behind).is_empty()
+            || !after.properties().look_set().subtract(ahead).is_empty();
+
+        Some(RuleDfas {
+            before_context: exact_dfa(&Hir::concat(vec![any(), before]))?,
+            after_context: exact_dfa(&Hir::concat(vec![after, any()]))?,
+            looks_across,
+        })
+    }
+}
+
+/// Whether every string matched by `a` is matched by `b`. `None` if that could not be decided within [MAX_STATES].
+fn is_subset(a: &Dfa, b: &Dfa) -> Option<bool> {
+    let config = start::Config::new().anchored(Anchored::Yes);
+    let start = (a.start_state(&config).ok()?, b.start_state(&config).ok()?);
+
+    // one byte of each combination of byte classes of the two DFAs
+    let mut seen = HashSet::new();
+    let bytes: Vec<u8> = (0..=255u8)
+        .filter(|&byte| seen.insert((a.byte_classes().get(byte), b.byte_classes().get(byte))))
+        .collect();
+
+    let accepts = |dfa: &Dfa, state| {
+        !dfa.is_dead_state(state) && dfa.is_match_state(dfa.next_eoi_state(state))
+    };
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some((state_a, state_b)) = queue.pop_front() {
+        if accepts(a, state_a) && !accepts(b, state_b) {
+            return Some(false);
+        }
+
+        for &byte in &bytes {
+            let next = (a.next_state(state_a, byte), b.next_state(state_b, byte));
+            if !a.is_dead_state(next.0) && visited.insert(next) {
+                if visited.len() > MAX_STATES {
+                    return None;
+                }
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Some(true)
+}
+
+/// Whether the DFA matches any string at any position of a text, i. e. after any byte. `None` if that could not be
+/// decided within [MAX_STATES].
+fn matches_anything(dfa: &Dfa) -> Option<bool> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    for look_behind in std::iter::once(None).chain((0..=255u8).map(Some)) {
+        let config = start::Config::new()
+            .anchored(Anchored::Yes)
+            .look_behind(look_behind);
+        let start = dfa.start_state(&config).ok()?;
+        if visited.insert(start) {
+            queue.push_back(start);
+        }
+    }
+
+    let bytes: Vec<u8> = dfa
+        .byte_classes()
+        .representatives(..)
+        .filter_map(|unit| unit.as_u8())
+        .collect();
+
+    while let Some(state) = queue.pop_front() {
+        // matches are delayed by one byte, so a match is found in the state after the match ends
+        if dfa.is_match_state(state) || dfa.is_match_state(dfa.next_eoi_state(state)) {
+            return Some(true);
+        }
+
+        for &byte in &bytes {
+            let next = dfa.next_state(state, byte);
+            if !dfa.is_dead_state(next) && visited.insert(next) {
+                if visited.len() > MAX_STATES {
+                    return None;
+                }
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Some(false)
+}
+
+/// Whether the rule can not match any text.
+fn never_matches(rule: &Rule) -> bool {
+    let hir = match syntax::parse(rule.regex.as_str()) {
+        Ok(hir) => hir,
+        Err(_) => return false,
+    };
+    // e. g. empty character classes
+    if hir.properties().minimum_len().is_none() {
+        return true;
+    }
+
+    // e. g. contradictory anchors
+    exact_dfa(&hir).is_some_and(|dfa| matches_anything(&dfa) == Some(false))
+}
+
+impl Rules {
+    /// Finds the indices of rules which can not match any text, e. g. because of contradictory anchors such
+    /// as `a^b` or empty character classes such as `[^\s\S]`. Such rules never decide anything.
+    ///
+    /// This is a static analysis of the patterns. Rules which can not be compiled to a DFA (e. g. with Unicode
+    /// word boundaries `\b`) are only found if their pattern is trivially empty.
+    pub fn never_matching_rules(&self) -> Vec<usize> {
+        (0..self.rules.len())
+            .filter(|&rule| never_matches(&self.rules[rule]))
+            .collect()
+    }
+
+    /// Finds rules which can never decide because an earlier rule matches at every position they match at:
+    /// every `before_break` of the later rule ends with a match of the `before_break` of the earlier rule, and
+    /// every `after_break` of the later rule starts with a match of the `after_break` of the earlier rule.
+    /// This is the case e. g. for duplicate rules or when the earlier rule is strictly more general.
+    ///
+    /// Anchors are taken into account, e. g. `^A\.` only decides at the start of the text and therefore does not
+    /// shadow `A\.`. Earlier rules with an assertion at the break (e. g. `$` in `before_break`) are skipped.
+    ///
+    /// This is a static analysis of the patterns, so rules only shadowed by a combination of earlier rules are not
+    /// found. Rules which can not be compiled to a DFA (e. g. with Unicode word boundaries `\b`) are skipped.
+    pub fn shadowed_rules(&self) -> Vec<Shadowing> {
+        let dfas: Vec<Option<RuleDfas>> = self.rules.iter().map(RuleDfas::new).collect();
+
+        (0..dfas.len())
+            .filter_map(|rule| {
+                let later = dfas[rule].as_ref()?;
+
+                let by = (0..rule).find(|&earlier| {
+                    dfas[earlier].as_ref().is_some_and(|earlier| {
+                        !earlier.looks_across
+                            && is_subset(&later.after_context, &earlier.after_context) == Some(true)
+                            && is_subset(&later.before_context, &earlier.before_context)
+                                == Some(true)
+                    })
+                })?;
+
+                Some(Shadowing {
+                    rule,
+                    by,
+                    conflicting: self.rules[rule].do_break() != self.rules[by].do_break(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(rules: &[(&str, &str, bool)]) -> Rules {
+        Rules::from_rules(
+            rules
+                .iter()
+                .map(|&(before, after, do_break)| {
+                    Rule::new(Some(before), Some(after), do_break).expect("test rule is valid")
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn never_matching_rules_found() {
+        let rules = rules(&[
+            ("[\\.\\?!]", "\\s", true),
+            ("a^", "b", false),
+            ("[^\\s\\S]", "\\s", true),
+            ("\\.", "$\\s", true),
+            ("^\\s*[0-9]+\\.", "\\s", false),
+            ("\\b[A-Z]\\.", "\\s", false),
+            ("\\.", "(?m)$\\n", true),
+        ]);
+
+        assert_eq!(rules.never_matching_rules(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn shadowed_rules_found() {
+        let rules = rules(&[
+            ("[\\.\\?!]", "\\s", true),
+            ("Mr\\.", "\\s", false),
+            ("\\?", "\\s+[A-Z]", true),
+            ("\\s[a-z]+\\.", "\\s", true),
+            ("\\.", "\\s", true),
+            ("\\.", "", true),
+        ]);
+
+        assert_eq!(
+            rules.shadowed_rules(),
+            vec![
+                Shadowing {
+                    rule: 1,
+                    by: 0,
+                    conflicting: true
+                },
+                Shadowing {
+                    rule: 2,
+                    by: 0,
+                    conflicting: false
+                },
+                Shadowing {
+                    rule: 3,
+                    by: 0,
+                    conflicting: false
+                },
+                Shadowing {
+                    rule: 4,
+                    by: 0,
+                    conflicting: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn anchored_rules_only_shadow_where_they_match() {
+        let rules = rules(&[
+            ("^A\\.", "\\s", false),
+            ("A\\.", "\\s", true),
+            ("^A\\.", "\\s+", true),
+            ("x$", "", false),
+            ("x", "y", true),
+        ]);
+
+        // the anchored rule only decides at the start of the text
+        assert_eq!(
+            rules.split("x A. b").collect::<Vec<_>>(),
+            vec!["x A.", " b"]
+        );
+        assert_eq!(
+            rules.shadowed_rules(),
+            vec![Shadowing {
+                rule: 2,
+                by: 0,
+                conflicting: true
+            }]
+        );
+    }
+}
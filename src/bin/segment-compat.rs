@@ -0,0 +1,139 @@
+//! `segment-compat`: a drop-in replacement for the command line tool of [loomchild/segment](https://github.com/loomchild/segment)
+//! e. g. in Moses or Bitextor pipelines.
+//!
+//! Accepts the same options for the commonly used features:
+//!
+//! ```text
+//! segment-compat -s segment.srx -l en [-i input] [-o output] [-b begin] [-e end] [-a algorithm]
+//! ```
+//!
+//! Like `segment`, the whole input is split as one text (so line breaks are handled by the rules) and every
+//! segment is written surrounded by the begin and end strings. The algorithm is accepted for compatibility
+//! but does not change the output.
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::PathBuf,
+    process,
+    str::FromStr,
+};
+
+use clap::{Parser, ValueEnum};
+use srx::SRX;
+
+/// Algorithms of `segment`. All of them produce the same output here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Algorithm {
+    Accurate,
+    Fast,
+    Ultimatefast,
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "segment-compat",
+    version,
+    about = "Splits text into segments with SRX rules, compatible with the options of loomchild/segment."
+)]
+struct Args {
+    /// Path to the SRX file.
+    #[arg(short = 's')]
+    srx: PathBuf,
+    /// Language code used to select the rules.
+    #[arg(short = 'l')]
+    lang: String,
+    /// File to read text from. Reads from stdin if omitted.
+    #[arg(short = 'i')]
+    input: Option<PathBuf>,
+    /// File to write segments to. Writes to stdout if omitted.
+    #[arg(short = 'o')]
+    output: Option<PathBuf>,
+    /// String written before each segment. `\n`, `\t`, `\r` and `\\` are unescaped.
+    #[arg(short = 'b', default_value = "")]
+    begin: String,
+    /// String written after each segment. `\n`, `\t`, `\r` and `\\` are unescaped.
+    #[arg(short = 'e', default_value = "\\n")]
+    end: String,
+    /// Segmentation algorithm, only accepted for compatibility.
+    #[arg(short = 'a', value_enum, default_value_t = Algorithm::Accurate)]
+    algorithm: Algorithm,
+}
+
+/// Replaces the escape sequences shells do not expand in arguments with the characters they denote.
+fn unescape(string: &str) -> String {
+    let mut unescaped = String::with_capacity(string.len());
+    let mut chars = string.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let srx = SRX::from_str(&fs::read_to_string(&args.srx)?)?;
+    let rules = srx.language_rules(&args.lang);
+    let (begin, end) = (unescape(&args.begin), unescape(&args.end));
+
+    let input: Box<dyn Read> = match &args.input {
+        Some(path) => Box::new(File::open(path)?),
+        None => Box::new(io::stdin().lock()),
+    };
+    let mut output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    for segment in rules.split_reader(input) {
+        write!(output, "{}{}{}", begin, segment?, end)?;
+    }
+
+    output.flush()?;
+    Ok(())
+}
+
+fn main() {
+    let args = Args::parse();
+
+    if let Err(error) = run(&args) {
+        // the reader of the output went away, which is not an error
+        if error
+            .downcast_ref::<io::Error>()
+            .is_some_and(|error| error.kind() == io::ErrorKind::BrokenPipe)
+        {
+            return;
+        }
+
+        eprintln!("segment-compat: {}", error);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_replaced() {
+        assert_eq!(unescape("\\n"), "\n");
+        assert_eq!(unescape("<s>\\t\\\\"), "<s>\t\\");
+        assert_eq!(unescape("\\x\\"), "\\x\\");
+    }
+}
@@ -0,0 +1,117 @@
+use std::{
+    error::Error,
+    fs,
+    io::Write,
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use srx::{Segmenter, SRX};
+
+/// Timings of loading an SRX file and splitting a corpus with it.
+#[derive(Debug)]
+struct Measurement {
+    read: Duration,
+    // parsing the XML and compiling the regexes happen together
+    compile: Duration,
+    resolve: Duration,
+    // fastest of all iterations
+    split: Duration,
+    segments: usize,
+}
+
+fn measure(
+    srx_path: &Path,
+    lang: &str,
+    corpus: &str,
+    iterations: usize,
+) -> Result<Measurement, Box<dyn Error>> {
+    let start = Instant::now();
+    let content = fs::read_to_string(srx_path)?;
+    let read = start.elapsed();
+
+    let start = Instant::now();
+    let srx = SRX::from_str(&content)?;
+    let compile = start.elapsed();
+
+    let start = Instant::now();
+    let mut segmenter = Segmenter::new(srx.language_rules(lang));
+    let resolve = start.elapsed();
+
+    let mut split = Duration::MAX;
+    let mut segments = 0;
+    for _ in 0..iterations.max(1) {
+        let start = Instant::now();
+        segments = corpus
+            .lines()
+            .map(|line| segmenter.split_ranges(line).len())
+            .sum();
+        split = split.min(start.elapsed());
+    }
+
+    Ok(Measurement {
+        read,
+        compile,
+        resolve,
+        split,
+        segments,
+    })
+}
+
+/// Measures loading each SRX file and splitting every line of the corpus with its rules for the language.
+/// Writes a Markdown table with one row per SRX file.
+pub fn bench<W: Write>(
+    srx_paths: &[&Path],
+    lang: &str,
+    corpus_path: &Path,
+    iterations: usize,
+    output: &mut W,
+) -> Result<(), Box<dyn Error>> {
+    let corpus = fs::read_to_string(corpus_path)?;
+    let megabytes = corpus.len() as f64 / 1_000_000.0;
+
+    writeln!(
+        output,
+        "| SRX file | read (ms) | parse and compile (ms) | resolve rules (ms) | split (ms) | MB/s | segments | segments/s |"
+    )?;
+    writeln!(output, "|---|---|---|---|---|---|---|---|")?;
+
+    for srx_path in srx_paths {
+        let measurement = measure(srx_path, lang, &corpus, iterations)?;
+        let seconds = measurement.split.as_secs_f64();
+
+        writeln!(
+            output,
+            "| {} | {:.2} | {:.2} | {:.2} | {:.2} | {:.2} | {} | {:.0} |",
+            srx_path.display(),
+            measurement.read.as_secs_f64() * 1000.0,
+            measurement.compile.as_secs_f64() * 1000.0,
+            measurement.resolve.as_secs_f64() * 1000.0,
+            seconds * 1000.0,
+            megabytes / seconds,
+            measurement.segments,
+            measurement.segments as f64 / seconds
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments_counted() {
+        let measurement = measure(
+            Path::new("data/example.srx"),
+            "en",
+            "First sentence. Second one.\nThird line.\n",
+            2,
+        )
+        .expect("measuring succeeds");
+
+        assert_eq!(measurement.segments, 3);
+    }
+}
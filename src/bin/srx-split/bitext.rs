@@ -0,0 +1,140 @@
+use std::io::{self, BufRead, Write};
+
+use srx::Rules;
+
+use super::escape_tsv;
+
+/// Reads pairs of lines from two parallel inputs. It is an error if one has more lines than the other.
+pub fn parallel_lines<'a, S: BufRead + 'a, T: BufRead + 'a>(
+    source: S,
+    target: T,
+) -> impl Iterator<Item = io::Result<(String, String)>> + 'a {
+    let (mut source, mut target) = (source.lines(), target.lines());
+
+    std::iter::from_fn(move || match (source.next(), target.next()) {
+        (Some(source), Some(target)) => Some(source.and_then(|source| Ok((source, target?)))),
+        (None, None) => None,
+        _ => Some(Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the source and target files have a different number of lines",
+        ))),
+    })
+}
+
+/// Reads pairs of source and target text from the first two columns of TSV lines.
+pub fn tsv_lines<'a, R: BufRead + 'a>(
+    input: R,
+) -> impl Iterator<Item = io::Result<(String, String)>> + 'a {
+    input.lines().enumerate().map(|(i, line)| {
+        let line = line?;
+        let mut fields = line.split('\t');
+
+        match (fields.next(), fields.next()) {
+            (Some(source), Some(target)) => Ok((source.to_owned(), target.to_owned())),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {} has no target column", i + 1),
+            )),
+        }
+    })
+}
+
+/// Splits the source and target text of every pair with their rules and writes aligned `source<TAB>target` lines.
+/// If both are split into the same number of segments, one line is written per pair of segments. Otherwise the
+/// pair is written unsplit and a warning is written to `warnings`.
+///
+/// Returns the number of pairs and the number of pairs whose segment counts diverge.
+pub fn bitext<I, W, E>(
+    source_rules: &Rules,
+    target_rules: &Rules,
+    pairs: I,
+    output: &mut W,
+    warnings: &mut E,
+) -> io::Result<(usize, usize)>
+where
+    I: IntoIterator<Item = io::Result<(String, String)>>,
+    W: Write + ?Sized,
+    E: Write,
+{
+    let (mut source_splitter, mut target_splitter) =
+        (source_rules.splitter(), target_rules.splitter());
+    let (mut total, mut diverging) = (0, 0);
+
+    for (i, pair) in pairs.into_iter().enumerate() {
+        let (source, target) = pair?;
+        total += 1;
+
+        let source_ranges = source_splitter.split_ranges(&source).to_vec();
+        let target_ranges = target_splitter.split_ranges(&target);
+
+        if source_ranges.len() == target_ranges.len() {
+            for (source_range, target_range) in source_ranges.iter().zip(target_ranges) {
+                writeln!(
+                    output,
+                    "{}\t{}",
+                    escape_tsv(&source[source_range.clone()]),
+                    escape_tsv(&target[target_range.clone()])
+                )?;
+            }
+        } else {
+            diverging += 1;
+            writeln!(
+                warnings,
+                "srx-split: line {}: {} source and {} target segments, kept unsplit",
+                i + 1,
+                source_ranges.len(),
+                target_ranges.len()
+            )?;
+            writeln!(output, "{}\t{}", escape_tsv(&source), escape_tsv(&target))?;
+        }
+    }
+
+    Ok((total, diverging))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use srx::SRX;
+
+    use super::*;
+
+    #[test]
+    fn pairs_aligned() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let (english, french) = (srx.language_rules("en"), srx.language_rules("fr"));
+        let source = "Hello. Bye.\nOne. Two.\n";
+        let target = "Bonjour. Au revoir.\nUn, deux.\n";
+
+        let (mut output, mut warnings) = (Vec::new(), Vec::new());
+        let counts = bitext(
+            &english,
+            &french,
+            parallel_lines(source.as_bytes(), target.as_bytes()),
+            &mut output,
+            &mut warnings,
+        )
+        .expect("pairs are valid");
+
+        assert_eq!(counts, (2, 1));
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "Hello.\tBonjour.\n Bye.\t Au revoir.\nOne. Two.\tUn, deux.\n"
+        );
+        assert_eq!(
+            String::from_utf8(warnings).expect("warnings are valid UTF-8"),
+            "srx-split: line 2: 2 source and 1 target segments, kept unsplit\n"
+        );
+
+        assert!(parallel_lines("a\nb\n".as_bytes(), "a\n".as_bytes()).any(|pair| pair.is_err()));
+        assert_eq!(
+            tsv_lines("a\tb\n".as_bytes())
+                .map(|pair| pair.ok())
+                .collect::<Vec<_>>(),
+            vec![Some(("a".to_owned(), "b".to_owned()))]
+        );
+    }
+}
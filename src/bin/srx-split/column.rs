@@ -0,0 +1,124 @@
+use std::{
+    io::{self, Write},
+    ops::Range,
+};
+
+/// Parses a delimiter given on the command line, `\t` stands for a tab.
+pub fn parse_delimiter(delimiter: &str) -> Result<String, String> {
+    if delimiter.is_empty() {
+        return Err("the delimiter must not be empty".into());
+    }
+    Ok(delimiter.replace("\\t", "\t"))
+}
+
+/// A column of delimited rows which is split instead of the whole line.
+/// Fields are separated by the delimiter only, quoting is not supported.
+#[derive(Debug, Clone, Copy)]
+pub struct Column<'a> {
+    // starting at 0
+    pub index: usize,
+    pub delimiter: &'a str,
+    // if set, segments are joined with this separator into one row instead of writing one row per segment
+    pub join: Option<&'a str>,
+}
+
+impl Column<'_> {
+    /// Gets the byte range of the column in the row. It is empty at the end of the row if the row has fewer columns.
+    pub fn span(&self, row: &str) -> Range<usize> {
+        let mut start = 0;
+        for _ in 0..self.index {
+            match row[start..].find(self.delimiter) {
+                Some(i) => start += i + self.delimiter.len(),
+                None => return row.len()..row.len(),
+            }
+        }
+
+        let end = row[start..]
+            .find(self.delimiter)
+            .map_or(row.len(), |i| start + i);
+        start..end
+    }
+
+    /// Writes the row with the column replaced by each segment, or by the joined segments.
+    /// Rows without segments are written unchanged.
+    pub fn write_rows<W: Write, S: AsRef<str>>(
+        &self,
+        output: &mut W,
+        row: &str,
+        segments: &[S],
+        terminator: u8,
+    ) -> io::Result<()> {
+        let span = self.span(row);
+        let (before, after) = (&row[..span.start], &row[span.end..]);
+
+        let mut write_row = |field: &str| {
+            output.write_all(before.as_bytes())?;
+            output.write_all(field.as_bytes())?;
+            output.write_all(after.as_bytes())?;
+            output.write_all(&[terminator])
+        };
+
+        match self.join {
+            Some(separator) => {
+                let segments: Vec<_> = segments.iter().map(AsRef::as_ref).collect();
+                write_row(&segments.join(separator))
+            }
+            None if segments.is_empty() => write_row(&row[span]),
+            None => segments
+                .iter()
+                .try_for_each(|segment| write_row(segment.as_ref())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(column: Column, segments: &[&str]) -> String {
+        let mut output = Vec::new();
+        column
+            .write_rows(&mut output, "id\tOne. Two.\tmeta", segments, b'\n')
+            .expect("writing to a vector succeeds");
+        String::from_utf8(output).expect("output is valid UTF-8")
+    }
+
+    #[test]
+    fn spans_found() {
+        let column = Column {
+            index: 1,
+            delimiter: "::",
+            join: None,
+        };
+
+        assert_eq!(column.span("a::bc::d"), 3..5);
+        assert_eq!(column.span("a::bc"), 3..5);
+        assert_eq!(column.span("a"), 1..1);
+    }
+
+    #[test]
+    fn rows_written() {
+        let column = Column {
+            index: 1,
+            delimiter: "\t",
+            join: None,
+        };
+
+        assert_eq!(
+            written(column, &["One.", " Two."]),
+            "id\tOne.\tmeta\nid\t Two.\tmeta\n"
+        );
+        assert_eq!(
+            written(
+                Column {
+                    join: Some(" | "),
+                    ..column
+                },
+                &["One.", " Two."]
+            ),
+            "id\tOne. |  Two.\tmeta\n"
+        );
+        assert_eq!(written(column, &[]), "id\tOne. Two.\tmeta\n");
+        assert_eq!(parse_delimiter("\\t").as_deref(), Ok("\t"));
+    }
+}
@@ -0,0 +1,201 @@
+use std::{
+    collections::BTreeSet,
+    io::{self, Write},
+    process::{Command, Stdio},
+    thread,
+};
+
+use srx::Rules;
+
+use crate::debug::context;
+
+/// Byte offsets at which segments start, excluding the start of the text.
+type Boundaries = BTreeSet<usize>;
+
+/// Runs the reference segmenter with the text on stdin and gets its output, one segment per line.
+pub fn run_reference(command: &str, text: &str) -> io::Result<String> {
+    let mut parts = command.split_whitespace();
+    let program = parts.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the reference command is empty",
+        )
+    })?;
+
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    // write from another thread so that a segmenter writing before it read all input can not block
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = text.to_owned();
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output()?;
+    writer.join().expect("writer does not panic")?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "the reference command exited with {}",
+            output.status
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Finds the segments in the text, so segments which lost whitespace (e. g. by trimming) still have a boundary.
+/// Segments which can not be found are skipped.
+fn align<'a, I: IntoIterator<Item = &'a str>>(text: &str, segments: I) -> Boundaries {
+    let mut boundaries = Boundaries::new();
+    let mut cursor = 0;
+
+    for segment in segments {
+        let segment = segment.trim();
+        if segment.is_empty() {
+            continue;
+        }
+
+        if let Some(offset) = text[cursor..].find(segment) {
+            boundaries.insert(cursor + offset);
+            cursor += offset + segment.len();
+        }
+    }
+
+    boundaries.remove(&0);
+    boundaries
+}
+
+/// Moves boundaries past leading whitespace of the segment, so segmenters which attach whitespace to
+/// different segments still agree.
+fn normalize(text: &str, boundaries: &Boundaries) -> Boundaries {
+    boundaries
+        .iter()
+        .map(|&boundary| boundary + (text[boundary..].len() - text[boundary..].trim_start().len()))
+        .filter(|&boundary| boundary < text.len())
+        .collect()
+}
+
+fn percentage(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        100.0
+    } else {
+        part as f64 / whole as f64 * 100.0
+    }
+}
+
+/// Splits the text as one document and compares the boundaries with the segments of the reference (one per line).
+/// Writes how many boundaries agree, precision and recall of the rules with respect to the reference and up to
+/// `examples` contexts of each kind of disagreement.
+pub fn compare<W: Write>(
+    rules: &Rules,
+    text: &str,
+    reference: &str,
+    examples: usize,
+    output: &mut W,
+) -> io::Result<()> {
+    let ours = normalize(
+        text,
+        &rules
+            .split_ranges(text)
+            .into_iter()
+            .map(|range| range.start)
+            .filter(|&start| start > 0)
+            .collect(),
+    );
+    let theirs = normalize(text, &align(text, reference.lines()));
+
+    let common = ours.intersection(&theirs).count();
+    let precision = percentage(common, ours.len());
+    let recall = percentage(common, theirs.len());
+    let f1 = if precision + recall == 0.0 {
+        0.0
+    } else {
+        2.0 * precision * recall / (precision + recall)
+    };
+
+    writeln!(
+        output,
+        "boundaries: {} srx, {} reference",
+        ours.len(),
+        theirs.len()
+    )?;
+    writeln!(
+        output,
+        "agreement: {} common ({:.2}% of all boundaries)",
+        common,
+        percentage(common, ours.union(&theirs).count())
+    )?;
+    writeln!(
+        output,
+        "precision: {:.2}%, recall: {:.2}%, F1: {:.2}%",
+        precision, recall, f1
+    )?;
+
+    for (name, boundaries, other) in [("srx", &ours, &theirs), ("reference", &theirs, &ours)] {
+        let only: Vec<_> = boundaries.difference(other).collect();
+        writeln!(output, "only {}: {}", name, only.len())?;
+        for &position in only.iter().take(examples) {
+            writeln!(output, "    {} \"{}\"", position, context(text, *position))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use srx::SRX;
+
+    use super::*;
+
+    fn rules() -> Rules {
+        SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+            .expect("example file is valid")
+            .language_rules("en")
+    }
+
+    #[test]
+    fn trimmed_segments_aligned() {
+        let text = "One. Two.  Three.";
+        let boundaries = align(text, vec!["One.", "Two.", "Three."]);
+
+        assert_eq!(boundaries, Boundaries::from([5, 11]));
+        assert_eq!(normalize(text, &Boundaries::from([4, 9])), boundaries);
+    }
+
+    #[test]
+    fn agreement_reported() {
+        let text = "Ask Mr. Smith. He is well. Bye.";
+        let mut output = Vec::new();
+        compare(
+            &rules(),
+            text,
+            "Ask Mr.\nSmith. He is well.\nBye.\n",
+            1,
+            &mut output,
+        )
+        .expect("writing to a vector succeeds");
+
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "boundaries: 2 srx, 2 reference\n\
+             agreement: 1 common (33.33% of all boundaries)\n\
+             precision: 50.00%, recall: 50.00%, F1: 50.00%\n\
+             only srx: 1\n    15 \"Ask Mr. Smith. |He is well. Bye.\"\n\
+             only reference: 1\n    8 \"Ask Mr. |Smith. He is well. B\"\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reference_command_run() {
+        assert_eq!(
+            run_reference("cat", "One.\nTwo.\n").expect("cat can be run"),
+            "One.\nTwo.\n"
+        );
+        assert!(run_reference("", "").is_err());
+    }
+}
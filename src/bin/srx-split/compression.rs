@@ -0,0 +1,168 @@
+use std::{
+    ffi::OsStr,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::Path,
+};
+
+use clap::ValueEnum;
+use flate2::{read::MultiGzDecoder, write::GzEncoder};
+use serde_crate::Deserialize;
+use xz2::{read::XzDecoder, write::XzEncoder};
+
+/// Compression level used for gzip and xz, a compromise between speed and size.
+const LEVEL: u32 = 6;
+
+/// Compression formats of inputs and outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(crate = "serde_crate", rename_all = "lowercase")]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Compression {
+    /// Detects the compression from the extension of the file.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(OsStr::to_str) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            Some("xz") => Compression::Xz,
+            _ => Compression::None,
+        }
+    }
+
+    /// Wraps the reader to decompress its content.
+    pub fn reader<R: Read + Send + 'static>(
+        self,
+        reader: R,
+    ) -> io::Result<Box<dyn BufRead + Send>> {
+        Ok(match self {
+            Compression::None => Box::new(BufReader::new(reader)),
+            // concatenated members and frames are common in web crawl shards, so all of them are read
+            Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+            Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::new(reader)?)),
+            Compression::Xz => Box::new(BufReader::new(XzDecoder::new_multi_decoder(reader))),
+        })
+    }
+
+    /// Wraps the writer to compress everything written to it. [Writer::finish] must be called once done.
+    pub fn writer<W: Write>(self, writer: W) -> io::Result<Writer<W>> {
+        Ok(match self {
+            Compression::None => Writer::None(writer),
+            Compression::Gzip => {
+                Writer::Gzip(GzEncoder::new(writer, flate2::Compression::new(LEVEL)))
+            }
+            Compression::Zstd => Writer::Zstd(zstd::Encoder::new(writer, 0)?),
+            Compression::Xz => Writer::Xz(XzEncoder::new(writer, LEVEL)),
+        })
+    }
+}
+
+/// Removes the extension of a compressed file, e. g. `corpus.txt.gz` becomes `corpus.txt`.
+pub fn strip_extension(path: &Path) -> &Path {
+    match Compression::from_path(path) {
+        Compression::None => path,
+        _ => path.file_stem().map_or(path, Path::new),
+    }
+}
+
+/// A writer compressing its output. Created by [Compression::writer].
+pub enum Writer<W: Write> {
+    None(W),
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+    Xz(XzEncoder<W>),
+}
+
+impl<W: Write> Writer<W> {
+    /// Writes the end of the compressed stream and flushes the underlying writer.
+    pub fn finish(self) -> io::Result<()> {
+        let mut writer = match self {
+            Writer::None(writer) => writer,
+            Writer::Gzip(encoder) => encoder.finish()?,
+            Writer::Zstd(encoder) => encoder.finish()?,
+            Writer::Xz(encoder) => encoder.finish()?,
+        };
+        writer.flush()
+    }
+}
+
+impl<W: Write> Write for Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Writer::None(writer) => writer.write(buf),
+            Writer::Gzip(encoder) => encoder.write(buf),
+            Writer::Zstd(encoder) => encoder.write(buf),
+            Writer::Xz(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Writer::None(writer) => writer.flush(),
+            Writer::Gzip(encoder) => encoder.flush(),
+            Writer::Zstd(encoder) => encoder.flush(),
+            Writer::Xz(encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let text = "First line. Second sentence.\nSecond line.\n".repeat(100);
+
+        for compression in [
+            Compression::None,
+            Compression::Gzip,
+            Compression::Zstd,
+            Compression::Xz,
+        ] {
+            let mut writer = compression
+                .writer(Vec::new())
+                .expect("creating an encoder succeeds");
+            writer
+                .write_all(text.as_bytes())
+                .expect("writing to a vector succeeds");
+            let compressed = match writer {
+                Writer::None(buffer) => buffer,
+                Writer::Gzip(encoder) => encoder.finish().expect("finishing succeeds"),
+                Writer::Zstd(encoder) => encoder.finish().expect("finishing succeeds"),
+                Writer::Xz(encoder) => encoder.finish().expect("finishing succeeds"),
+            };
+
+            let mut decompressed = String::new();
+            compression
+                .reader(io::Cursor::new(compressed))
+                .expect("creating a decoder succeeds")
+                .read_to_string(&mut decompressed)
+                .expect("decompressing succeeds");
+            assert_eq!(decompressed, text, "{:?}", compression);
+        }
+    }
+
+    #[test]
+    fn detected_from_extension() {
+        assert_eq!(
+            Compression::from_path(Path::new("a/corpus.txt.gz")),
+            Compression::Gzip
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("corpus.zst")),
+            Compression::Zstd
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("corpus.txt")),
+            Compression::None
+        );
+        assert_eq!(
+            strip_extension(Path::new("a/corpus.txt.xz")),
+            Path::new("corpus.txt")
+        );
+    }
+}
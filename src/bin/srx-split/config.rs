@@ -0,0 +1,163 @@
+use std::{error::Error, fs, num::NonZeroUsize, path::Path};
+
+use clap::{parser::ValueSource, ArgMatches};
+use serde_crate::Deserialize;
+
+use super::{
+    compression::Compression, markup::InputFormat, newlines::Newlines, Args, CountLevel, Format,
+};
+
+/// Options read from a TOML config file. Keys are named like the command line options, e. g.
+///
+/// ```toml
+/// srx = "segment.srx"
+/// lang = "en"
+/// format = "jsonl"
+/// threads = 4
+/// show-errors = true
+/// ```
+///
+/// Options given on the command line or in environment variables take precedence over the config file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(crate = "serde_crate", rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    #[serde(alias = "srxfile")]
+    srx: Option<String>,
+    lang: Option<String>,
+    input: Option<Vec<String>>,
+    filelist: Option<String>,
+    output: Option<String>,
+    output_template: Option<String>,
+    skip_existing: Option<bool>,
+    state: Option<String>,
+    format: Option<Format>,
+    threads: Option<usize>,
+    compress: Option<Compression>,
+    show_errors: Option<bool>,
+    annotate: Option<String>,
+    sample: Option<usize>,
+    rule_stats: Option<bool>,
+    progress: Option<bool>,
+    null: Option<bool>,
+    max_length: Option<NonZeroUsize>,
+    verbose: Option<bool>,
+    trim: Option<bool>,
+    keep_blank_lines: Option<bool>,
+    count: Option<CountLevel>,
+    newlines: Option<Newlines>,
+    column: Option<NonZeroUsize>,
+    delimiter: Option<String>,
+    join: Option<String>,
+    doc_marker: Option<String>,
+    input_format: Option<InputFormat>,
+    encoding: Option<String>,
+    break_at_blocks: Option<bool>,
+    text_field: Option<String>,
+    segments_array: Option<bool>,
+}
+
+impl Config {
+    /// Reads the config file.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|error| format!("invalid config file {}: {}", path.display(), error).into())
+    }
+
+    /// Sets the options of the arguments which were neither given on the command line nor in the environment.
+    /// Paths in the config file are relative to `base`, the directory of the config file.
+    pub fn apply(self, args: &mut Args, matches: &ArgMatches, base: &Path) {
+        let unset = |id: &str| {
+            matches!(
+                matches.value_source(id),
+                None | Some(ValueSource::DefaultValue)
+            )
+        };
+
+        macro_rules! set {
+            ($field:ident, $value:expr) => {
+                if let Some(value) = self.$field {
+                    if unset(stringify!($field)) {
+                        args.$field = $value(value);
+                    }
+                }
+            };
+        }
+
+        set!(srx, |path| Some(base.join(path)));
+        set!(lang, Some);
+        set!(input, |paths: Vec<String>| paths
+            .into_iter()
+            .map(|path| base.join(path))
+            .collect());
+        set!(filelist, |path| Some(base.join(path)));
+        set!(output, |path| Some(base.join(path)));
+        set!(output_template, Some);
+        set!(skip_existing, |skip_existing| skip_existing);
+        set!(state, |path| Some(base.join(path)));
+        set!(format, |format| format);
+        set!(threads, |threads| threads);
+        set!(compress, Some);
+        set!(show_errors, |show_errors| show_errors);
+        set!(annotate, Some);
+        set!(sample, Some);
+        set!(rule_stats, |rule_stats| rule_stats);
+        set!(progress, |progress| progress);
+        set!(null, |null| null);
+        set!(max_length, Some);
+        set!(verbose, |verbose| verbose);
+        set!(trim, |trim| trim);
+        set!(keep_blank_lines, |keep_blank_lines| keep_blank_lines);
+        set!(count, Some);
+        set!(newlines, |newlines| newlines);
+        set!(column, Some);
+        set!(delimiter, |delimiter| delimiter);
+        set!(join, Some);
+        set!(doc_marker, Some);
+        set!(input_format, |input_format| input_format);
+        set!(encoding, Some);
+        set!(break_at_blocks, |break_at_blocks| break_at_blocks);
+        set!(text_field, |text_field| text_field);
+        set!(segments_array, |segments_array| segments_array);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, FromArgMatches};
+
+    use super::*;
+
+    fn parse(command_line: &[&str], config: &str) -> Args {
+        let matches = Args::command()
+            .try_get_matches_from(command_line)
+            .expect("arguments are valid");
+        let mut args = Args::from_arg_matches(&matches).expect("arguments are valid");
+        let config: Config = toml::from_str(config).expect("config is valid");
+        config.apply(&mut args, &matches, Path::new("conf"));
+        args
+    }
+
+    #[test]
+    fn command_line_takes_precedence() {
+        let config = r#"
+            srxfile = "segment.srx"
+            lang = "en"
+            format = "tsv"
+            threads = 4
+            show-errors = true
+        "#;
+        let args = parse(&["srx-split", "--lang", "de", "-t", "2"], config);
+
+        assert_eq!(args.lang.as_deref(), Some("de"));
+        assert_eq!(args.threads, 2);
+        assert_eq!(args.format, Format::Tsv);
+        assert_eq!(args.srx, Some(Path::new("conf").join("segment.srx")));
+        assert!(args.show_errors);
+        assert!(!args.progress);
+    }
+
+    #[test]
+    fn unknown_keys_rejected() {
+        assert!(toml::from_str::<Config>("language = \"en\"").is_err());
+    }
+}
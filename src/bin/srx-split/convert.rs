@@ -0,0 +1,223 @@
+use std::{
+    error::Error,
+    ffi::OsStr,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+    process,
+};
+
+use clap::ValueEnum;
+use serde_crate::{de::DeserializeOwned, Serialize};
+use srx::SRX;
+
+/// File formats of SRX files and rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FileFormat {
+    /// SRX XML, only an entire SRX file (and not the flattened rules of one language) can be written.
+    Xml,
+    Json,
+    Yaml,
+    /// The serialization of the crate with `bincode`, fastest to load.
+    Binary,
+}
+
+impl FileFormat {
+    /// Detects the format from the extension of the file.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(OsStr::to_str)? {
+            "srx" | "xml" => Some(FileFormat::Xml),
+            "json" => Some(FileFormat::Json),
+            "yaml" | "yml" => Some(FileFormat::Yaml),
+            "bin" => Some(FileFormat::Binary),
+            _ => None,
+        }
+    }
+
+    pub fn resolve(format: Option<Self>, path: &Path) -> Result<Self, Box<dyn Error>> {
+        format
+            .or_else(|| FileFormat::from_path(path))
+            .ok_or_else(|| {
+                format!(
+                    "cannot detect the format of {}, set it explicitly",
+                    path.display()
+                )
+                .into()
+            })
+    }
+}
+
+fn read<T: DeserializeOwned>(path: &Path, format: FileFormat) -> Result<T, Box<dyn Error>> {
+    let reader = BufReader::new(File::open(path)?);
+
+    Ok(match format {
+        FileFormat::Xml => unreachable!("XML is only read into an SRX"),
+        FileFormat::Json => serde_json::from_reader(reader)?,
+        FileFormat::Yaml => serde_yaml::from_reader(reader)?,
+        FileFormat::Binary => bincode::deserialize_from(reader)?,
+    })
+}
+
+/// Reads an SRX file in any format.
+pub fn read_srx(path: &Path, format: FileFormat) -> Result<SRX, Box<dyn Error>> {
+    match format {
+        FileFormat::Xml => Ok(SRX::from_reader(BufReader::new(File::open(path)?))?),
+        format => read(path, format),
+    }
+}
+
+fn serialize<T: Serialize, W: Write>(
+    value: &T,
+    writer: W,
+    format: FileFormat,
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        FileFormat::Xml => unreachable!("only an SRX is written as XML"),
+        FileFormat::Json => serde_json::to_writer_pretty(writer, value)?,
+        FileFormat::Yaml => serde_yaml::to_writer(writer, value)?,
+        FileFormat::Binary => bincode::serialize_into(writer, value)?,
+    }
+
+    Ok(())
+}
+
+/// Writes to a temporary file next to the path which then replaces the file at the path,
+/// so the file is left as it was if writing fails (e. g. if it is also one of the inputs).
+fn write<F>(path: &Path, write: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnOnce(&mut BufWriter<File>) -> Result<(), Box<dyn Error>>,
+{
+    let name = path
+        .file_name()
+        .ok_or_else(|| format!("{} is not a file", path.display()))?;
+    let mut temporary = name.to_owned();
+    temporary.push(format!(".{}.tmp", process::id()));
+    let temporary = path.with_file_name(temporary);
+
+    let result = File::create(&temporary)
+        .map_err(Box::<dyn Error>::from)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            write(&mut writer)?;
+            writer.flush()?;
+            Ok(())
+        })
+        .and_then(|()| Ok(fs::rename(&temporary, path)?));
+    if result.is_err() {
+        // the temporary file might not have been created
+        let _ = fs::remove_file(&temporary);
+    }
+    result
+}
+
+/// Writes an SRX file in any format.
+pub fn write_srx(srx: &SRX, path: &Path, format: FileFormat) -> Result<(), Box<dyn Error>> {
+    write(path, |writer| match format {
+        FileFormat::Xml => Ok(srx.write_xml(writer)?),
+        format => serialize(srx, writer, format),
+    })
+}
+
+/// Converts an SRX file between formats. Formats are detected from the file extensions unless they are given.
+///
+/// If a language code is given, the cascade is flattened: only the [srx::Rules] for the language are written.
+pub fn convert(
+    input: &Path,
+    output: &Path,
+    from: Option<FileFormat>,
+    to: Option<FileFormat>,
+    lang: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let (from, to) = (
+        FileFormat::resolve(from, input)?,
+        FileFormat::resolve(to, output)?,
+    );
+    if lang.is_some() && to == FileFormat::Xml {
+        return Err("the rules of one language can not be written as SRX XML".into());
+    }
+
+    let srx = read_srx(input, from)?;
+
+    match lang {
+        Some(lang) => write(output, |writer| {
+            serialize(&srx.language_rules(lang), writer, to)
+        }),
+        None => write_srx(&srx, output, to),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use srx::Rules;
+
+    use super::*;
+
+    #[test]
+    fn formats_roundtrip() {
+        let directory = env::temp_dir().join(format!("srx-convert-{}", process::id()));
+        fs::create_dir_all(&directory).expect("temporary directory can be created");
+        let text = "First sentence. Second one.";
+        let expected =
+            SRX::from_reader(File::open("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en");
+
+        let mut previous = Path::new("data/example.srx").to_owned();
+        for extension in ["json", "yaml", "bin"] {
+            let path = directory.join(format!("example.{}", extension));
+            convert(&previous, &path, None, None, None).expect("conversion succeeds");
+
+            let srx: SRX = read(
+                &path,
+                FileFormat::from_path(&path).expect("format is detected"),
+            )
+            .expect("converted file is valid");
+            assert_eq!(
+                srx.language_rules("en").split(text).collect::<Vec<_>>(),
+                expected.split(text).collect::<Vec<_>>()
+            );
+            previous = path;
+        }
+
+        let path = directory.join("en.json");
+        convert(&previous, &path, None, None, Some("en")).expect("conversion succeeds");
+        let rules: Rules = read(&path, FileFormat::Json).expect("converted file is valid");
+        assert_eq!(rules.len(), expected.len());
+
+        let path = directory.join("en.srx");
+        assert!(convert(&previous, &path, None, None, Some("en")).is_err());
+        assert!(!path.exists());
+        fs::remove_dir_all(&directory).expect("temporary directory can be removed");
+    }
+
+    #[test]
+    fn xml_roundtrip() {
+        let directory = env::temp_dir().join(format!("srx-convert-xml-{}", process::id()));
+        fs::create_dir_all(&directory).expect("temporary directory can be created");
+        let text = "First sentence. Second one. Mr. Smith is here.";
+        let (json, xml) = (
+            directory.join("example.json"),
+            directory.join("example.srx"),
+        );
+
+        convert(Path::new("data/example.srx"), &json, None, None, None)
+            .expect("conversion succeeds");
+        convert(&json, &xml, None, None, None).expect("conversion succeeds");
+
+        let expected = read_srx(Path::new("data/example.srx"), FileFormat::Xml)
+            .expect("example file is valid");
+        let srx = read_srx(&xml, FileFormat::Xml).expect("converted file is valid");
+        for lang in ["en", "fr", "de"] {
+            assert_eq!(
+                srx.language_rules(lang).split(text).collect::<Vec<_>>(),
+                expected
+                    .language_rules(lang)
+                    .split(text)
+                    .collect::<Vec<_>>()
+            );
+        }
+        fs::remove_dir_all(&directory).expect("temporary directory can be removed");
+    }
+}
@@ -0,0 +1,102 @@
+use std::io::{self, Write};
+
+use srx::Rules;
+
+/// Number of characters of context shown around each position.
+const CONTEXT: usize = 20;
+
+pub fn decision_name(do_break: bool) -> &'static str {
+    if do_break {
+        "break"
+    } else {
+        "no break"
+    }
+}
+
+/// Describes the rule at the index by its patterns as written in the SRX file.
+pub fn rule_source(rules: &Rules, index: usize) -> String {
+    let (before_break, after_break) = rules.rule_source(index).unwrap_or_default();
+    format!(
+        "beforebreak {:?}, afterbreak {:?}",
+        before_break.unwrap_or_default(),
+        after_break.unwrap_or_default()
+    )
+}
+
+/// Gets the text around the position with a `|` at the position, escaping line breaks.
+pub fn context(text: &str, position: usize) -> String {
+    let before: String = text[..position].chars().rev().take(CONTEXT).collect();
+    let before: String = before.chars().rev().collect();
+    let after: String = text[position..].chars().take(CONTEXT).collect();
+
+    format!("{}|{}", before, after).escape_debug().to_string()
+}
+
+/// Writes every decision of the rules on the text: the position, whether to break, the deciding rule
+/// and the rules it shadows.
+pub fn debug<W: Write>(rules: &Rules, text: &str, output: &mut W) -> io::Result<()> {
+    for decision in rules.trace(text) {
+        writeln!(
+            output,
+            "{} {}: \"{}\"",
+            decision.position,
+            decision_name(decision.do_break),
+            context(text, decision.position)
+        )?;
+        writeln!(
+            output,
+            "    rule {}: {}",
+            decision.rule,
+            rule_source(rules, decision.rule)
+        )?;
+
+        for &rule in &decision.shadowed {
+            writeln!(
+                output,
+                "    shadowed rule {} ({}): {}",
+                rule,
+                decision_name(rules.rule_breaks(rule).unwrap_or_default()),
+                rule_source(rules, rule)
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use srx::SRX;
+
+    use super::*;
+
+    #[test]
+    fn decisions_written() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en");
+
+        let mut output = Vec::new();
+        debug(&rules, "First sentence.\nSecond one.", &mut output)
+            .expect("writing to a vector succeeds");
+        let output = String::from_utf8(output).expect("output is valid UTF-8");
+
+        assert!(output.starts_with(
+            "15 break: \"First sentence.|\\nSecond one.\"\n    rule 4: beforebreak \"\", afterbreak \"\\\\n\"\n"
+        ));
+    }
+
+    #[test]
+    fn context_truncated() {
+        let text = "a".repeat(30) + "ä" + &"b".repeat(30);
+        let position = 30;
+
+        assert_eq!(
+            context(&text, position),
+            format!("{}|ä{}", "a".repeat(20), "b".repeat(19))
+        );
+    }
+}
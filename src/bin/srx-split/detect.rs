@@ -0,0 +1,53 @@
+use std::io::{self, BufRead, Read};
+
+use srx::{detect_language, DETECTION_SAMPLE_SIZE};
+
+/// Detects the language of a document from its first [DETECTION_SAMPLE_SIZE] bytes.
+/// Gets the language code and a reader which still yields the entire document.
+pub fn detect_document(
+    mut input: Box<dyn BufRead + Send>,
+) -> io::Result<(&'static str, Box<dyn BufRead + Send>)> {
+    let mut sample = Vec::with_capacity(DETECTION_SAMPLE_SIZE);
+    (&mut input)
+        .take(DETECTION_SAMPLE_SIZE as u64)
+        .read_to_end(&mut sample)?;
+
+    // the sample may end in the middle of a character
+    let lang = detect_language(&String::from_utf8_lossy(&sample));
+    Ok((lang, Box::new(io::Cursor::new(sample).chain(input))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_kept() {
+        let text = "Ceci est une phrase en français.\n".repeat(500);
+        let (lang, mut input) =
+            detect_document(Box::new(io::Cursor::new(text.clone().into_bytes())))
+                .expect("reading from memory succeeds");
+
+        let mut read = String::new();
+        input
+            .read_to_string(&mut read)
+            .expect("reading from memory succeeds");
+        assert_eq!(lang, "fr");
+        assert_eq!(read, text);
+    }
+
+    #[test]
+    fn single_line_sampled() {
+        let text = "Ceci est une phrase en français. ".repeat(500);
+        let (lang, mut input) =
+            detect_document(Box::new(io::Cursor::new(text.clone().into_bytes())))
+                .expect("reading from memory succeeds");
+
+        let mut read = String::new();
+        input
+            .read_to_string(&mut read)
+            .expect("reading from memory succeeds");
+        assert_eq!(lang, "fr");
+        assert_eq!(read, text);
+    }
+}
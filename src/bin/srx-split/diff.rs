@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeSet,
+    io::{self, BufRead, Write},
+    ops::Range,
+};
+
+use srx::{Rules, SRX};
+
+/// Marker between segments when showing how a line is segmented.
+const MARKER: &str = "‖";
+
+/// A step turning one sequence into another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    // index in the old and the new sequence
+    Keep(usize, usize),
+    Remove(usize),
+    Add(usize),
+}
+
+/// Computes the shortest edit script between the sequences via their longest common subsequence.
+fn edits<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    // `lengths[i][j]` is the length of the longest common subsequence of `old[i..]` and `new[j..]`
+    let mut lengths = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push(Edit::Keep(i, j));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            edits.push(Edit::Remove(i));
+            i += 1;
+        } else {
+            edits.push(Edit::Add(j));
+            j += 1;
+        }
+    }
+    edits
+}
+
+fn break_label(do_break: bool) -> &'static str {
+    if do_break {
+        "break"
+    } else {
+        "no break"
+    }
+}
+
+/// Writes the differences between the rules of two SRX files: languages which were added or removed,
+/// rules which were added, removed or changed per language, and changes of the `<languagemap>`.
+/// Rules are identified by their pattern, rules with the same pattern but a different `break` are changed.
+/// Nothing is written if the rules are the same.
+pub fn diff<W: Write>(old: &SRX, new: &SRX, output: &mut W) -> io::Result<()> {
+    let languages: BTreeSet<_> = old.languages().into_iter().chain(new.languages()).collect();
+
+    for language in languages {
+        let (old_rules, new_rules): (Vec<_>, Vec<_>) =
+            match (old.rule_patterns(language), new.rule_patterns(language)) {
+                (Some(old_rules), Some(new_rules)) => (old_rules.collect(), new_rules.collect()),
+                (Some(old_rules), None) => {
+                    writeln!(
+                        output,
+                        "- language {} ({} rules)",
+                        language.0,
+                        old_rules.count()
+                    )?;
+                    continue;
+                }
+                (None, Some(new_rules)) => {
+                    writeln!(
+                        output,
+                        "+ language {} ({} rules)",
+                        language.0,
+                        new_rules.count()
+                    )?;
+                    continue;
+                }
+                (None, None) => unreachable!("language has rules in one of the files"),
+            };
+
+        let old_patterns: Vec<_> = old_rules.iter().map(|(pattern, _)| pattern).collect();
+        let new_patterns: Vec<_> = new_rules.iter().map(|(pattern, _)| pattern).collect();
+        let mut lines = Vec::new();
+        for edit in edits(&old_patterns, &new_patterns) {
+            match edit {
+                Edit::Keep(i, j) if old_rules[i].1 != new_rules[j].1 => lines.push(format!(
+                    "  ~ rule {} ({} -> {}): {}",
+                    j,
+                    break_label(old_rules[i].1),
+                    break_label(new_rules[j].1),
+                    new_rules[j].0
+                )),
+                Edit::Keep(..) => {}
+                Edit::Remove(i) => lines.push(format!(
+                    "  - rule {} ({}): {}",
+                    i,
+                    break_label(old_rules[i].1),
+                    old_rules[i].0
+                )),
+                Edit::Add(j) => lines.push(format!(
+                    "  + rule {} ({}): {}",
+                    j,
+                    break_label(new_rules[j].1),
+                    new_rules[j].0
+                )),
+            }
+        }
+
+        if !lines.is_empty() {
+            writeln!(output, "language {}", language.0)?;
+            for line in lines {
+                writeln!(output, "{}", line)?;
+            }
+        }
+    }
+
+    let old_map: Vec<_> = old.language_patterns().collect();
+    let new_map: Vec<_> = new.language_patterns().collect();
+    let map_edits: Vec<_> = edits(&old_map, &new_map)
+        .into_iter()
+        .filter(|edit| !matches!(edit, Edit::Keep(..)))
+        .collect();
+    if !map_edits.is_empty() {
+        writeln!(output, "map")?;
+        for edit in map_edits {
+            match edit {
+                Edit::Remove(i) => writeln!(output, "  - {} -> {}", old_map[i].0, old_map[i].1 .0)?,
+                Edit::Add(j) => writeln!(output, "  + {} -> {}", new_map[j].0, new_map[j].1 .0)?,
+                Edit::Keep(..) => unreachable!("kept entries are filtered"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits every line of the corpus with the old and the new rules and writes the lines which are segmented
+/// differently, with `‖` between the segments, followed by the number of changed lines.
+pub fn diff_corpus<R: BufRead, W: Write>(
+    old: &Rules,
+    new: &Rules,
+    corpus: R,
+    output: &mut W,
+) -> io::Result<()> {
+    let (mut old_splitter, mut new_splitter) = (old.splitter(), new.splitter());
+    let (mut total, mut changed) = (0, 0);
+
+    for (number, line) in corpus.lines().enumerate() {
+        let line = line?;
+        total += 1;
+
+        let old_ranges = old_splitter.split_ranges(&line).to_vec();
+        let new_ranges = new_splitter.split_ranges(&line);
+        if old_ranges == new_ranges {
+            continue;
+        }
+
+        changed += 1;
+        let annotated = |ranges: &[Range<usize>]| {
+            ranges
+                .iter()
+                .map(|range| &line[range.clone()])
+                .collect::<Vec<_>>()
+                .join(MARKER)
+        };
+        writeln!(output, "line {}", number + 1)?;
+        writeln!(output, "  - {}", annotated(&old_ranges))?;
+        writeln!(output, "  + {}", annotated(new_ranges))?;
+    }
+
+    writeln!(
+        output,
+        "{} of {} lines segmented differently",
+        changed, total
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+
+    fn written(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+        let mut output = Vec::new();
+        write(&mut output).expect("writing to a vector succeeds");
+        String::from_utf8(output).expect("output is valid UTF-8")
+    }
+
+    #[test]
+    fn shortest_edits() {
+        assert_eq!(
+            edits(&["a", "b", "c"], &["a", "c", "d"]),
+            vec![
+                Edit::Keep(0, 0),
+                Edit::Remove(1),
+                Edit::Keep(2, 1),
+                Edit::Add(2)
+            ]
+        );
+    }
+
+    #[test]
+    fn changes_written() {
+        let content = fs::read_to_string("data/example.srx").expect("example file exists");
+        let old = SRX::from_str(&content).expect("example file is valid");
+        let new = SRX::from_str(
+            &content
+                .replacen(
+                    r#"<rule break="no">
+                    <beforebreak>\sMr\.</beforebreak>"#,
+                    r#"<rule break="yes">
+                    <beforebreak>\sMr\.</beforebreak>"#,
+                    1,
+                )
+                .replacen(
+                    r#"<languagemap languagepattern="[Ff][Rr].*" languagerulename="French"/>"#,
+                    "",
+                    1,
+                ),
+        )
+        .expect("changed file is valid");
+
+        assert_eq!(written(|output| diff(&old, &old, output)), "");
+        assert_eq!(
+            written(|output| diff(&old, &new, output)),
+            "language English\n  ~ rule 1 (no break -> break): \\sMr\\.(\\s)\nmap\n  - [Ff][Rr].* -> French\n"
+        );
+        assert_eq!(
+            written(|output| diff_corpus(
+                &old.language_rules("en"),
+                &new.language_rules("en"),
+                "Ask Mr. Smith.\nNo change.\n".as_bytes(),
+                output
+            )),
+            "line 1\n  - Ask Mr. Smith.\n  + Ask Mr.‖ Smith.\n1 of 2 lines segmented differently\n"
+        );
+    }
+}
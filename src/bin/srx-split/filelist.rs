@@ -0,0 +1,62 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// An input file listed in a file list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: PathBuf,
+    /// Language code of the file, overriding `--lang`.
+    pub lang: Option<String>,
+}
+
+impl From<PathBuf> for Entry {
+    fn from(path: PathBuf) -> Self {
+        Entry { path, lang: None }
+    }
+}
+
+/// Parses a file list: every line is a path, optionally followed by a tab and the language code of the file.
+/// Empty lines and lines starting with `#` are ignored.
+pub fn parse(content: &str) -> Vec<Entry> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| match line.split_once('\t') {
+            Some((path, lang)) if !lang.trim().is_empty() => Entry {
+                path: PathBuf::from(path),
+                lang: Some(lang.trim().to_owned()),
+            },
+            Some((path, _)) => PathBuf::from(path).into(),
+            None => PathBuf::from(line).into(),
+        })
+        .collect()
+}
+
+/// Reads the entries of the file list at the path, see [parse].
+pub fn read(path: &Path) -> io::Result<Vec<Entry>> {
+    fs::read_to_string(path).map(|content| parse(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_parsed() {
+        let content = "# crawl 2024\ncorpus/a.txt\ncorpus/b.txt.gz\tde\n\ncorpus/c d.txt\t\n";
+
+        assert_eq!(
+            parse(content),
+            vec![
+                PathBuf::from("corpus/a.txt").into(),
+                Entry {
+                    path: PathBuf::from("corpus/b.txt.gz"),
+                    lang: Some("de".into())
+                },
+                PathBuf::from("corpus/c d.txt").into(),
+            ]
+        );
+    }
+}
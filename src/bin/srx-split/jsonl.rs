@@ -0,0 +1,133 @@
+use std::io::{self, BufRead, Write};
+
+use serde_json::{Map, Value};
+use srx::{Pipeline, Rules};
+
+/// How the segments of JSON records are written.
+#[derive(Debug, Clone, Copy)]
+pub struct RecordOptions<'a> {
+    /// The field of the records which is split.
+    pub text_field: &'a str,
+    /// Write every record once with a `segments` array added instead of one record per segment.
+    pub segments_array: bool,
+    pub trim: bool,
+    // written after every record
+    pub terminator: u8,
+}
+
+fn invalid(line: usize, message: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid JSON record on line {}: {}", line, message),
+    )
+}
+
+/// Splits the text field of every JSON record of the input (one object per line) and writes the segments.
+///
+/// By default, a copy of the record is written for every segment, with the text field replaced by the segment
+/// and `start` and `end` set to the byte offsets of the segment in the field. With `segments_array`, every record
+/// is written once with the segments added as a `segments` array. Other fields are kept as they are, empty lines
+/// are skipped. Returns the number of segments.
+pub fn split_records<R: BufRead, W: Write>(
+    rules: &Rules,
+    input: R,
+    options: RecordOptions,
+    output: &mut W,
+) -> io::Result<usize> {
+    let pipeline = if options.trim {
+        Pipeline::new().trim()
+    } else {
+        Pipeline::new()
+    };
+    let mut count = 0;
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut record: Map<String, Value> =
+            serde_json::from_str(&line).map_err(|error| invalid(i + 1, error))?;
+        let text = match record.get(options.text_field) {
+            Some(Value::String(text)) => text.clone(),
+            _ => {
+                return Err(invalid(
+                    i + 1,
+                    format!("no string field {:?}", options.text_field),
+                ))
+            }
+        };
+        let ranges = pipeline.split_ranges(rules, &text);
+        count += ranges.len();
+
+        if options.segments_array {
+            let segments = ranges
+                .into_iter()
+                .map(|range| Value::String(text[range].to_owned()))
+                .collect();
+            record.insert("segments".into(), Value::Array(segments));
+            serde_json::to_writer(&mut *output, &record)?;
+            output.write_all(&[options.terminator])?;
+            continue;
+        }
+
+        for range in ranges {
+            let mut segment = record.clone();
+            segment.insert(
+                options.text_field.into(),
+                Value::String(text[range.clone()].to_owned()),
+            );
+            segment.insert("start".into(), range.start.into());
+            segment.insert("end".into(), range.end.into());
+            serde_json::to_writer(&mut *output, &segment)?;
+            output.write_all(&[options.terminator])?;
+        }
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use srx::SRX;
+
+    use super::*;
+
+    fn split(input: &str, segments_array: bool) -> io::Result<String> {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en");
+        let options = RecordOptions {
+            text_field: "content",
+            segments_array,
+            trim: true,
+            terminator: b'\n',
+        };
+
+        let mut output = Vec::new();
+        split_records(&rules, input.as_bytes(), options, &mut output)?;
+        Ok(String::from_utf8(output).expect("output is valid UTF-8"))
+    }
+
+    #[test]
+    fn records_split() -> io::Result<()> {
+        let input = "{\"url\":\"https://example.com\",\"content\":\"Hello. Bye.\"}\n\n";
+
+        assert_eq!(
+            split(input, false)?,
+            "{\"url\":\"https://example.com\",\"content\":\"Hello.\",\"start\":0,\"end\":6}\n\
+             {\"url\":\"https://example.com\",\"content\":\"Bye.\",\"start\":7,\"end\":11}\n"
+        );
+        assert_eq!(
+            split(input, true)?,
+            "{\"url\":\"https://example.com\",\"content\":\"Hello. Bye.\",\"segments\":[\"Hello.\",\"Bye.\"]}\n"
+        );
+        assert!(split("{\"text\":\"Hello.\"}", false).is_err());
+        assert!(split("not json", false).is_err());
+        Ok(())
+    }
+}
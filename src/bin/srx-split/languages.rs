@@ -0,0 +1,50 @@
+use std::io::{self, Write};
+
+use srx::SRX;
+
+/// Writes one line per language with rules: its name, the patterns mapping to it and its number of rules,
+/// separated by tabs. Multiple patterns are separated by spaces.
+pub fn list_languages<W: Write>(srx: &SRX, output: &mut W) -> io::Result<()> {
+    writeln!(output, "language\tpatterns\trules")?;
+
+    for language in srx.languages() {
+        let patterns: Vec<_> = srx
+            .language_patterns()
+            .filter(|(_, mapped)| *mapped == language)
+            .map(|(pattern, _)| pattern)
+            .collect();
+
+        writeln!(
+            output,
+            "{}\t{}\t{}",
+            language.0,
+            patterns.join(" "),
+            srx.rule_count(language).unwrap_or(0)
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+
+    #[test]
+    fn languages_listed() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+
+        let mut output = Vec::new();
+        list_languages(&srx, &mut output).expect("writing to a vector succeeds");
+        let output = String::from_utf8(output).expect("output is valid UTF-8");
+
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].starts_with("Default\t.*\t"));
+        assert!(lines[2].starts_with("English\t[Ee][Nn].*\t"));
+    }
+}
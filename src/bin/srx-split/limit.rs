@@ -0,0 +1,64 @@
+use std::{num::NonZeroUsize, ops::Range};
+
+/// Pushes the range of the text to `ranges`, split into pieces of at most `max_length` characters.
+/// Pieces are split after the last whitespace within the limit, or at the limit if there is none.
+/// Returns whether the range had to be split.
+pub fn limit_length(
+    text: &str,
+    range: Range<usize>,
+    max_length: NonZeroUsize,
+    ranges: &mut Vec<Range<usize>>,
+) -> bool {
+    let mut start = range.start;
+    let mut forced = false;
+
+    // byte index after the first `max_length` characters, `None` if the rest fits
+    while let Some((limit, _)) = text[start..range.end].char_indices().nth(max_length.get()) {
+        let limit = start + limit;
+        let end = text[start..limit]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(limit, |(i, c)| start + i + c.len_utf8());
+
+        ranges.push(start..end);
+        start = end;
+        forced = true;
+    }
+
+    ranges.push(start..range.end);
+    forced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limited(text: &str, max_length: usize) -> (Vec<&str>, bool) {
+        let mut ranges = Vec::new();
+        let forced = limit_length(
+            text,
+            0..text.len(),
+            NonZeroUsize::new(max_length).expect("limit is not zero"),
+            &mut ranges,
+        );
+        (
+            ranges.into_iter().map(|range| &text[range]).collect(),
+            forced,
+        )
+    }
+
+    #[test]
+    fn split_at_whitespace() {
+        assert_eq!(limited("short one", 20), (vec!["short one"], false));
+        assert_eq!(
+            limited("a rather long segment", 10),
+            (vec!["a rather ", "long ", "segment"], true)
+        );
+    }
+
+    #[test]
+    fn split_at_limit_without_whitespace() {
+        assert_eq!(limited("äöüäöü xy", 4), (vec!["äöüä", "öü ", "xy"], true));
+    }
+}
@@ -0,0 +1,1889 @@
+//! `srx-split`: splits text into segments with the rules from an SRX file.
+//!
+//! Reads from stdin and writes to stdout unless `--input` or `--output` are given, so it composes with pipelines:
+//!
+//! ```text
+//! cat corpus.txt | srx-split -l en -s segment.srx > segments.txt
+//! ```
+//!
+//! Every line of the input is split separately. With `--format plain` (the default) each segment is written on its own line,
+//! with `--format jsonl` each segment is written as a JSON object with its byte offsets in the input, e. g.
+//!
+//! ```text
+//! {"text":"Hello. ","start":0,"end":7,"line":1,"file":"corpus.txt"}
+//! ```
+//!
+//! with `--format tsv` each segment is written as `doc_id<TAB>sent_id<TAB>text`, and with `--format conllu` each
+//! segment is written as a CoNLL-U sentence (see [srx::conllu]).
+//!
+//! Inputs and outputs ending in `.gz`, `.zst` or `.xz` are decompressed and compressed transparently,
+//! `--compress` sets the compression of the output explicitly (e. g. when writing to stdout).
+//!
+//! With `--threads`, lines are split in parallel by a pool of workers sharing the rules.
+//!
+//! With `--filelist manifest.txt`, input paths are read from a file instead (one per line, optionally followed by
+//! a tab and the language code of the file, see [filelist::parse]), so huge batches do not hit argument length limits.
+//!
+//! Every input file is a document. Its ID is the file name without extension, or the number of the document
+//! (starting at 1) if it has no file name (i. e. when reading from stdin).
+//!
+//! With `--input-format html`, the text of HTML documents is extracted (see [markup::extract_html]) and split,
+//! offsets in the output refer to the original document. With `--break-at-blocks`, block elements like `<p>` or `<li>`
+//! always end a segment.
+//!
+//! With `--input-format markdown`, paragraphs, headings and list items are split separately and code is skipped
+//! (see [markup::extract_markdown]).
+//!
+//! With `--input-format jsonl`, every line of the input is a JSON object whose `--text-field` (`text` by default) is
+//! split. A copy of the record is written for every segment, or the record is written once with a `segments` array
+//! added with `--segments-array`; other fields are kept (see [jsonl::split_records]).
+//!
+//! With `--input-format warc` (and the `warc` feature), the text records of WARC web archives (e. g. the `conversion`
+//! records of extracted text) are split, each as a document identified by its target URI (see [warc::text_records]).
+//!
+//! With `--annotate`, the input is echoed with a marker (`‖` by default) at every break instead, which helps
+//! to check the rules in context.
+//!
+//! With `--lang auto` (and the `language-detection` feature), the language of each document is detected from its start
+//! and the rules are resolved via the `<languagemap>`.
+//!
+//! `--max-length` splits segments which are longer than the limit at whitespace, with `--verbose` every such
+//! segment is reported on stderr.
+//!
+//! `--trim` strips whitespace from the start and end of every segment and drops segments which are empty afterwards.
+//!
+//! In the plain format, `--keep-blank-lines` writes blank lines of the input as they are, so paragraph and document
+//! boundaries survive, and lines matching `--passthrough REGEX` (e. g. `^<doc ` for document markers) are written
+//! as they are instead of being split.
+//!
+//! With `--encoding` (and the `encoding` feature), inputs in other encodings than UTF-8 (e. g. `utf-16`, `latin1` or
+//! `windows-1252`) are transcoded before splitting. With `--encoding auto` (and the `encoding-detection` feature),
+//! the encoding of every input is guessed from its start, with `--verbose` the detected encoding is reported on stderr.
+//! A byte order mark always takes precedence.
+//!
+//! `--newlines` sets how line breaks besides `\n` (`\r`, U+0085, U+2028 and U+2029) are handled: `keep` leaves
+//! them in the segments, `normalize` writes them as spaces (and line endings as `\n`) and `break` ends lines at them.
+//! `\r\n` line endings are never part of segments.
+//!
+//! With `--column N`, only the `N`th field (starting at 1) of rows separated by `--delimiter` (a tab by default) is split.
+//! In the plain format, the row is repeated for every segment with the field replaced by the segment, or written once
+//! with the segments joined by the separator given with `--join`. Offsets in the other formats refer to the whole line.
+//!
+//! `--doc-marker` writes a separator line between documents (an empty line by default, `{id}` in the marker is
+//! replaced by the ID of the following document), so the documents can be told apart in one output stream.
+//! With `--format jsonl`, a record like
+//!
+//! ```text
+//! {"document":"corpus","file":"corpus.txt"}
+//! ```
+//!
+//! is written before the segments of every document instead.
+//!
+//! With `--output-template`, every input file is split into its own output file, whose path is derived from the
+//! input path, e. g. `--output-template '{dir}/{stem}.sents{ext}'` writes `corpus/news.txt` to `corpus/news.sents.txt`
+//! (see [template::output_path]). Outputs are written to a `.partial` file which is renamed once it is complete.
+//! With `--skip-existing`, inputs whose outputs already exist are skipped, so a batch job can be resumed after a crash.
+//! `--state FILE` records every completed input, outputs of inputs which are not recorded are then written again.
+//!
+//! `--count` only writes the number of segments per document (`--count lines`: per line) followed by the total.
+//!
+//! With `-0`/`--null`, segments are terminated by `\0` instead of `\n`, e. g. for `xargs -0`.
+//!
+//! `--sample N` only splits the first `N` lines (of all documents together) and writes the segments to stdout,
+//! to preview the effect of a change of the rules before splitting a whole corpus.
+//!
+//! `--rule-stats` reports on stderr how many boundaries each rule produced or suppressed, sorted by count,
+//! to see which rules do the work on a corpus.
+//!
+//! `--progress` shows how much of the input has been split on stderr.
+//!
+//! With the `default-rules` feature, `--srx` can be omitted to use the embedded `segment.srx` of LanguageTool,
+//! which is reported on stderr.
+//!
+//! Rules with regexes which are not supported are skipped when loading, `--show-errors` lists them.
+//!
+//! Options can also be read from a TOML config file given with `--config` (see [config::Config]), and
+//! `SRX_FILE`, `SRX_LANG`, `SRX_FORMAT`, `SRX_THREADS` and `SRX_CONFIG` set the corresponding options from the environment.
+//! Options given on the command line take precedence over the environment, which takes precedence over the config file.
+//!
+//! Subcommands inspect SRX files instead of splitting text:
+//!
+//! - `list-languages --srxfile segment.srx`: lists the language rules with their map patterns and rule counts.
+//! - `convert segment.srx segment.json`: converts between SRX XML, JSON, YAML and the binary serialization,
+//!   with `--lang` only the flattened rules for one language are written (in any format but SRX XML).
+//! - `merge base.srx overlay.srx -o merged.json`: layers the rules and language maps of an overlay on a base file
+//!   (see [srx::SRX::merge]). The result is written in one of the formats of `convert`.
+//! - `diff old.srx new.srx`: lists added, removed and changed rules per language and changes of the language map,
+//!   `--corpus sample.txt -l en` also shows the lines of a sample corpus which are segmented differently.
+//! - `test --srxfile segment.srx tests.yaml`: checks that inputs are split into the expected segments per language
+//!   and lists the missing and unexpected breaks of failing cases. Exits with an error if any case fails.
+//! - `serve --srxfile segment.srx --port 8080` (with the `serve` feature): answers `POST /segment` requests with a
+//!   JSON body like `{"text": "Hello. World.", "lang": "en"}` with the segments and their byte offsets.
+//! - `bitext --srxfile segment.srx --source-lang en --target-lang de source.txt target.txt`: splits parallel files
+//!   (or a TSV file of source and target) and writes aligned `source<TAB>target` segment pairs. Lines whose segment
+//!   counts diverge are kept unsplit with a warning.
+//! - `debug --srxfile segment.srx -l en "Some text."`: explains every decision of the rules on the text.
+//! - `bench --srxfile segment.srx -l en corpus.txt`: measures loading the rules and splitting the corpus,
+//!   `--compare other.srx` measures another SRX file on the same corpus.
+//! - `compare --srxfile segment.srx -l en corpus.txt --command "segment -s segment.srx -l en"`: splits the corpus
+//!   as one text and reports how many boundaries agree with a reference segmenter (e. g.
+//!   [loomchild/segment](https://github.com/loomchild/segment)), with examples of disagreements.
+//!   `--reference segments.txt` reads recorded segments of the reference instead.
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    collections::HashMap,
+    error::Error,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Cursor, Write},
+    mem,
+    num::NonZeroUsize,
+    ops::Range,
+    path::PathBuf,
+    process,
+    str::FromStr,
+    thread,
+};
+
+use clap::{ArgGroup, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use column::Column;
+use compression::Compression;
+use config::Config;
+use indicatif::ProgressBar;
+use jsonl::RecordOptions;
+use markup::{InputFormat, Offsets};
+use newlines::{BreakLines, Newlines};
+use progress::ProgressReader;
+use regex::Regex;
+use resume::State;
+use serde_crate::{Deserialize, Serialize};
+use srx::{conllu, Rules, SRX};
+#[cfg(feature = "encoding")]
+use srx::{Encoding, TranscodingReader};
+
+/// Language code to detect the language of each document.
+const AUTO: &str = "auto";
+
+mod bench;
+mod bitext;
+mod column;
+mod compare;
+mod compression;
+mod config;
+mod convert;
+mod debug;
+#[cfg(feature = "language-detection")]
+mod detect;
+mod diff;
+mod filelist;
+mod jsonl;
+mod languages;
+mod limit;
+mod markup;
+mod merge;
+mod newlines;
+mod parallel;
+mod progress;
+mod resume;
+#[cfg(feature = "serve")]
+mod serve;
+mod stats;
+mod suite;
+mod template;
+#[cfg(feature = "warc")]
+mod warc;
+
+/// Output formats of the segments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(crate = "serde_crate", rename_all = "lowercase")]
+enum Format {
+    /// One segment per line.
+    Plain,
+    /// One JSON object per line with the text, byte offsets, line number and input file of the segment.
+    Jsonl,
+    /// `doc_id<TAB>sent_id<TAB>text` per line. Tabs, line breaks and backslashes in the text are escaped with a backslash.
+    Tsv,
+    /// CoNLL-U blocks with `# sent_id = doc_id-sent_id` and `# text` and one line per whitespace-separated token,
+    /// starting each document with `# newdoc id = doc_id`. Segments are trimmed.
+    Conllu,
+}
+
+/// Levels at which segments are counted with `--count`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(crate = "serde_crate", rename_all = "lowercase")]
+enum CountLevel {
+    /// `doc_id<TAB>line<TAB>count` per line.
+    Lines,
+    /// `doc_id<TAB>count` per document.
+    Documents,
+    /// Only the total.
+    Total,
+}
+
+/// An input document.
+#[derive(Debug)]
+struct Document {
+    id: String,
+    // `None` if reading from stdin
+    file: Option<String>,
+    // set if the text was extracted from the document
+    offsets: Option<Offsets>,
+    // ranges of the text which must not be split
+    protected: Vec<Range<usize>>,
+}
+
+/// The record written before the segments of a document with `--doc-marker` and `--format jsonl`.
+#[derive(Debug, Serialize)]
+#[serde(crate = "serde_crate")]
+struct DocumentRecord<'a> {
+    document: &'a str,
+    // `None` if reading from stdin
+    file: Option<&'a str>,
+}
+
+/// A segment with its position in the input.
+#[derive(Debug, Serialize)]
+#[serde(crate = "serde_crate")]
+struct Segment<'a> {
+    text: &'a str,
+    // byte offsets in the input
+    start: usize,
+    end: usize,
+    // 1-based line number in the input
+    line: usize,
+    // `None` if reading from stdin
+    file: Option<&'a str>,
+    #[serde(skip)]
+    doc_id: &'a str,
+    // 1-based number of the segment in the document
+    #[serde(skip)]
+    sent_id: usize,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Lists the language rules of an SRX file with their map patterns and rule counts.
+    ListLanguages {
+        /// Path to the SRX file.
+        #[arg(short, long, visible_alias = "srxfile")]
+        srx: PathBuf,
+    },
+    /// Converts an SRX file between formats.
+    Convert {
+        /// File to read.
+        input: PathBuf,
+        /// File to write.
+        output: PathBuf,
+        /// Format of the input. Derived from the extension if omitted.
+        #[arg(long, value_enum)]
+        from: Option<convert::FileFormat>,
+        /// Format of the output. Derived from the extension if omitted.
+        #[arg(long, value_enum)]
+        to: Option<convert::FileFormat>,
+        /// Flattens the cascade and only writes the rules for this language code.
+        #[arg(short, long)]
+        lang: Option<String>,
+    },
+    /// Layers the rules of an overlay SRX file on a base SRX file, rules of the overlay take precedence.
+    Merge {
+        /// File with the base rules.
+        base: PathBuf,
+        /// File with the rules layered on top.
+        overlay: PathBuf,
+        /// File to write the merged rules to.
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Format of the output. Derived from the extension if omitted.
+        #[arg(long, value_enum)]
+        to: Option<convert::FileFormat>,
+    },
+    /// Prints the differences between the rules of two SRX files.
+    Diff {
+        /// File with the old rules.
+        old: PathBuf,
+        /// File with the new rules.
+        new: PathBuf,
+        /// Sample corpus whose lines are split with both files to show how the segmentation changes.
+        #[arg(long, requires = "lang")]
+        corpus: Option<PathBuf>,
+        /// Language code used to select the rules for splitting the corpus.
+        #[arg(short, long)]
+        lang: Option<String>,
+    },
+    /// Runs a test suite of inputs and their expected segments per language code.
+    Test {
+        /// Path to the SRX file.
+        #[arg(short, long, visible_alias = "srxfile")]
+        srx: PathBuf,
+        /// YAML file mapping language codes to lists of cases with an `input`, the expected `segments`
+        /// and optionally a `name`, or a `.txt` file in the line format with `[lang]` headers and a `|` at each
+        /// expected break.
+        suite: PathBuf,
+    },
+    /// Serves a JSON API splitting texts over HTTP: `POST /segment` with `{"text": ..., "lang": ...}`.
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Path to the SRX file.
+        #[arg(short, long, visible_alias = "srxfile")]
+        srx: PathBuf,
+        /// Port to listen on.
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+        /// Address to listen on.
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
+    /// Splits parallel texts and writes aligned `source<TAB>target` pairs of segments.
+    Bitext {
+        /// Path to the SRX file.
+        #[arg(short, long, visible_alias = "srxfile")]
+        srx: PathBuf,
+        /// Language code of the source text.
+        #[arg(long)]
+        source_lang: String,
+        /// Language code of the target text.
+        #[arg(long)]
+        target_lang: String,
+        /// File with the source text, or a TSV file with source and target columns if no target file is given.
+        source: PathBuf,
+        /// File with the target text, line by line parallel to the source.
+        target: Option<PathBuf>,
+        /// File to write the pairs to. Writes to stdout if omitted.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Prints every boundary decision on a text: the position, the deciding rule and the rules it shadows.
+    Debug {
+        /// Path to the SRX file.
+        #[arg(short, long, visible_alias = "srxfile")]
+        srx: PathBuf,
+        /// Language code used to select the rules, e. g. `en`.
+        #[arg(short, long)]
+        lang: String,
+        /// Text to split. Reads from stdin if omitted.
+        text: Option<String>,
+    },
+    /// Measures load time and splitting throughput of SRX files on a corpus.
+    Bench {
+        /// Path to the SRX file.
+        #[arg(short, long, visible_alias = "srxfile")]
+        srx: PathBuf,
+        /// Language code used to select the rules, e. g. `en`.
+        #[arg(short, long)]
+        lang: String,
+        /// Corpus whose lines are split.
+        corpus: PathBuf,
+        /// Another SRX file to measure on the same corpus.
+        #[arg(long)]
+        compare: Option<PathBuf>,
+        /// Number of times the corpus is split, the fastest time is reported.
+        #[arg(long, default_value_t = 3)]
+        iterations: usize,
+    },
+    /// Compares the boundaries of the rules on a corpus with those of a reference segmenter.
+    Compare {
+        /// Path to the SRX file.
+        #[arg(short, long, visible_alias = "srxfile")]
+        srx: PathBuf,
+        /// Language code used to select the rules, e. g. `en`.
+        #[arg(short, long)]
+        lang: String,
+        /// Corpus which is split as one text.
+        corpus: PathBuf,
+        /// File with the segments of the corpus recorded from the reference segmenter, one per line.
+        #[arg(long, required_unless_present = "command", conflicts_with = "command")]
+        reference: Option<PathBuf>,
+        /// Command running the reference segmenter, e. g. `segment -s segment.srx -l en`. It receives the corpus
+        /// on stdin and must write one segment per line.
+        #[arg(long)]
+        command: Option<String>,
+        /// Maximum number of disagreements of each kind which are shown.
+        #[arg(long, default_value_t = 10)]
+        examples: usize,
+    },
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "srx-split",
+    version,
+    about = "Splits text into segments with SRX rules.",
+    group(ArgGroup::new("inputs").args(["input", "filelist"]).multiple(true))
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Language code used to select the rules, e. g. `en`. With `auto`, the language of each document is detected
+    /// (requires the `language-detection` feature).
+    #[arg(short, long, env = "SRX_LANG")]
+    lang: Option<String>,
+    /// Path to the SRX file. Uses the embedded rules if omitted and built with the `default-rules` feature.
+    #[arg(short, long, visible_alias = "srxfile", env = "SRX_FILE")]
+    srx: Option<PathBuf>,
+    /// File to read text from, can be given multiple times. Reads from stdin if omitted.
+    #[arg(short, long)]
+    input: Vec<PathBuf>,
+    /// File listing input paths, one per line, each optionally followed by a tab and its language code.
+    #[arg(long, value_name = "FILE")]
+    filelist: Option<PathBuf>,
+    /// File to write segments to. Writes to stdout if omitted.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Writes the segments of every input to its own file, e. g. `{dir}/{stem}.sents{ext}`.
+    #[arg(long, value_name = "TEMPLATE", requires = "inputs", conflicts_with_all = ["output", "count"])]
+    output_template: Option<String>,
+    /// Skips inputs whose output file of `--output-template` already exists.
+    #[arg(long, requires = "output_template")]
+    skip_existing: bool,
+    /// File recording the completed inputs of `--output-template`, appended to after every input.
+    #[arg(long, value_name = "FILE", requires = "output_template")]
+    state: Option<PathBuf>,
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Plain, env = "SRX_FORMAT")]
+    format: Format,
+    /// Number of threads splitting lines in parallel. Output order is preserved.
+    /// Uses all available cores if set to 0.
+    #[arg(short, long, default_value_t = 1, env = "SRX_THREADS")]
+    threads: usize,
+    /// Compression of the output. Derived from the extension of the output file if omitted.
+    #[arg(short, long, value_enum)]
+    compress: Option<Compression>,
+    /// Prints the rules which were skipped because their regexes are not supported to stderr.
+    #[arg(long)]
+    show_errors: bool,
+    /// Echoes the input with a marker inserted at every break instead of writing segments.
+    #[arg(long, value_name = "MARKER", num_args = 0..=1, default_missing_value = "‖")]
+    annotate: Option<String>,
+    /// Only splits the first N lines of the input and writes the segments to stdout.
+    #[arg(long, value_name = "N", conflicts_with_all = ["output", "output_template"])]
+    sample: Option<usize>,
+    /// Reports how many boundaries each rule produced or suppressed on stderr after splitting.
+    #[arg(long)]
+    rule_stats: bool,
+    /// Shows the progress on stderr: a bar with an ETA when reading files, the number of bytes read from stdin.
+    #[arg(long)]
+    progress: bool,
+    /// Terminates segments with `\0` instead of `\n`, so segments containing line breaks are unambiguous.
+    #[arg(short = '0', long)]
+    null: bool,
+    /// Splits segments longer than this number of characters at the last whitespace within the limit.
+    #[arg(long, value_name = "N")]
+    max_length: Option<NonZeroUsize>,
+    /// Reports segments which were split because of `--max-length` on stderr.
+    #[arg(short, long)]
+    verbose: bool,
+    /// Strips leading and trailing whitespace from segments and drops segments which become empty.
+    #[arg(long)]
+    trim: bool,
+    /// Writes blank lines of the input as they are in the plain format, instead of dropping them.
+    #[arg(long)]
+    keep_blank_lines: bool,
+    /// Writes lines matching this regex as they are instead of splitting them in the plain format,
+    /// e. g. structural markers like `^<doc `.
+    #[arg(long, value_name = "REGEX", value_parser = Regex::new)]
+    passthrough: Option<Regex>,
+    /// Only writes the number of segments at this level, followed by `total<TAB>count`.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "LEVEL",
+        num_args = 0..=1,
+        default_missing_value = "documents",
+        conflicts_with = "annotate"
+    )]
+    count: Option<CountLevel>,
+    /// How line breaks besides `\n` are handled, e. g. the `\r` of old Mac files or U+2028.
+    #[arg(long, value_enum, default_value_t = Newlines::Keep)]
+    newlines: Newlines,
+    /// Only splits this field (starting at 1) of delimited rows, e. g. of TSV files.
+    #[arg(long, value_name = "N")]
+    column: Option<NonZeroUsize>,
+    /// Delimiter of the fields with `--column`, `\t` stands for a tab.
+    #[arg(long, default_value = "\t", value_parser = column::parse_delimiter)]
+    delimiter: String,
+    /// Writes one row with the segments joined by this separator instead of one row per segment with `--column`.
+    #[arg(long, value_name = "SEPARATOR", requires = "column")]
+    join: Option<String>,
+    /// Writes this line between documents (`{id}` is replaced by the ID of the next document), or a document record with `--format jsonl`.
+    #[arg(
+        long,
+        value_name = "MARKER",
+        num_args = 0..=1,
+        default_missing_value = "",
+        conflicts_with = "count"
+    )]
+    doc_marker: Option<String>,
+    /// Format of the input documents.
+    #[arg(long, value_enum, default_value_t = InputFormat::Text)]
+    input_format: InputFormat,
+    /// Encoding of the inputs, e. g. `utf-16` or `latin1`, or `auto` to detect it (requires the `encoding` feature).
+    #[arg(long, value_name = "LABEL")]
+    encoding: Option<String>,
+    /// Field of the records which is split with `--input-format jsonl`.
+    #[arg(long, value_name = "FIELD", default_value = "text")]
+    text_field: String,
+    /// Writes every record once with a `segments` array instead of one record per segment with `--input-format jsonl`.
+    #[arg(long)]
+    segments_array: bool,
+    /// Ends segments at block elements like `<p>` or `<li>` with `--input-format html`.
+    #[arg(long)]
+    break_at_blocks: bool,
+    /// TOML file with default values for the options above.
+    #[arg(long, env = "SRX_CONFIG")]
+    config: Option<PathBuf>,
+}
+
+impl Args {
+    /// Parses the command line and fills in the options which were not given from the config file.
+    fn load() -> Result<Self, Box<dyn Error>> {
+        let matches = Args::command().get_matches();
+        let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+
+        if let Some(path) = &args.config {
+            let base = path.parent().map(PathBuf::from).unwrap_or_default();
+            Config::load(path)?.apply(&mut args, &matches, &base);
+        }
+
+        Ok(args)
+    }
+
+    fn output_options(&self) -> OutputOptions<'_> {
+        OutputOptions {
+            format: self.format,
+            marker: self.annotate.as_deref(),
+            terminator: if self.null { b'\0' } else { b'\n' },
+            max_length: self.max_length,
+            verbose: self.verbose,
+            trim: self.trim,
+            keep_blank_lines: self.keep_blank_lines,
+            passthrough: self.passthrough.as_ref(),
+            count: self.count,
+            newlines: self.newlines,
+            column: self.column.map(|column| Column {
+                index: column.get() - 1,
+                delimiter: &self.delimiter,
+                join: self.join.as_deref(),
+            }),
+        }
+    }
+
+    fn record_options(&self) -> RecordOptions<'_> {
+        RecordOptions {
+            text_field: &self.text_field,
+            segments_array: self.segments_array,
+            trim: self.trim,
+            terminator: if self.null { b'\0' } else { b'\n' },
+        }
+    }
+}
+
+/// How segments are written.
+#[derive(Debug, Clone, Copy)]
+struct OutputOptions<'a> {
+    format: Format,
+    // if set, lines are echoed with this marker at breaks instead of writing segments in the format
+    marker: Option<&'a str>,
+    // written after every segment (or annotated line)
+    terminator: u8,
+    max_length: Option<NonZeroUsize>,
+    verbose: bool,
+    trim: bool,
+    // blank lines and lines matching the regex are written as they are in the plain format
+    keep_blank_lines: bool,
+    passthrough: Option<&'a Regex>,
+    // if set, only the number of segments is written
+    count: Option<CountLevel>,
+    newlines: Newlines,
+    // if set, only this column of the lines is split
+    column: Option<Column<'a>>,
+}
+
+impl OutputOptions<'_> {
+    /// Gets the byte range of the line (without line ending) which is split.
+    fn span(&self, text: &str) -> Range<usize> {
+        match self.column {
+            Some(column) => column.span(text),
+            None => 0..text.len(),
+        }
+    }
+}
+
+/// A buffered output, compressed if requested.
+type Output = BufWriter<compression::Writer<Box<dyn Write>>>;
+
+fn open_output(path: Option<&PathBuf>, compression: Option<Compression>) -> io::Result<Output> {
+    let (sink, detected): (Box<dyn Write>, _) = match path {
+        Some(path) => (Box::new(File::create(path)?), Compression::from_path(path)),
+        None => (Box::new(io::stdout().lock()), Compression::None),
+    };
+
+    Ok(BufWriter::new(
+        compression.unwrap_or(detected).writer(sink)?,
+    ))
+}
+
+/// Flushes the output and finishes its compression.
+fn finish_output(output: Output) -> io::Result<()> {
+    output
+        .into_inner()
+        .map_err(io::IntoInnerError::into_error)?
+        .finish()
+}
+
+fn load_srx(path: &PathBuf) -> Result<SRX, Box<dyn Error>> {
+    Ok(SRX::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Path of the rules embedded with the `default-rules` feature, relative to the crate root.
+#[cfg(feature = "default-rules")]
+const DEFAULT_RULES_PATH: &str = "data/segment.srx";
+
+#[cfg(feature = "default-rules")]
+const DEFAULT_RULES: &str = include_str!("../../../data/segment.srx");
+
+/// Loads the rules embedded with the `default-rules` feature and reports on stderr that they are used.
+fn load_default_rules() -> Result<SRX, Box<dyn Error>> {
+    #[cfg(feature = "default-rules")]
+    {
+        eprintln!(
+            "srx-split: no SRX file given, using the embedded rules of LanguageTool ({})",
+            DEFAULT_RULES_PATH
+        );
+        Ok(SRX::from_str(DEFAULT_RULES)?)
+    }
+
+    #[cfg(not(feature = "default-rules"))]
+    Err("no SRX file given, use --srx or set it in the config file".into())
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    if let Some(command) = &args.command {
+        let mut output = BufWriter::new(io::stdout().lock());
+
+        match command {
+            Command::ListLanguages { srx } => {
+                languages::list_languages(&load_srx(srx)?, &mut output)?
+            }
+            Command::Convert {
+                input,
+                output,
+                from,
+                to,
+                lang,
+            } => convert::convert(input, output, *from, *to, lang.as_deref())?,
+            Command::Merge {
+                base,
+                overlay,
+                output,
+                to,
+            } => merge::merge(base, overlay, output, *to)?,
+            Command::Diff {
+                old,
+                new,
+                corpus,
+                lang,
+            } => {
+                let read = |path: &PathBuf| {
+                    convert::read_srx(path, convert::FileFormat::resolve(None, path)?)
+                };
+                let (old, new) = (read(old)?, read(new)?);
+                diff::diff(&old, &new, &mut output)?;
+
+                if let (Some(corpus), Some(lang)) = (corpus, lang) {
+                    let corpus = Compression::from_path(corpus).reader(File::open(corpus)?)?;
+                    diff::diff_corpus(
+                        &old.language_rules(lang),
+                        &new.language_rules(lang),
+                        corpus,
+                        &mut output,
+                    )?;
+                }
+            }
+            Command::Test { srx, suite } => {
+                let failed = suite::run(
+                    &load_srx(srx)?,
+                    suite,
+                    &fs::read_to_string(suite)?,
+                    &mut output,
+                )?;
+                if failed > 0 {
+                    output.flush()?;
+                    return Err(format!("{} test case(s) failed", failed).into());
+                }
+            }
+            #[cfg(feature = "serve")]
+            Command::Serve { srx, port, host } => {
+                serve::serve(&load_srx(srx)?, &format!("{}:{}", host, port))?
+            }
+            Command::Bitext {
+                srx,
+                source_lang,
+                target_lang,
+                source,
+                target,
+                output: path,
+            } => {
+                let srx = load_srx(srx)?;
+                let open = |path: &PathBuf| Compression::from_path(path).reader(File::open(path)?);
+                let pairs: Box<dyn Iterator<Item = io::Result<(String, String)>>> = match target {
+                    Some(target) => Box::new(bitext::parallel_lines(open(source)?, open(target)?)),
+                    None => Box::new(bitext::tsv_lines(open(source)?)),
+                };
+
+                let mut file = path
+                    .as_ref()
+                    .map(|path| open_output(Some(path), None))
+                    .transpose()?;
+                let writer: &mut dyn Write = match &mut file {
+                    Some(file) => file,
+                    None => &mut output,
+                };
+                let (total, diverging) = bitext::bitext(
+                    &srx.language_rules(source_lang),
+                    &srx.language_rules(target_lang),
+                    pairs,
+                    writer,
+                    &mut io::stderr().lock(),
+                )?;
+                if let Some(file) = file {
+                    file.into_inner()
+                        .map_err(io::IntoInnerError::into_error)?
+                        .finish()?;
+                }
+                if diverging > 0 {
+                    eprintln!(
+                        "srx-split: {} of {} lines kept unsplit because their segment counts diverge",
+                        diverging, total
+                    );
+                }
+            }
+            Command::Debug { srx, lang, text } => {
+                let text = match text {
+                    Some(text) => text.clone(),
+                    None => io::read_to_string(io::stdin())?,
+                };
+                debug::debug(&load_srx(srx)?.language_rules(lang), &text, &mut output)?
+            }
+            Command::Bench {
+                srx,
+                lang,
+                corpus,
+                compare,
+                iterations,
+            } => {
+                let srx_paths: Vec<_> = std::iter::once(srx)
+                    .chain(compare)
+                    .map(PathBuf::as_path)
+                    .collect();
+                bench::bench(&srx_paths, lang, corpus, *iterations, &mut output)?
+            }
+            Command::Compare {
+                srx,
+                lang,
+                corpus,
+                reference,
+                command,
+                examples,
+            } => {
+                let text = fs::read_to_string(corpus)?;
+                let reference = match (reference, command) {
+                    (Some(reference), _) => fs::read_to_string(reference)?,
+                    (None, Some(command)) => compare::run_reference(command, &text)?,
+                    (None, None) => unreachable!("clap requires a reference or a command"),
+                };
+                compare::compare(
+                    &load_srx(srx)?.language_rules(lang),
+                    &text,
+                    &reference,
+                    *examples,
+                    &mut output,
+                )?
+            }
+        }
+        output.flush()?;
+        return Ok(());
+    }
+
+    let srx = match &args.srx {
+        Some(path) => load_srx(path)?,
+        None => load_default_rules()?,
+    };
+    if args.show_errors {
+        write_errors(&srx, &mut io::stderr().lock())?;
+    }
+    let mut inputs: Vec<filelist::Entry> = args.input.iter().cloned().map(Into::into).collect();
+    if let Some(path) = &args.filelist {
+        inputs.extend(filelist::read(path)?);
+    }
+    let lang = args.lang.as_deref();
+    if lang.is_none() && (inputs.is_empty() || inputs.iter().any(|entry| entry.lang.is_none())) {
+        return Err("no language given, use --lang or set it in the config file".into());
+    }
+    let threads = match args.threads {
+        0 => thread::available_parallelism().map_or(1, usize::from),
+        threads => threads,
+    };
+
+    let mut output = open_output(args.output.as_ref(), args.compress)?;
+
+    let bar = if args.progress {
+        let total = if inputs.is_empty() {
+            None
+        } else {
+            // compressed files are counted by their compressed size, which is what is read from disk
+            let sizes: io::Result<Vec<_>> = inputs
+                .iter()
+                .map(|entry| fs::metadata(&entry.path).map(|metadata| metadata.len()))
+                .collect();
+            Some(sizes?.into_iter().sum())
+        };
+        progress::progress_bar(total)
+    } else {
+        ProgressBar::hidden()
+    };
+
+    // with language detection, documents in the same language share compiled rules
+    let mut rules_cache: HashMap<String, Rules> = HashMap::new();
+    // the number of lines left to split with `--sample`
+    let remaining = Cell::new(args.sample);
+    let mut total = 0;
+    let mut documents = 0;
+    let mut split = |mut document: Document,
+                     lang: &str,
+                     input: Box<dyn BufRead + Send>,
+                     output: &mut Output| {
+        if let Some(marker) = &args.doc_marker {
+            write_document_marker(
+                output,
+                args.output_options(),
+                marker,
+                &document,
+                documents == 0 || args.output_template.is_some(),
+            )?;
+        }
+        documents += 1;
+
+        let input: Box<dyn BufRead + Send> = match args.input_format {
+            InputFormat::Text | InputFormat::Jsonl | InputFormat::Warc => input,
+            format => {
+                let input = io::read_to_string(input)?;
+                let extracted = match format {
+                    InputFormat::Markdown => markup::extract_markdown(&input),
+                    _ => markup::extract_html(&input, args.break_at_blocks),
+                };
+                document.offsets = Some(extracted.offsets);
+                document.protected = extracted.protected;
+                Box::new(Cursor::new(extracted.text))
+            }
+        };
+        let (lang, input) = resolve_language(lang, input)?;
+        let input: Box<dyn BufRead + Send> = match args.newlines {
+            Newlines::Break => Box::new(BreakLines::new(input)),
+            _ => input,
+        };
+        let input: Box<dyn BufRead + Send> = match remaining.get() {
+            Some(limit) => {
+                let (lines, count) = take_lines(input, limit)?;
+                remaining.set(Some(limit - count));
+                Box::new(Cursor::new(lines))
+            }
+            None => input,
+        };
+        let rules = rules_cache.entry(lang.to_owned()).or_insert_with(|| {
+            let rules = srx.language_rules(lang);
+            if args.rule_stats {
+                rules.with_metrics()
+            } else {
+                rules
+            }
+        });
+
+        if args.input_format == InputFormat::Jsonl {
+            return jsonl::split_records(rules, input, args.record_options(), output);
+        }
+        split_document(
+            rules,
+            input,
+            &document,
+            args.output_options(),
+            threads,
+            output,
+        )
+    };
+
+    // inputs in other encodings are transcoded before anything else reads them
+    let mut split = |document: Document,
+                     lang: &str,
+                     input: Box<dyn BufRead + Send>,
+                     output: &mut Output|
+     -> io::Result<usize> {
+        let input = match &args.encoding {
+            Some(encoding) => transcode(input, encoding, &document, args.verbose)?,
+            None => input,
+        };
+        split(document, lang, input, output)
+    };
+
+    // WARC files contain many documents, one per text record
+    let mut split = |document: Document,
+                     lang: &str,
+                     input: Box<dyn BufRead + Send>,
+                     output: &mut Output|
+     -> io::Result<usize> {
+        if args.input_format != InputFormat::Warc {
+            return split(document, lang, input, output);
+        }
+
+        #[cfg(feature = "warc")]
+        {
+            let mut total = 0;
+            for record in warc::text_records(input) {
+                if remaining.get() == Some(0) {
+                    break;
+                }
+                let record = record?;
+                let document = Document {
+                    id: record.id,
+                    file: document.file.clone(),
+                    offsets: None,
+                    protected: Vec::new(),
+                };
+                total += split(document, lang, Box::new(Cursor::new(record.text)), output)?;
+            }
+            Ok(total)
+        }
+
+        #[cfg(not(feature = "warc"))]
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "reading WARC files requires the `warc` feature",
+        ))
+    };
+
+    if inputs.is_empty() {
+        let document = Document {
+            id: "1".into(),
+            file: None,
+            offsets: None,
+            protected: Vec::new(),
+        };
+        total += split(
+            document,
+            lang.expect("a language is given without inputs"),
+            Box::new(BufReader::new(ProgressReader::new(
+                io::stdin(),
+                bar.clone(),
+            ))),
+            &mut output,
+        )?;
+    }
+
+    let mut state = State::load(args.state.as_deref())?;
+    for (i, entry) in inputs.iter().enumerate() {
+        if remaining.get() == Some(0) {
+            break;
+        }
+        let path = &entry.path;
+        let lang = entry
+            .lang
+            .as_deref()
+            .or(lang)
+            .expect("a language is given for every input");
+        let output_path = args
+            .output_template
+            .as_ref()
+            .map(|template| template::output_path(template, path));
+        if let Some(output_path) = &output_path {
+            if args.skip_existing && state.is_complete(path, output_path) {
+                bar.inc(fs::metadata(path)?.len());
+                continue;
+            }
+        }
+
+        let document = Document {
+            id: compression::strip_extension(path)
+                .file_stem()
+                .map_or_else(|| (i + 1).to_string(), |stem| stem.to_string_lossy().into()),
+            file: Some(path.to_string_lossy().into()),
+            offsets: None,
+            protected: Vec::new(),
+        };
+        let file = ProgressReader::new(File::open(path)?, bar.clone());
+        let input = Compression::from_path(path).reader(file)?;
+        match output_path {
+            Some(output_path) => {
+                let partial = resume::partial_path(&output_path);
+                let compression = args
+                    .compress
+                    .unwrap_or_else(|| Compression::from_path(&output_path));
+                let mut output = open_output(Some(&partial), Some(compression))?;
+                total += split(document, lang, input, &mut output)?;
+                finish_output(output)?;
+                fs::rename(partial, output_path)?;
+                state.complete(path)?;
+            }
+            None => total += split(document, lang, input, &mut output)?,
+        }
+    }
+    bar.finish();
+
+    if args.rule_stats {
+        let mut languages: Vec<_> = rules_cache.iter().collect();
+        languages.sort_by_key(|&(lang, _)| lang);
+        let mut stderr = io::stderr().lock();
+        for (lang, rules) in languages {
+            stats::write_rule_stats(lang, rules, &mut stderr)?;
+        }
+    }
+
+    if args.count.is_some() {
+        write!(output, "total\t{}", total)?;
+        output.write_all(&[args.output_options().terminator])?;
+    }
+
+    finish_output(output)?;
+    Ok(())
+}
+
+/// Writes the errors of rules which were skipped when loading, grouped by language.
+fn write_errors<W: Write>(srx: &SRX, output: &mut W) -> io::Result<()> {
+    for language in srx.languages() {
+        let errors = match srx.errors().get(language) {
+            Some(errors) if !errors.is_empty() => errors,
+            _ => continue,
+        };
+
+        writeln!(output, "{}: skipped {} rule(s)", language.0, errors.len())?;
+        for error in errors {
+            writeln!(
+                output,
+                "    rule {} (beforebreak: {:?}, afterbreak: {:?}):",
+                error.index,
+                error.before_break.as_deref().unwrap_or_default(),
+                error.after_break.as_deref().unwrap_or_default()
+            )?;
+            // errors of `regex` span multiple lines with the pattern and the reason
+            for line in error.message.lines() {
+                writeln!(output, "        {}", line)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads at most `limit` lines of the input, gets them with the number of lines read.
+fn take_lines<R: BufRead>(mut input: R, limit: usize) -> io::Result<(Vec<u8>, usize)> {
+    let mut lines = Vec::new();
+    let mut count = 0;
+    while count < limit && input.read_until(b'\n', &mut lines)? > 0 {
+        count += 1;
+    }
+    Ok((lines, count))
+}
+
+/// Wraps the input to transcode it from the encoding with the label (see `Encoding::for_label`) to UTF-8,
+/// or from the detected encoding if the label is [AUTO].
+#[cfg_attr(not(feature = "encoding"), allow(unused_variables))]
+fn transcode(
+    input: Box<dyn BufRead + Send>,
+    label: &str,
+    document: &Document,
+    verbose: bool,
+) -> io::Result<Box<dyn BufRead + Send>> {
+    #[cfg(feature = "encoding")]
+    {
+        let reader = if label == AUTO {
+            detect_encoding(input)?
+        } else {
+            let encoding = Encoding::for_label(label.as_bytes()).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown encoding {:?}", label),
+                )
+            })?;
+            TranscodingReader::new(input, encoding)?
+        };
+
+        if verbose {
+            eprintln!(
+                "srx-split: {}: reading as {}",
+                document.file.as_deref().unwrap_or(&document.id),
+                reader.encoding().name()
+            );
+        }
+        Ok(Box::new(BufReader::new(reader)))
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reading other encodings than UTF-8 requires the `encoding` feature",
+    ))
+}
+
+#[cfg(feature = "encoding")]
+#[cfg_attr(not(feature = "encoding-detection"), allow(unused_variables))]
+fn detect_encoding<R: io::Read>(input: R) -> io::Result<TranscodingReader<R>> {
+    #[cfg(feature = "encoding-detection")]
+    return TranscodingReader::detect(input);
+
+    #[cfg(not(feature = "encoding-detection"))]
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "detecting the encoding requires the `encoding-detection` feature",
+    ))
+}
+
+/// Gets the language code of the document, detecting it if the code is [AUTO].
+fn resolve_language(
+    lang: &str,
+    input: Box<dyn BufRead + Send>,
+) -> io::Result<(&str, Box<dyn BufRead + Send>)> {
+    if lang != AUTO {
+        return Ok((lang, input));
+    }
+
+    #[cfg(feature = "language-detection")]
+    return detect::detect_document(input);
+
+    #[cfg(not(feature = "language-detection"))]
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "detecting the language requires the `language-detection` feature",
+    ))
+}
+
+/// Splits every line of the input and writes the segments.
+fn split_document<R: BufRead + Send, W: Write>(
+    rules: &Rules,
+    mut input: R,
+    document: &Document,
+    options: OutputOptions,
+    threads: usize,
+    output: &mut W,
+) -> io::Result<usize> {
+    let mut writer = DocumentWriter::new(document, options);
+
+    if threads > 1 {
+        parallel::split_lines(
+            rules,
+            input,
+            threads,
+            |text| options.span(text),
+            |line, ranges| writer.write_line(output, line, ranges),
+        )?;
+    } else {
+        let mut splitter = rules.splitter();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if input.read_line(&mut line)? == 0 {
+                break;
+            }
+
+            let text = strip_line_ending(&line);
+            let ranges = splitter.split_ranges(&text[options.span(text)]);
+            writer.write_line(output, &line, ranges)?;
+        }
+    }
+
+    writer.finish(output)
+}
+
+/// Writes the segments of the lines of a document, keeping track of their positions.
+struct DocumentWriter<'a> {
+    document: &'a Document,
+    options: OutputOptions<'a>,
+    // byte offset of the current line in the input
+    offset: usize,
+    // number of lines and segments written so far
+    lines: usize,
+    segments: usize,
+    // buffer for the ranges split to the maximum length
+    limited: Vec<Range<usize>>,
+}
+
+impl<'a> DocumentWriter<'a> {
+    fn new(document: &'a Document, options: OutputOptions<'a>) -> Self {
+        DocumentWriter {
+            document,
+            options,
+            offset: 0,
+            lines: 0,
+            segments: 0,
+            limited: Vec::new(),
+        }
+    }
+
+    /// Writes the segments at the ranges of the line. The line includes its line ending, the ranges are relative to the
+    /// span of the line which is split (see [OutputOptions::span]).
+    fn write_line<W: Write>(
+        &mut self,
+        output: &mut W,
+        line: &str,
+        ranges: &[Range<usize>],
+    ) -> io::Result<()> {
+        self.lines += 1;
+
+        let merged;
+        let ranges = if self.document.protected.is_empty() {
+            ranges
+        } else {
+            merged = markup::merge_protected(ranges, &self.document.protected, self.offset);
+            &merged
+        };
+
+        let text = strip_line_ending(line);
+        let offset = self.options.span(text).start;
+        let mut limited = mem::take(&mut self.limited);
+        let ranges = match self.options.max_length {
+            None if offset == 0 => ranges,
+            None => {
+                limited.clear();
+                limited.extend(
+                    ranges
+                        .iter()
+                        .map(|range| offset + range.start..offset + range.end),
+                );
+                &limited
+            }
+            Some(max_length) => {
+                limited.clear();
+                for range in ranges {
+                    let range = offset + range.start..offset + range.end;
+                    let forced = limit::limit_length(text, range.clone(), max_length, &mut limited);
+                    if forced && self.options.verbose {
+                        eprintln!(
+                            "srx-split: {}:{}: split segment of {} characters longer than --max-length",
+                            self.document.file.as_deref().unwrap_or(&self.document.id),
+                            self.lines,
+                            text[range.clone()].chars().count()
+                        );
+                    }
+                }
+                &limited
+            }
+        };
+
+        let result = self.write_ranges(output, line, text, ranges);
+        self.limited = limited;
+        result
+    }
+
+    fn write_ranges<W: Write>(
+        &mut self,
+        output: &mut W,
+        line: &str,
+        text: &str,
+        ranges: &[Range<usize>],
+    ) -> io::Result<()> {
+        let normalize = self.options.newlines == Newlines::Normalize;
+
+        if let Some(marker) = self.options.marker {
+            for (i, range) in ranges.iter().enumerate() {
+                if i > 0 {
+                    output.write_all(marker.as_bytes())?;
+                }
+                if normalize {
+                    output.write_all(newlines::normalize(&text[range.clone()]).as_bytes())?;
+                } else {
+                    output.write_all(&text.as_bytes()[range.clone()])?;
+                }
+            }
+            if normalize && text.len() < line.len() {
+                output.write_all(b"\n")?;
+            } else if self.options.terminator == b'\n' {
+                output.write_all(&line.as_bytes()[text.len()..])?;
+            } else if text.len() < line.len() {
+                output.write_all(&[self.options.terminator])?;
+            }
+
+            self.offset += line.len();
+            return Ok(());
+        }
+
+        if self.options.format == Format::Plain && self.options.count.is_none() {
+            let structural = (self.options.keep_blank_lines && text.trim().is_empty())
+                || self
+                    .options
+                    .passthrough
+                    .is_some_and(|regex| regex.is_match(text));
+            if structural {
+                output.write_all(text.as_bytes())?;
+                output.write_all(&[self.options.terminator])?;
+                self.offset += line.len();
+                return Ok(());
+            }
+        }
+
+        // with a column in the plain format, rows are written once all segments of the line are known
+        let rows = match self.options.format {
+            Format::Plain => self.options.column,
+            _ => None,
+        };
+        let mut row_segments = Vec::new();
+
+        let line_start = self.segments;
+        // CoNLL-U sentences can not start or end with whitespace
+        let trim = self.options.trim || self.options.format == Format::Conllu;
+        for range in ranges {
+            let range = if trim {
+                let range = trim_range(text, range.clone());
+                if range.is_empty() {
+                    continue;
+                }
+                range
+            } else {
+                range.clone()
+            };
+
+            self.segments += 1;
+            if self.options.count.is_some() {
+                continue;
+            }
+
+            let segment_text = if normalize {
+                newlines::normalize(&text[range.clone()])
+            } else {
+                Cow::Borrowed(&text[range.clone()])
+            };
+            if rows.is_some() {
+                row_segments.push(segment_text);
+                continue;
+            }
+
+            let (start, end) = (self.offset + range.start, self.offset + range.end);
+            let (start, end) = match &self.document.offsets {
+                Some(offsets) => (offsets.start(start), offsets.end(end)),
+                None => (start, end),
+            };
+            let segment = Segment {
+                text: &segment_text,
+                start,
+                end,
+                line: self.lines,
+                file: self.document.file.as_deref(),
+                doc_id: &self.document.id,
+                sent_id: self.segments,
+            };
+            if self.options.format == Format::Conllu {
+                // blocks end with a blank line instead of a terminator
+                if self.segments == 1 {
+                    conllu::write_document(output, &self.document.id)?;
+                }
+                write_segment(output, self.options.format, &segment)?;
+                continue;
+            }
+            write_segment(output, self.options.format, &segment)?;
+            output.write_all(&[self.options.terminator])?;
+        }
+
+        if let (Some(column), None) = (rows, self.options.count) {
+            column.write_rows(output, text, &row_segments, self.options.terminator)?;
+        }
+
+        if self.options.count == Some(CountLevel::Lines) {
+            write!(
+                output,
+                "{}\t{}\t{}",
+                escape_tsv(&self.document.id),
+                self.lines,
+                self.segments - line_start
+            )?;
+            output.write_all(&[self.options.terminator])?;
+        }
+
+        self.offset += line.len();
+        Ok(())
+    }
+
+    /// Writes the number of segments of the document if they are counted per document.
+    /// Returns the number of segments.
+    fn finish<W: Write>(self, output: &mut W) -> io::Result<usize> {
+        if self.options.count == Some(CountLevel::Documents) {
+            write!(
+                output,
+                "{}\t{}",
+                escape_tsv(&self.document.id),
+                self.segments
+            )?;
+            output.write_all(&[self.options.terminator])?;
+        }
+
+        Ok(self.segments)
+    }
+}
+
+/// Shrinks the range of the text to exclude leading and trailing whitespace.
+fn trim_range(text: &str, range: Range<usize>) -> Range<usize> {
+    let segment = &text[range.clone()];
+    let start = range.start + (segment.len() - segment.trim_start().len());
+    let end = range.end - (segment.len() - segment.trim_end().len());
+    start..end.max(start)
+}
+
+/// Removes a trailing `\r\n`, `\n` or other line break (see [newlines::LINE_BREAKS]) from the line.
+fn strip_line_ending(line: &str) -> &str {
+    match line.strip_suffix("\r\n") {
+        Some(line) => line,
+        None => line
+            .strip_suffix(|c| c == '\n' || newlines::LINE_BREAKS.contains(&c))
+            .unwrap_or(line),
+    }
+}
+
+/// Writes the marker before a document: the marker line between documents, or the document record in the JSONL format.
+fn write_document_marker<W: Write>(
+    output: &mut W,
+    options: OutputOptions,
+    marker: &str,
+    document: &Document,
+    first: bool,
+) -> io::Result<()> {
+    if options.format == Format::Jsonl && options.marker.is_none() {
+        let record = DocumentRecord {
+            document: &document.id,
+            file: document.file.as_deref(),
+        };
+        serde_json::to_writer(&mut *output, &record)?;
+    } else if !first {
+        output.write_all(marker.replace("{id}", &document.id).as_bytes())?;
+    } else {
+        return Ok(());
+    }
+
+    output.write_all(&[options.terminator])
+}
+
+/// Writes the segment in the format, without a terminator.
+fn write_segment<W: Write>(output: &mut W, format: Format, segment: &Segment) -> io::Result<()> {
+    match format {
+        Format::Plain => output.write_all(segment.text.as_bytes()),
+        Format::Jsonl => Ok(serde_json::to_writer(&mut *output, segment)?),
+        Format::Tsv => write!(
+            output,
+            "{}\t{}\t{}",
+            escape_tsv(segment.doc_id),
+            segment.sent_id,
+            escape_tsv(segment.text)
+        ),
+        Format::Conllu => conllu::write_sentence(
+            output,
+            &format!("{}-{}", segment.doc_id, segment.sent_id),
+            segment.text,
+        ),
+    }
+}
+
+/// Escapes characters which would break a TSV field.
+fn escape_tsv(field: &str) -> Cow<'_, str> {
+    if !field.contains(['\\', '\t', '\n', '\r']) {
+        return Cow::Borrowed(field);
+    }
+
+    let mut escaped = String::with_capacity(field.len() + 1);
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+fn main() {
+    if let Err(error) = Args::load().and_then(|args| run(&args)) {
+        // the reader of the output went away (e. g. `srx-split ... | head`), which is not an error
+        if error
+            .downcast_ref::<io::Error>()
+            .is_some_and(|error| error.kind() == io::ErrorKind::BrokenPipe)
+        {
+            return;
+        }
+
+        eprintln!("srx-split: {}", error);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_taken_up_to_limit() -> io::Result<()> {
+        let input = "One.\nTwo.\nThree.";
+
+        assert_eq!(
+            take_lines(input.as_bytes(), 2)?,
+            (b"One.\nTwo.\n".to_vec(), 2)
+        );
+        assert_eq!(
+            take_lines(input.as_bytes(), 5)?,
+            (input.as_bytes().to_vec(), 3)
+        );
+        assert_eq!(take_lines(input.as_bytes(), 0)?, (Vec::new(), 0));
+        Ok(())
+    }
+
+    fn document() -> Document {
+        Document {
+            id: "1".into(),
+            file: None,
+            offsets: None,
+            protected: Vec::new(),
+        }
+    }
+
+    fn options<'a>() -> OutputOptions<'a> {
+        OutputOptions {
+            format: Format::Plain,
+            marker: None,
+            terminator: b'\n',
+            max_length: None,
+            verbose: false,
+            trim: false,
+            keep_blank_lines: false,
+            passthrough: None,
+            count: None,
+            newlines: Newlines::Keep,
+            column: None,
+        }
+    }
+
+    fn written(format: Format, segment: &Segment) -> String {
+        let mut output = Vec::new();
+        write_segment(&mut output, format, segment).expect("writing to a vector succeeds");
+        String::from_utf8(output).expect("output is valid UTF-8")
+    }
+
+    #[test]
+    fn segments_written_in_format() {
+        let segment = Segment {
+            text: "Second \"one\".",
+            start: 20,
+            end: 33,
+            line: 2,
+            file: Some("corpus.txt"),
+            doc_id: "corpus",
+            sent_id: 3,
+        };
+
+        assert_eq!(written(Format::Plain, &segment), "Second \"one\".");
+        assert_eq!(written(Format::Tsv, &segment), "corpus\t3\tSecond \"one\".");
+        assert_eq!(
+            written(Format::Jsonl, &segment),
+            "{\"text\":\"Second \\\"one\\\".\",\"start\":20,\"end\":33,\"line\":2,\"file\":\"corpus.txt\"}"
+        );
+        assert_eq!(
+            written(Format::Conllu, &segment),
+            "# sent_id = corpus-3\n# text = Second \"one\".\n1\tSecond\t_\t_\t_\t_\t_\t_\t_\t_\n2\t\"one\".\t_\t_\t_\t_\t_\t_\t_\t_\n\n"
+        );
+    }
+
+    #[test]
+    fn tsv_fields_escaped() {
+        let segment = Segment {
+            text: "a\tb\\c",
+            start: 0,
+            end: 5,
+            line: 1,
+            file: None,
+            doc_id: "1",
+            sent_id: 1,
+        };
+
+        assert_eq!(written(Format::Tsv, &segment), "1\t1\ta\\tb\\\\c");
+    }
+
+    #[test]
+    fn document_markers_written() {
+        let document = Document {
+            id: "news".into(),
+            file: Some("news.txt".into()),
+            ..document()
+        };
+        let options = options();
+        let marked = |options, first| {
+            let mut output = Vec::new();
+            write_document_marker(&mut output, options, "## {id}", &document, first)
+                .expect("writing to a vector succeeds");
+            String::from_utf8(output).expect("output is valid UTF-8")
+        };
+
+        assert_eq!(marked(options, true), "");
+        assert_eq!(marked(options, false), "## news\n");
+        let options = OutputOptions {
+            format: Format::Jsonl,
+            ..options
+        };
+        assert_eq!(
+            marked(options, true),
+            "{\"document\":\"news\",\"file\":\"news.txt\"}\n"
+        );
+    }
+
+    #[cfg(feature = "default-rules")]
+    #[test]
+    fn default_rules_embedded() {
+        let srx = load_default_rules().expect("embedded rules are valid");
+        let expected = load_srx(&PathBuf::from(DEFAULT_RULES_PATH)).expect("segment file is valid");
+
+        assert_eq!(srx.languages(), expected.languages());
+    }
+
+    #[test]
+    fn errors_written() {
+        let srx = load_srx(&PathBuf::from("data/segment.srx")).expect("segment file is valid");
+
+        let mut output = Vec::new();
+        write_errors(&srx, &mut output).expect("writing to a vector succeeds");
+        let output = String::from_utf8(output).expect("output is valid UTF-8");
+
+        let skipped: usize = output
+            .lines()
+            .filter_map(|line| line.split_once(": skipped "))
+            .map(|(_, count)| {
+                count
+                    .trim_end_matches(" rule(s)")
+                    .parse::<usize>()
+                    .expect("count is a number")
+            })
+            .sum();
+        assert_eq!(skipped, srx.errors().values().flatten().count());
+        assert!(output.contains("look-around"));
+    }
+
+    #[test]
+    fn breaks_annotated() {
+        let document = document();
+        let options = OutputOptions {
+            marker: Some("<sb/>"),
+            ..options()
+        };
+        let mut writer = DocumentWriter::new(&document, options);
+
+        let mut output = Vec::new();
+        writer
+            .write_line(&mut output, "First. Second.\r\n", &[0..7, 7..14])
+            .expect("writing to a vector succeeds");
+        writer
+            .write_line(&mut output, "\n", &[])
+            .expect("writing to a vector succeeds");
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "First. <sb/>Second.\r\n\n"
+        );
+    }
+
+    #[test]
+    fn structure_passed_through() {
+        let document = document();
+        let passthrough = Regex::new("^<doc ").expect("regex is valid");
+        let options = OutputOptions {
+            trim: true,
+            keep_blank_lines: true,
+            passthrough: Some(&passthrough),
+            ..options()
+        };
+        let mut writer = DocumentWriter::new(&document, options);
+
+        let mut output = Vec::new();
+        for (line, ranges) in [
+            ("<doc id=\"1\">. Not split.\n", &[0..14, 14..25][..]),
+            ("First. Second.\n", &[0..6, 6..14]),
+            ("\n", &[]),
+            ("Third. Fourth.\n", &[0..6, 6..14]),
+        ] {
+            writer
+                .write_line(&mut output, line, ranges)
+                .expect("writing to a vector succeeds");
+        }
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "<doc id=\"1\">. Not split.\nFirst.\nSecond.\n\nThird.\nFourth.\n"
+        );
+    }
+
+    #[test]
+    fn segments_null_terminated() {
+        let document = document();
+        let options = OutputOptions {
+            terminator: b'\0',
+            ..options()
+        };
+        let mut writer = DocumentWriter::new(&document, options);
+
+        let mut output = Vec::new();
+        writer
+            .write_line(&mut output, "First. Second.\n", &[0..7, 7..14])
+            .expect("writing to a vector succeeds");
+        assert_eq!(output, b"First. \0Second.\0");
+    }
+
+    #[test]
+    fn long_segments_split() {
+        let document = document();
+        let options = OutputOptions {
+            max_length: NonZeroUsize::new(12),
+            ..options()
+        };
+        let mut writer = DocumentWriter::new(&document, options);
+
+        let mut output = Vec::new();
+        writer
+            .write_line(&mut output, "Short. A longer second one.\n", &[0..7, 7..27])
+            .expect("writing to a vector succeeds");
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "Short. \nA longer \nsecond one.\n"
+        );
+    }
+
+    #[test]
+    fn segments_trimmed() {
+        let document = document();
+        let options = OutputOptions {
+            format: Format::Jsonl,
+            trim: true,
+            ..options()
+        };
+        let mut writer = DocumentWriter::new(&document, options);
+
+        let mut output = Vec::new();
+        writer
+            .write_line(&mut output, "First.  Second. \t\n", &[0..6, 6..15, 15..17])
+            .expect("writing to a vector succeeds");
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "{\"text\":\"First.\",\"start\":0,\"end\":6,\"line\":1,\"file\":null}\n\
+             {\"text\":\"Second.\",\"start\":8,\"end\":15,\"line\":1,\"file\":null}\n"
+        );
+    }
+
+    #[test]
+    fn segments_counted() {
+        let document = Document {
+            id: "corpus".into(),
+            ..document()
+        };
+        let options = OutputOptions {
+            count: Some(CountLevel::Lines),
+            ..options()
+        };
+
+        let mut output = Vec::new();
+        let mut writer = DocumentWriter::new(&document, options);
+        writer
+            .write_line(&mut output, "First. Second.\n", &[0..7, 7..14])
+            .expect("writing to a vector succeeds");
+        writer
+            .write_line(&mut output, "\n", &[])
+            .expect("writing to a vector succeeds");
+        assert_eq!(writer.finish(&mut output).ok(), Some(2));
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "corpus\t1\t2\ncorpus\t2\t0\n"
+        );
+
+        let mut output = Vec::new();
+        let mut writer = DocumentWriter::new(
+            &document,
+            OutputOptions {
+                count: Some(CountLevel::Documents),
+                ..options
+            },
+        );
+        writer
+            .write_line(&mut output, "First. Second.\n", &[0..7, 7..14])
+            .expect("writing to a vector succeeds");
+        assert_eq!(writer.finish(&mut output).ok(), Some(2));
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "corpus\t2\n"
+        );
+    }
+
+    #[test]
+    fn newlines_normalized() {
+        let document = document();
+        let options = OutputOptions {
+            marker: Some("|"),
+            newlines: Newlines::Normalize,
+            ..options()
+        };
+
+        let mut output = Vec::new();
+        let mut writer = DocumentWriter::new(&document, options);
+        writer
+            .write_line(&mut output, "One.\u{2028}Two.\r\n", &[0..4, 4..11])
+            .expect("writing to a vector succeeds");
+        let mut writer = DocumentWriter::new(
+            &document,
+            OutputOptions {
+                marker: None,
+                ..options
+            },
+        );
+        writer
+            .write_line(&mut output, "A\rB. C.\n", &[0..4, 4..7])
+            .expect("writing to a vector succeeds");
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "One.| Two.\nA B.\n C.\n"
+        );
+    }
+
+    #[test]
+    fn column_split() {
+        let document = document();
+        let options = OutputOptions {
+            format: Format::Jsonl,
+            trim: true,
+            column: Some(Column {
+                index: 1,
+                delimiter: "\t",
+                join: None,
+            }),
+            ..options()
+        };
+        let line = "7\tOne. Two.\tmeta\n";
+        let text = strip_line_ending(line);
+        assert_eq!(options.span(text), 2..11);
+
+        let mut output = Vec::new();
+        let mut writer = DocumentWriter::new(&document, options);
+        writer
+            .write_line(&mut output, line, &[0..4, 4..9])
+            .expect("writing to a vector succeeds");
+        let mut writer = DocumentWriter::new(
+            &document,
+            OutputOptions {
+                format: Format::Plain,
+                ..options
+            },
+        );
+        writer
+            .write_line(&mut output, line, &[0..4, 4..9])
+            .expect("writing to a vector succeeds");
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "{\"text\":\"One.\",\"start\":2,\"end\":6,\"line\":1,\"file\":null}\n\
+             {\"text\":\"Two.\",\"start\":7,\"end\":11,\"line\":1,\"file\":null}\n\
+             7\tOne.\tmeta\n7\tTwo.\tmeta\n"
+        );
+    }
+
+    #[test]
+    fn line_endings_stripped() {
+        assert_eq!(strip_line_ending("line\r\n"), "line");
+        assert_eq!(strip_line_ending("line\n"), "line");
+        assert_eq!(strip_line_ending("line"), "line");
+        assert_eq!(strip_line_ending("line\u{2028}"), "line");
+        assert_eq!(strip_line_ending("line\r\r\n"), "line\r");
+    }
+}
@@ -0,0 +1,504 @@
+use std::ops::Range;
+
+use clap::ValueEnum;
+use serde_crate::Deserialize;
+
+/// Elements which start a new block of text.
+const BLOCK_ELEMENTS: [&str; 36] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "title",
+    "tr",
+    "ul",
+];
+
+/// Elements whose content is not text.
+const RAW_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Formats of the input documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(crate = "serde_crate", rename_all = "lowercase")]
+pub enum InputFormat {
+    /// Plain text, split line by line.
+    Text,
+    /// HTML (or XML), the text between the tags is split.
+    Html,
+    /// Markdown, paragraphs, headings and list items are split separately and code is skipped.
+    Markdown,
+    /// JSON lines, the text field of every record is split.
+    Jsonl,
+    /// WARC web archives, every text record is split as a document (requires the `warc` feature).
+    Warc,
+}
+
+/// Maps byte offsets in extracted text to byte offsets in the original document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Offsets(
+    // offsets in the text and the original document at which a contiguous piece of text starts
+    Vec<(usize, usize)>,
+);
+
+impl Offsets {
+    /// Gets the offset in the original document of the text at the position.
+    pub fn start(&self, position: usize) -> usize {
+        self.original(position, |text| text <= position)
+    }
+
+    /// Gets the offset in the original document of the end of text ending at the position.
+    pub fn end(&self, position: usize) -> usize {
+        self.original(position, |text| text < position)
+    }
+
+    fn original(&self, position: usize, before: impl Fn(usize) -> bool) -> usize {
+        match self
+            .0
+            .partition_point(|&(text, _)| before(text))
+            .checked_sub(1)
+        {
+            Some(i) => {
+                let (text, original) = self.0[i];
+                original + (position - text)
+            }
+            None => position,
+        }
+    }
+}
+
+/// Text extracted from a document together with the offsets of the text in the document.
+#[derive(Debug, Default)]
+pub struct Extracted {
+    pub text: String,
+    pub offsets: Offsets,
+    // ranges of the text which must not be split, sorted
+    pub protected: Vec<Range<usize>>,
+    // offset in the original document continuing the last piece of text, if any
+    next: Option<usize>,
+}
+
+impl Extracted {
+    /// Appends text found at the offset in the original document.
+    fn push(&mut self, text: &str, original: usize) {
+        if self.next != Some(original) {
+            self.offsets.0.push((self.text.len(), original));
+        }
+        self.text.push_str(text);
+        self.next = Some(original + text.len());
+    }
+
+    /// Appends text replacing `length` bytes (e. g. of an entity) at the offset in the original document.
+    fn push_replaced(&mut self, text: &str, original: usize, length: usize) {
+        self.offsets.0.push((self.text.len(), original));
+        self.text.push_str(text);
+        self.next = if text.len() == length {
+            Some(original + length)
+        } else {
+            None
+        };
+    }
+
+    /// Appends a space unless the text is empty or already ends with whitespace.
+    fn push_space(&mut self, original: usize) {
+        if !self.text.is_empty() && !self.text.ends_with([' ', '\n']) {
+            self.push(" ", original);
+        }
+    }
+
+    /// Ends the current line unless the text is empty or already ends with a line break.
+    fn push_break(&mut self, original: usize) {
+        if self.text.ends_with(' ') {
+            self.text.pop();
+        }
+        if !self.text.is_empty() && !self.text.ends_with('\n') {
+            self.push_replaced("\n", original, 0);
+        }
+    }
+
+    /// Ends the last line of the text.
+    fn finish(mut self, original: usize) -> Self {
+        self.push_break(original);
+        self
+    }
+}
+
+/// Decodes the character reference (e. g. `&amp;` or `&#233;`) at the start of the text.
+/// Returns the character and the length of the reference.
+fn decode_reference(text: &str) -> Option<(char, usize)> {
+    let end = text.get(..12).unwrap_or(text).find(';')?;
+    let name = &text[1..end];
+
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        _ => {
+            let code = match name.strip_prefix('#')? {
+                hex if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16).ok()?,
+                decimal => decimal.parse().ok()?,
+            };
+            char::from_u32(code)?
+        }
+    };
+    Some((c, end + 1))
+}
+
+/// Gets the byte index after the `>` closing the tag which starts at the start of the text, ignoring `>` in quotes.
+fn tag_end(text: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Extracts the text of an HTML document. Tags and comments are removed, the content of `<script>` and `<style>`
+/// is skipped, character references are decoded and runs of whitespace are collapsed into a single space.
+/// Block elements (e. g. `<p>` or `<li>`) end lines if `break_blocks` is set, otherwise they separate text by a space.
+pub fn extract_html(html: &str, break_blocks: bool) -> Extracted {
+    let mut extracted = Extracted::default();
+    let mut position = 0;
+
+    while let Some(c) = html[position..].chars().next() {
+        let rest = &html[position..];
+
+        if c == '<' {
+            if rest.starts_with("<!--") {
+                position += rest.find("-->").map_or(rest.len(), |i| i + 3);
+                continue;
+            }
+
+            let closing = rest[1..].starts_with('/');
+            let name_start = 1 + usize::from(closing);
+            let name_length = rest[name_start..]
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(rest.len() - name_start);
+            let name = rest[name_start..name_start + name_length].to_ascii_lowercase();
+
+            if name.is_empty() && !rest[1..].starts_with(['!', '?']) {
+                // not a tag, e. g. `a < b`
+                extracted.push("<", position);
+                position += 1;
+                continue;
+            }
+
+            let end = tag_end(rest);
+            if BLOCK_ELEMENTS.contains(&name.as_str()) {
+                if break_blocks {
+                    extracted.push_break(position);
+                } else {
+                    extracted.push_space(position);
+                }
+            }
+            position += end;
+
+            if !closing && RAW_ELEMENTS.contains(&name.as_str()) {
+                let closing_tag = format!("</{}", name);
+                let rest = html[position..].to_ascii_lowercase();
+                position += rest.find(&closing_tag).unwrap_or(rest.len());
+            }
+        } else if c == '&' {
+            match decode_reference(rest) {
+                Some((decoded, length)) => {
+                    extracted.push_replaced(decoded.encode_utf8(&mut [0; 4]), position, length);
+                    position += length;
+                }
+                None => {
+                    extracted.push("&", position);
+                    position += 1;
+                }
+            }
+        } else if c.is_ascii_whitespace() {
+            extracted.push_space(position);
+            position += 1;
+        } else {
+            let length = rest
+                .find(|c: char| c == '<' || c == '&' || c.is_ascii_whitespace())
+                .unwrap_or(rest.len());
+            extracted.push(&rest[..length], position);
+            position += length;
+        }
+    }
+
+    extracted.finish(html.len())
+}
+
+/// Gets the length of the `#` marker (and the following whitespace) of an ATX heading at the start of the line.
+fn heading(line: &str) -> Option<usize> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[level..];
+    let content = rest.trim_start();
+
+    ((1..=6).contains(&level) && (rest.is_empty() || content.len() < rest.len()))
+        .then_some(line.len() - content.len())
+}
+
+/// Gets the length of the list item marker (`-`, `*`, `+`, `1.` or `1)` and the following whitespace)
+/// at the start of the line.
+fn list_marker(line: &str) -> Option<usize> {
+    let marker = match line.chars().next()? {
+        '-' | '*' | '+' => 1,
+        c if c.is_ascii_digit() => {
+            let digits = line.find(|c: char| !c.is_ascii_digit())?;
+            if !line[digits..].starts_with(['.', ')']) {
+                return None;
+            }
+            digits + 1
+        }
+        _ => return None,
+    };
+    let content = line[marker..].trim_start();
+
+    (content.len() < line.len() - marker).then_some(line.len() - content.len())
+}
+
+/// Whether the line is a thematic break (e. g. `***`) or the underline of a setext heading (e. g. `===`).
+fn is_rule(line: &str) -> bool {
+    let line = line.trim_end();
+    line.len() >= 3
+        && ['-', '=', '*', '_']
+            .iter()
+            .any(|&c| line.chars().all(|other| other == c || other == ' '))
+}
+
+/// Gets the byte ranges of inline code spans in the text: from a run of backticks to the next run of the same length.
+fn code_spans(text: &str) -> Vec<Range<usize>> {
+    let run_length = |start: usize| {
+        text[start..]
+            .find(|c| c != '`')
+            .unwrap_or(text.len() - start)
+    };
+
+    let mut spans = Vec::new();
+    let mut position = 0;
+    while let Some(start) = text[position..].find('`') {
+        let start = position + start;
+        let length = run_length(start);
+
+        let mut search = start + length;
+        position = search;
+        while let Some(i) = text[search..].find('`') {
+            let i = search + i;
+            let run = run_length(i);
+            if run == length {
+                spans.push(start..i + run);
+                position = i + run;
+                break;
+            }
+            search = i + run;
+        }
+    }
+    spans
+}
+
+/// Extracts the prose of a Markdown document. Every paragraph, heading, list item and table row becomes one line,
+/// so segments never span them. Lines of a paragraph are joined by a space and the markers of headings,
+/// list items and block quotes are removed. Fenced and indented code blocks are skipped and inline code spans
+/// are protected from being split.
+pub fn extract_markdown(markdown: &str) -> Extracted {
+    let mut extracted = Extracted::default();
+    // the fence of the open code block
+    let mut fence = None;
+    // whether the next line continues a paragraph
+    let mut paragraph = false;
+    let mut line_start = 0;
+
+    for line in markdown.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+
+        let content = line.trim_end_matches(['\n', '\r']);
+        let mut text = content.trim_start();
+        let mut offset = start + (content.len() - text.len());
+
+        if let Some(open) = fence {
+            if text.starts_with(open) {
+                fence = None;
+            }
+            continue;
+        }
+        if text.starts_with("```") || text.starts_with("~~~") {
+            extracted.push_break(start);
+            fence = Some(&text[..3]);
+            paragraph = false;
+            continue;
+        }
+        if !paragraph && !text.is_empty() && (content.starts_with('\t') || offset - start >= 4) {
+            // indented code block
+            continue;
+        }
+
+        while let Some(quoted) = text.strip_prefix('>') {
+            let quoted_text = quoted.trim_start();
+            offset += text.len() - quoted_text.len();
+            text = quoted_text;
+        }
+
+        if text.is_empty() || is_rule(text) {
+            extracted.push_break(start);
+            paragraph = false;
+            continue;
+        }
+
+        let heading = heading(text);
+        let table_row = text.starts_with('|');
+        match heading.or_else(|| list_marker(text)) {
+            Some(marker) => {
+                extracted.push_break(offset);
+                offset += marker;
+                text = &text[marker..];
+            }
+            None if table_row => extracted.push_break(offset),
+            None if paragraph => extracted.push_space(offset),
+            None => {}
+        }
+        if heading.is_some() {
+            text = text.trim_end().trim_end_matches('#').trim_end();
+        }
+
+        let position = extracted.text.len();
+        extracted.protected.extend(
+            code_spans(text)
+                .into_iter()
+                .map(|span| position + span.start..position + span.end),
+        );
+        extracted.push(text, offset);
+
+        paragraph = heading.is_none() && !table_row;
+        if !paragraph {
+            extracted.push_break(start + content.len());
+        }
+    }
+
+    extracted.finish(markdown.len())
+}
+
+/// Merges ranges which are separated by a boundary inside one of the protected ranges.
+/// The ranges start at `offset` in the text the protected ranges refer to.
+pub fn merge_protected(
+    ranges: &[Range<usize>],
+    protected: &[Range<usize>],
+    offset: usize,
+) -> Vec<Range<usize>> {
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+
+    for range in ranges {
+        let boundary = offset + range.start;
+        let i = protected.partition_point(|span| span.end <= boundary);
+        let inside = protected.get(i).is_some_and(|span| span.start < boundary);
+
+        match merged.last_mut() {
+            Some(last) if inside => last.end = range.end,
+            _ => merged.push(range.clone()),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_extracted() {
+        let html = "<html><head><title>T</title><style>p { color: red; }</style></head>\n\
+                    <body><p>Caf&eacute;? <b>Fish &amp; chips</b>.</p><!-- note -->\n\
+                    <ul><li>One.\n  Two.</li><li>a &lt; b</li></ul></body></html>";
+
+        assert_eq!(
+            extract_html(html, false).text,
+            "T Caf&eacute;? Fish & chips. One. Two. a < b\n"
+        );
+        assert_eq!(
+            extract_html(html, true).text,
+            "T\nCaf&eacute;? Fish & chips.\nOne. Two.\na < b\n"
+        );
+    }
+
+    #[test]
+    fn prose_extracted() {
+        let markdown = "# Intro #\n\nFirst line\ncontinues. Use `a. b` here.\n\n\
+                        ```rust\nlet x = 1. ;\n```\n\n    indented. code\n\n\
+                        - One. Two\n- Three\n\n> Quoted.\n\n---\n| a | b |\n";
+        let extracted = extract_markdown(markdown);
+
+        assert_eq!(
+            extracted.text,
+            "Intro\nFirst line continues. Use `a. b` here.\nOne. Two\nThree\nQuoted.\n| a | b |\n"
+        );
+        let code = extracted.text.find('`').expect("text contains code");
+        assert_eq!(extracted.protected, vec![code..code + 6]);
+
+        let three = extracted
+            .text
+            .find("Three")
+            .expect("text contains a list item");
+        assert_eq!(
+            &markdown[extracted.offsets.start(three)..extracted.offsets.end(three + 5)],
+            "Three"
+        );
+    }
+
+    #[test]
+    fn protected_ranges_merged() {
+        assert_eq!(
+            merge_protected(&[0..4, 4..9, 9..12], &[1..3, 12..16], 10),
+            vec![0..9, 9..12]
+        );
+        assert_eq!(code_spans("a ``b ` c`` `d` `e"), vec![2..11, 12..15]);
+    }
+
+    #[test]
+    fn offsets_preserved() {
+        let html = "<p>Fish &amp;  <i>chips</i>.</p>";
+        let extracted = extract_html(html, true);
+        assert_eq!(extracted.text, "Fish & chips.\n");
+
+        let chips = extracted.text.find("chips").expect("text contains chips");
+        let start = extracted.offsets.start(chips);
+        let end = extracted.offsets.end(chips + "chips".len());
+        assert_eq!(&html[start..end], "chips");
+        assert_eq!(extracted.offsets.start(0), 3);
+        assert_eq!(
+            extracted.offsets.end(extracted.text.len() - 1),
+            html.find("</p>").expect("html contains </p>")
+        );
+    }
+}
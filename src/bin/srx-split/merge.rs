@@ -0,0 +1,100 @@
+use std::{error::Error, path::Path};
+
+use crate::convert::{self, FileFormat};
+
+/// Merges the overlay SRX file into the base SRX file and writes the result.
+/// The formats of the inputs are detected from their extensions.
+pub fn merge(
+    base: &Path,
+    overlay: &Path,
+    output: &Path,
+    to: Option<FileFormat>,
+) -> Result<(), Box<dyn Error>> {
+    let to = FileFormat::resolve(to, output)?;
+    let base = convert::read_srx(base, FileFormat::resolve(None, base)?)?;
+    let overlay = convert::read_srx(overlay, FileFormat::resolve(None, overlay)?)?;
+
+    convert::write_srx(&base.merge(overlay), output, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use srx::SRX;
+
+    use super::*;
+
+    #[test]
+    fn merged_file_written() {
+        let directory = env::temp_dir().join(format!("srx-merge-{}", process::id()));
+        fs::create_dir_all(&directory).expect("temporary directory can be created");
+        let path = directory.join("merged.yaml");
+
+        let example = Path::new("data/example.srx");
+        merge(example, example, &path, None).expect("merging succeeds");
+
+        let srx: SRX =
+            serde_yaml::from_str(&fs::read_to_string(&path).expect("merged file was written"))
+                .expect("merged file is valid");
+        // identical map entries are kept once, the rules of every language are layered
+        assert_eq!(srx.language_patterns().count(), 4);
+        assert_eq!(
+            srx.language_rules("en").len(),
+            2 * SRX::from_reader(fs::File::open(example).expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en")
+                .len()
+        );
+        fs::remove_dir_all(&directory).expect("temporary directory can be removed");
+    }
+
+    #[test]
+    fn merged_xml_written_over_input() {
+        let directory = env::temp_dir().join(format!("srx-merge-xml-{}", process::id()));
+        fs::create_dir_all(&directory).expect("temporary directory can be created");
+        let (base, overlay) = (directory.join("base.srx"), directory.join("overlay.srx"));
+        fs::copy("data/example.srx", &base).expect("example file can be copied");
+        fs::write(
+            &overlay,
+            r#"<?xml version="1.0"?>
+<srx version="2.0" xmlns="http://www.lisa.org/srx20">
+    <header segmentsubflows="yes" cascade="yes"/>
+    <body>
+        <languagerules>
+            <languagerule languagerulename="English">
+                <rule break="no">
+                    <beforebreak>\sDr\.</beforebreak>
+                    <afterbreak>\s</afterbreak>
+                </rule>
+            </languagerule>
+        </languagerules>
+        <maprules>
+            <languagemap languagepattern="en.*" languagerulename="English"/>
+        </maprules>
+    </body>
+</srx>
+"#,
+        )
+        .expect("overlay can be written");
+
+        // the base is only replaced once the merged rules are written completely
+        merge(&base, &overlay, &base, None).expect("merging succeeds");
+
+        let merged = convert::read_srx(&base, FileFormat::Xml).expect("merged file is valid");
+        let text = "Ask Dr. Smith. Then go.";
+        assert_eq!(
+            merged.language_rules("en").split(text).collect::<Vec<_>>(),
+            vec!["Ask Dr. Smith.", " Then go."]
+        );
+        assert_eq!(
+            merged.language_rules("fr").split(text).collect::<Vec<_>>(),
+            vec!["Ask Dr.", " Smith.", " Then go."]
+        );
+        assert_eq!(
+            fs::read_dir(&directory).expect("directory exists").count(),
+            2
+        );
+        fs::remove_dir_all(&directory).expect("temporary directory can be removed");
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    borrow::Cow,
+    io::{self, BufRead, Read},
+};
+
+use clap::ValueEnum;
+use serde_crate::Deserialize;
+
+/// Line break characters besides `\n`.
+pub const LINE_BREAKS: [char; 4] = ['\r', '\u{85}', '\u{2028}', '\u{2029}'];
+
+/// How line breaks besides `\n` (and the `\r` of `\r\n` line endings) are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(crate = "serde_crate", rename_all = "lowercase")]
+pub enum Newlines {
+    /// Kept as they are in the segments.
+    Keep,
+    /// Written as spaces, and line endings are written as `\n`.
+    Normalize,
+    /// End lines like `\n`, so segments never span them.
+    Break,
+}
+
+/// Replaces line break characters besides `\n` with spaces.
+pub fn normalize(text: &str) -> Cow<'_, str> {
+    if text.contains(LINE_BREAKS) {
+        Cow::Owned(text.replace(LINE_BREAKS, " "))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// A reader whose lines also end at line breaks besides `\n`, `\r\n` still ends a line once.
+#[derive(Debug)]
+pub struct BreakLines<R> {
+    inner: R,
+    // the rest of the `\n`-terminated line read from the inner reader starts at `position`
+    pending: String,
+    position: usize,
+}
+
+impl<R: BufRead> BreakLines<R> {
+    pub fn new(inner: R) -> Self {
+        BreakLines {
+            inner,
+            pending: String::new(),
+            position: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Read for BreakLines<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let length = available.len().min(buf.len());
+        buf[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+        Ok(length)
+    }
+}
+
+impl<R: BufRead> BufRead for BreakLines<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position < self.pending.len() {
+            Ok(&self.pending.as_bytes()[self.position..])
+        } else {
+            self.inner.fill_buf()
+        }
+    }
+
+    fn consume(&mut self, amount: usize) {
+        if self.position < self.pending.len() {
+            self.position += amount;
+        } else {
+            self.inner.consume(amount)
+        }
+    }
+
+    fn read_line(&mut self, line: &mut String) -> io::Result<usize> {
+        if self.position >= self.pending.len() {
+            self.pending.clear();
+            self.position = 0;
+            if self.inner.read_line(&mut self.pending)? == 0 {
+                return Ok(0);
+            }
+        }
+
+        let rest = &self.pending[self.position..];
+        let mut chars = rest.char_indices().peekable();
+        let mut end = rest.len();
+        while let Some((i, c)) = chars.next() {
+            if LINE_BREAKS.contains(&c) {
+                end = match chars.peek() {
+                    Some((_, '\n')) if c == '\r' => i + 2,
+                    _ => i + c.len_utf8(),
+                };
+                break;
+            }
+        }
+
+        line.push_str(&rest[..end]);
+        self.position += end;
+        Ok(end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_broken() {
+        let mut input = BreakLines::new("a\rb\r\nc\u{2028}d\ne".as_bytes());
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        while input.read_line(&mut line).expect("reading bytes succeeds") > 0 {
+            lines.push(line.clone());
+            line.clear();
+        }
+
+        assert_eq!(lines, vec!["a\r", "b\r\n", "c\u{2028}", "d\n", "e"]);
+    }
+
+    #[test]
+    fn line_breaks_normalized() {
+        assert_eq!(normalize("a\u{2029}b\rc"), "a b c");
+        assert!(matches!(normalize("a b"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead},
+    ops::Range,
+    sync::{mpsc, Arc, Mutex, PoisonError},
+    thread,
+};
+
+use srx::Rules;
+
+use super::strip_line_ending;
+
+/// Number of lines sent to a worker at once.
+const BATCH_SIZE: usize = 1024;
+
+/// A batch of lines (including line endings) with its position in the input.
+type Batch = (usize, Vec<String>);
+
+/// Splits the lines of the input on `threads` workers and calls `write` with each line and its ranges in input order.
+/// Only the `span` of each line (without line ending) is split, the ranges are relative to it.
+///
+/// One thread reads batches of lines, the workers split them with their own [srx::Splitter] borrowing the shared
+/// rules and the calling thread reorders and writes the results. Channels are bounded so that memory usage does not
+/// grow with the input if writing is slower than splitting.
+pub fn split_lines<R, S, F>(
+    rules: &Rules,
+    input: R,
+    threads: usize,
+    span: S,
+    mut write: F,
+) -> io::Result<()>
+where
+    R: BufRead + Send,
+    S: Fn(&str) -> Range<usize> + Sync,
+    F: FnMut(&str, &[Range<usize>]) -> io::Result<()>,
+{
+    let (batch_sender, batch_receiver) = mpsc::sync_channel::<Batch>(threads * 2);
+    // owned by the workers only, so the reader stops once all workers are gone
+    let batch_receiver = Arc::new(Mutex::new(batch_receiver));
+    let (result_sender, result_receiver) = mpsc::sync_channel(threads * 2);
+
+    thread::scope(|scope| {
+        let reader = scope.spawn(move || read_batches(input, batch_sender));
+
+        for _ in 0..threads {
+            let batch_receiver = Arc::clone(&batch_receiver);
+            let result_sender = result_sender.clone();
+            let span = &span;
+
+            scope.spawn(move || {
+                let mut splitter = rules.splitter();
+
+                loop {
+                    let batch = batch_receiver
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .recv();
+                    let (index, lines) = match batch {
+                        Ok(batch) => batch,
+                        Err(_) => break,
+                    };
+
+                    let ranges: Vec<Vec<_>> = lines
+                        .iter()
+                        .map(|line| {
+                            let text = strip_line_ending(line);
+                            splitter.split_ranges(&text[span(text)]).to_vec()
+                        })
+                        .collect();
+                    if result_sender.send((index, lines, ranges)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(batch_receiver);
+        drop(result_sender);
+
+        let written = (|| {
+            // results arrive in any order, so keep them until all previous batches have been written
+            let mut pending = BTreeMap::new();
+            let mut next = 0;
+
+            for (index, lines, ranges) in &result_receiver {
+                pending.insert(index, (lines, ranges));
+
+                while let Some((lines, ranges)) = pending.remove(&next) {
+                    for (line, ranges) in lines.iter().zip(ranges.iter()) {
+                        write(line, ranges)?;
+                    }
+                    next += 1;
+                }
+            }
+            Ok(())
+        })();
+        // stops the workers and in turn the reader if writing failed
+        drop(result_receiver);
+
+        let read = reader.join().expect("reader thread does not panic");
+        written.and(read)
+    })
+}
+
+/// Reads batches of lines and sends them until the input is exhausted or nobody receives them anymore.
+fn read_batches<R: BufRead>(mut input: R, sender: mpsc::SyncSender<Batch>) -> io::Result<()> {
+    for index in 0.. {
+        let mut lines = Vec::with_capacity(BATCH_SIZE);
+
+        for _ in 0..BATCH_SIZE {
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                break;
+            }
+            lines.push(line);
+        }
+
+        if lines.is_empty() || sender.send((index, lines)).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use srx::SRX;
+
+    use super::*;
+
+    #[test]
+    fn order_preserved() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en");
+        let input: String = (0..BATCH_SIZE * 5 + 7)
+            .map(|i| format!("Line {}. Has two sentences.\n", i))
+            .collect();
+
+        let mut lines = Vec::new();
+        split_lines(
+            &rules,
+            input.as_bytes(),
+            3,
+            |text| 0..text.len(),
+            |line, ranges| {
+                lines.push((line.to_owned(), ranges.to_vec()));
+                Ok(())
+            },
+        )
+        .expect("splitting in memory succeeds");
+
+        assert_eq!(lines.len(), BATCH_SIZE * 5 + 7);
+        for ((line, ranges), expected) in lines.iter().zip(input.lines()) {
+            assert_eq!(strip_line_ending(line), expected);
+            assert_eq!(ranges, &rules.split_ranges(expected));
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::{
+    io::{self, Read},
+    time::Duration,
+};
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Creates a progress bar on stderr. Shows a bar with an ETA if the total number of bytes is known
+/// and a spinner with the number of bytes read so far otherwise.
+pub fn progress_bar(total: Option<u64>) -> ProgressBar {
+    let bar = match total {
+        Some(total) => ProgressBar::new(total).with_style(
+            ProgressStyle::with_template(
+                "{wide_bar} {bytes}/{total_bytes} ({binary_bytes_per_sec}, ETA {eta})",
+            )
+            .expect("template is valid"),
+        ),
+        None => ProgressBar::new_spinner().with_style(
+            ProgressStyle::with_template(
+                "{spinner} {bytes} read ({binary_bytes_per_sec}, {elapsed})",
+            )
+            .expect("template is valid"),
+        ),
+    };
+    bar.enable_steady_tick(Duration::from_millis(200));
+    bar
+}
+
+/// Reports the number of bytes read from the inner reader to a progress bar.
+pub struct ProgressReader<R> {
+    inner: R,
+    bar: ProgressBar,
+}
+
+impl<R> ProgressReader<R> {
+    pub fn new(inner: R, bar: ProgressBar) -> Self {
+        ProgressReader { inner, bar }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.bar.inc(read as u64);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_counted() {
+        let bar = ProgressBar::hidden();
+        let mut reader = ProgressReader::new("some text".as_bytes(), bar.clone());
+
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .expect("reading from memory succeeds");
+        assert_eq!(bar.position(), 9);
+    }
+}
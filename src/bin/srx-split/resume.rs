@@ -0,0 +1,82 @@
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Gets the path an output is written to until it is complete, i. e. the path with `.partial` appended.
+/// Outputs are renamed once they are complete, so an existing output is never a leftover of a crashed run.
+pub fn partial_path(path: &Path) -> PathBuf {
+    let mut partial = OsString::from(path);
+    partial.push(".partial");
+    PathBuf::from(partial)
+}
+
+/// Tracks which inputs of a batch job are done, to skip them when the job is run again.
+#[derive(Debug, Default)]
+pub struct State {
+    // if set, completed inputs are recorded in this file, one path per line
+    path: Option<PathBuf>,
+    completed: HashSet<PathBuf>,
+}
+
+impl State {
+    /// Reads the completed inputs from the state file, if there is one. A missing file has no completed inputs.
+    pub fn load(path: Option<&Path>) -> io::Result<Self> {
+        let completed = match path.map(fs::read_to_string) {
+            Some(Ok(content)) => content.lines().map(PathBuf::from).collect(),
+            Some(Err(error)) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+            _ => HashSet::new(),
+        };
+
+        Ok(State {
+            path: path.map(PathBuf::from),
+            completed,
+        })
+    }
+
+    /// Whether the output of the input is complete: it must exist and, with a state file, the input must be
+    /// recorded as completed.
+    pub fn is_complete(&self, input: &Path, output: &Path) -> bool {
+        output.exists() && (self.path.is_none() || self.completed.contains(input))
+    }
+
+    /// Records the input as completed, appending it to the state file right away so it survives crashes.
+    pub fn complete(&mut self, input: &Path) -> io::Result<()> {
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", input.display())?;
+        }
+        self.completed.insert(input.to_path_buf());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completed_inputs_recorded() -> io::Result<()> {
+        let dir = std::env::temp_dir().join(format!("srx-split-resume-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let state_path = dir.join("state");
+        let output = dir.join("a.sents.txt");
+        fs::write(&output, "A.\n")?;
+
+        let mut state = State::load(Some(&state_path))?;
+        assert!(!state.is_complete(Path::new("a.txt"), &output));
+        state.complete(Path::new("a.txt"))?;
+        assert!(state.is_complete(Path::new("a.txt"), &output));
+
+        let state = State::load(Some(&state_path))?;
+        assert!(state.is_complete(Path::new("a.txt"), &output));
+        assert!(!state.is_complete(Path::new("a.txt"), &dir.join("missing.txt")));
+        assert!(State::default().is_complete(Path::new("b.txt"), &output));
+        assert_eq!(partial_path(&output), dir.join("a.sents.txt.partial"));
+
+        fs::remove_dir_all(&dir)
+    }
+}
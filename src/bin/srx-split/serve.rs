@@ -0,0 +1,143 @@
+use std::{collections::HashMap, error::Error};
+
+use serde_crate::{Deserialize, Serialize};
+use srx::{Rules, SRX};
+use tiny_http::{Header, Method, Response, Server};
+
+/// Maximum number of languages whose compiled rules are kept between requests.
+const CACHE_SIZE: usize = 64;
+
+/// Body of a request to `POST /segment`.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "serde_crate", deny_unknown_fields)]
+struct SegmentRequest {
+    text: String,
+    lang: String,
+}
+
+/// A segment with its byte offsets in the text of the request.
+#[derive(Debug, Serialize)]
+#[serde(crate = "serde_crate")]
+struct Segment<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "serde_crate")]
+struct SegmentResponse<'a> {
+    segments: Vec<Segment<'a>>,
+}
+
+fn error_response(status: u16, message: &str) -> (u16, String) {
+    (status, serde_json::json!({ "error": message }).to_string())
+}
+
+/// Answers a request with its status code and JSON body.
+fn respond(
+    srx: &SRX,
+    cache: &mut HashMap<String, Rules>,
+    method: &Method,
+    url: &str,
+    body: &str,
+) -> (u16, String) {
+    if url != "/segment" {
+        return error_response(404, "not found, use POST /segment");
+    }
+    if *method != Method::Post {
+        return error_response(405, "method not allowed, use POST /segment");
+    }
+
+    let request: SegmentRequest = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(error) => return error_response(400, &error.to_string()),
+    };
+
+    // languages are chosen by clients, so the cache is bounded
+    if cache.len() >= CACHE_SIZE && !cache.contains_key(&request.lang) {
+        cache.clear();
+    }
+    let rules = cache
+        .entry(request.lang.clone())
+        .or_insert_with(|| srx.language_rules(&request.lang));
+
+    let segments = rules
+        .split_ranges(&request.text)
+        .into_iter()
+        .map(|range| Segment {
+            text: &request.text[range.clone()],
+            start: range.start,
+            end: range.end,
+        })
+        .collect();
+    let body =
+        serde_json::to_string(&SegmentResponse { segments }).expect("segments can be serialized");
+    (200, body)
+}
+
+/// Serves `POST /segment` on the address until the process is stopped. The request body is a JSON object
+/// with the `text` and the `lang` code, the response contains the `segments` with their `text` and byte offsets.
+/// Requests are handled one after another.
+pub fn serve(srx: &SRX, address: &str) -> Result<(), Box<dyn Error>> {
+    let server = Server::http(address)
+        .map_err(|error| format!("cannot listen on {}: {}", address, error))?;
+    eprintln!("srx-split: listening on http://{}", address);
+
+    let content_type = Header::from_bytes("Content-Type", "application/json")
+        .expect("content type header is valid");
+    let mut cache = HashMap::new();
+
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let (status, body) = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => respond(srx, &mut cache, request.method(), request.url(), &body),
+            Err(error) => error_response(400, &error.to_string()),
+        };
+
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        // the client going away only affects its own request
+        if let Err(error) = request.respond(response) {
+            eprintln!("srx-split: {}", error);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+
+    #[test]
+    fn requests_answered() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let mut cache = HashMap::new();
+        let mut respond = |method, url, body| respond(&srx, &mut cache, &method, url, body);
+
+        assert_eq!(
+            respond(
+                Method::Post,
+                "/segment",
+                r#"{"text": "Ask Mr. Smith. Now.", "lang": "en"}"#
+            ),
+            (
+                200,
+                r#"{"segments":[{"text":"Ask Mr. Smith.","start":0,"end":14},{"text":" Now.","start":14,"end":19}]}"#
+                    .to_owned()
+            )
+        );
+        assert_eq!(respond(Method::Get, "/segment", "").0, 405);
+        assert_eq!(respond(Method::Post, "/split", "").0, 404);
+        assert_eq!(
+            respond(Method::Post, "/segment", r#"{"text": "A."}"#).0,
+            400
+        );
+    }
+}
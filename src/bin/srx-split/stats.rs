@@ -0,0 +1,75 @@
+use std::io::{self, Write};
+
+use srx::Rules;
+
+use super::debug::{decision_name, rule_source};
+
+/// Writes how many boundaries each rule of the language produced (rules which break) or suppressed (rules which
+/// do not break) while splitting, sorted by count. Rules which never decided are omitted.
+pub fn write_rule_stats<W: Write>(lang: &str, rules: &Rules, output: &mut W) -> io::Result<()> {
+    let metrics = match rules.metrics() {
+        Some(metrics) => metrics,
+        None => return Ok(()),
+    };
+
+    let mut counts: Vec<(usize, usize)> = metrics
+        .claims()
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, count)| count > 0)
+        .collect();
+    // stable, so rules with the same count stay in rule order
+    counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    writeln!(
+        output,
+        "{}: {} of {} rule(s) decided {} position(s)",
+        lang,
+        counts.len(),
+        rules.len(),
+        counts.iter().map(|&(_, count)| count).sum::<usize>()
+    )?;
+    for (rule, count) in counts {
+        writeln!(
+            output,
+            "{:>10} {:<8} rule {}: {}",
+            count,
+            decision_name(rules.rule_breaks(rule).unwrap_or_default()),
+            rule,
+            rule_source(rules, rule)
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use srx::SRX;
+
+    use super::*;
+
+    #[test]
+    fn rules_sorted_by_count() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en")
+                .with_metrics();
+        rules.split_ranges("Hello. Mr. Smith is here. Bye.");
+
+        let mut output = Vec::new();
+        write_rule_stats("en", &rules, &mut output).expect("writing to a vector succeeds");
+        let output = String::from_utf8(output).expect("output is valid UTF-8");
+
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("en: 2 of "));
+        assert!(lines[0].ends_with("decided 3 position(s)"));
+        assert!(lines[1].trim_start().starts_with("2 break"));
+        assert!(lines[2].trim_start().starts_with("1 no break"));
+    }
+}
@@ -0,0 +1,70 @@
+use std::{error::Error, io::Write, path::Path, str::FromStr};
+
+use srx::{testkit::TestSuite, SRX};
+
+/// Runs the test suite (see [srx::testkit]) with the rules and writes the failing cases with the breaks which are
+/// missing or unexpected, followed by the number of passed and failed cases. Suites in files ending in `.txt` are in
+/// the line format, all others in YAML.
+///
+/// Returns the number of failed cases.
+pub fn run<W: Write>(
+    srx: &SRX,
+    path: &Path,
+    suite: &str,
+    output: &mut W,
+) -> Result<usize, Box<dyn Error>> {
+    let suite: TestSuite = if path.extension().is_some_and(|extension| extension == "txt") {
+        TestSuite::from_str(suite)?
+    } else {
+        serde_yaml::from_str(suite)?
+    };
+
+    let report = suite.run(srx);
+    write!(output, "{}", report)?;
+    Ok(report.failed())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn failures_written() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let suite = r#"
+en:
+  - input: "Ask Mr. Smith. Now."
+    segments: ["Ask Mr. Smith.", " Now."]
+  - name: wrong
+    input: "One. Two."
+    segments: ["One. Two."]
+fr:
+  - input: "Oui."
+    segments: ["Non."]
+"#;
+
+        let mut output = Vec::new();
+        let failed =
+            run(&srx, Path::new("suite.yaml"), suite, &mut output).expect("suite is valid");
+        assert_eq!(failed, 2);
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "FAIL en 2 (wrong)\n  unexpected break at 4: \"One.| Two.\"\n    rule 5: [\\.\\?!]+(\\s)\n\
+             INVALID fr 1: segments do not add up to the input\n1 passed, 2 failed\n"
+        );
+
+        let mut output = Vec::new();
+        let failed = run(
+            &srx,
+            Path::new("suite.txt"),
+            "[en]\nAsk Mr. Smith.| Now.",
+            &mut output,
+        )
+        .expect("suite is valid");
+        assert_eq!(failed, 0);
+    }
+}
@@ -0,0 +1,56 @@
+use std::path::{Path, PathBuf};
+
+/// Derives the output path of an input from a template like `{dir}/{stem}.sents{ext}`:
+///
+/// - `{dir}` is the directory of the input (`.` if it has none),
+/// - `{stem}` is the file name up to the first `.` after its start (i. e. the file name without extensions),
+/// - `{ext}` is the rest of the file name, e. g. `.txt` or `.txt.gz`, so outputs are compressed like the inputs.
+pub fn output_path(template: &str, input: &Path) -> PathBuf {
+    let dir = match input.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_string_lossy(),
+        _ => ".".into(),
+    };
+    let name = input
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    // a leading `.` (as in `.hidden`) is part of the stem
+    let split = name[1.min(name.len())..]
+        .find('.')
+        .map_or(name.len(), |index| index + 1);
+    let (stem, ext) = name.split_at(split);
+
+    PathBuf::from(
+        template
+            .replace("{dir}", &dir)
+            .replace("{stem}", stem)
+            .replace("{ext}", ext),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_replaced() {
+        let template = "{dir}/{stem}.sents{ext}";
+
+        assert_eq!(
+            output_path(template, Path::new("corpus/news.txt")),
+            Path::new("corpus/news.sents.txt")
+        );
+        assert_eq!(
+            output_path(template, Path::new("news.txt.gz")),
+            Path::new("./news.sents.txt.gz")
+        );
+        assert_eq!(
+            output_path(template, Path::new("/data/.hidden")),
+            Path::new("/data/.hidden.sents")
+        );
+        assert_eq!(
+            output_path("out/{stem}.jsonl", Path::new("a/b/README")),
+            Path::new("out/README.jsonl")
+        );
+    }
+}
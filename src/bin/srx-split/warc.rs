@@ -0,0 +1,172 @@
+use std::io::{self, BufRead, Read};
+
+/// A record of a WARC file containing text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// The `WARC-Target-URI` of the record, or its `WARC-Record-ID` if it has none.
+    pub id: String,
+    /// The content block, invalid UTF-8 is replaced.
+    pub text: String,
+}
+
+/// Iterator over the text records of a WARC file, created by [text_records].
+pub struct Records<R> {
+    reader: R,
+}
+
+/// Reads the records of a WARC file (e. g. as written by web crawlers) which contain text: `conversion` records
+/// (text extracted from other records) and `resource` and `response` records with a `Content-Type` of `text/plain`.
+/// Other records are skipped. Compressed files must be decompressed by the reader.
+pub fn text_records<R: BufRead>(reader: R) -> Records<R> {
+    Records { reader }
+}
+
+/// Header fields of a record as pairs of lowercase names and values.
+type Fields = Vec<(String, String)>;
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl<R: BufRead> Records<R> {
+    /// Reads the next record as its header fields (with lowercase names) and content block.
+    fn next_record(&mut self) -> io::Result<Option<(Fields, Vec<u8>)>> {
+        let mut line = String::new();
+        // records are separated by empty lines
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+        if !line.starts_with("WARC/") {
+            return Err(invalid(format!(
+                "expected a WARC record, found {:?}",
+                line.trim_end()
+            )));
+        }
+
+        let mut fields = Vec::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                fields.push((name.trim().to_lowercase(), value.trim().to_owned()));
+            }
+        }
+
+        let length: u64 = field(&fields, "content-length")
+            .and_then(|length| length.parse().ok())
+            .ok_or_else(|| invalid("WARC record without a valid Content-Length".into()))?;
+        let mut content = Vec::new();
+        (&mut self.reader).take(length).read_to_end(&mut content)?;
+        if (content.len() as u64) < length {
+            return Err(invalid("WARC record is truncated".into()));
+        }
+
+        Ok(Some((fields, content)))
+    }
+}
+
+fn field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(field, _)| field == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn is_text(fields: &[(String, String)]) -> bool {
+    match field(fields, "warc-type") {
+        Some("conversion") => true,
+        Some("resource") | Some("response") => field(fields, "content-type")
+            .is_some_and(|content_type| content_type.starts_with("text/plain")),
+        _ => false,
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (fields, content) = match self.next_record() {
+                Ok(Some(record)) => record,
+                Ok(None) => return None,
+                Err(error) => return Some(Err(error)),
+            };
+            if !is_text(&fields) {
+                continue;
+            }
+
+            let id = field(&fields, "warc-target-uri")
+                .or_else(|| field(&fields, "warc-record-id"))
+                .unwrap_or_default()
+                .to_owned();
+            return Some(Ok(Record {
+                id,
+                text: String::from_utf8_lossy(&content).into_owned(),
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(kind: &str, content_type: &str, content: &str) -> String {
+        format!(
+            "WARC/1.0\r\nWARC-Type: {}\r\nWARC-Target-URI: https://example.com/{}\r\nWARC-Record-ID: <urn:uuid:1>\r\n\
+             Content-Type: {}\r\nContent-Length: {}\r\n\r\n{}\r\n\r\n",
+            kind,
+            kind,
+            content_type,
+            content.len(),
+            content
+        )
+    }
+
+    #[test]
+    fn text_records_read() {
+        let warc = [
+            record(
+                "warcinfo",
+                "application/warc-fields",
+                "software: crawler\r\n",
+            ),
+            record("response", "text/html", "<p>Hello.</p>"),
+            record("conversion", "text/plain", "Hello. Bye.\nSecond line."),
+            record("resource", "text/plain; charset=utf-8", "Text."),
+        ]
+        .concat();
+
+        let records: Vec<_> = text_records(warc.as_bytes())
+            .collect::<io::Result<_>>()
+            .expect("records are valid");
+        assert_eq!(
+            records,
+            vec![
+                Record {
+                    id: "https://example.com/conversion".into(),
+                    text: "Hello. Bye.\nSecond line.".into()
+                },
+                Record {
+                    id: "https://example.com/resource".into(),
+                    text: "Text.".into()
+                },
+            ]
+        );
+
+        assert!(
+            text_records("WARC/1.0\r\nWARC-Type: conversion\r\n\r\n".as_bytes())
+                .next()
+                .expect("record is read")
+                .is_err()
+        );
+    }
+}
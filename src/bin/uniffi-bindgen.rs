@@ -0,0 +1,4 @@
+//! `uniffi-bindgen`: generates the Swift and Kotlin bindings of the `uniffi` feature, see the `mobile` module.
+fn main() {
+    uniffi::uniffi_bindgen_main()
+}
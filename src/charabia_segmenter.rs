@@ -0,0 +1,55 @@
+use charabia::segmenter::Segmenter;
+
+use super::Rules;
+
+/// A segmenter for [`charabia`](https://docs.rs/charabia), the tokenizer of Meilisearch, which splits at the
+/// segment boundaries found by [Rules], e. g. to index the sentences of documents with language-specific rules.
+///
+/// Segments keep their whitespace, so they are usually tokenized into words afterwards.
+#[derive(Debug, Clone)]
+pub struct CharabiaSegmenter {
+    rules: Rules,
+}
+
+impl CharabiaSegmenter {
+    pub fn new(rules: Rules) -> Self {
+        CharabiaSegmenter { rules }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+}
+
+impl Segmenter for CharabiaSegmenter {
+    fn segment_str<'o>(&self, s: &'o str) -> Box<dyn Iterator<Item = &'o str> + 'o> {
+        Box::new(
+            self.rules
+                .split_ranges(s)
+                .into_iter()
+                .map(move |range| &s[range]),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+    use crate::SRX;
+
+    #[test]
+    fn sentences_segmented() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let segmenter: Box<dyn Segmenter> =
+            Box::new(CharabiaSegmenter::new(srx.language_rules("en")));
+
+        assert_eq!(
+            segmenter.segment_str("Hello. Bye.").collect::<Vec<_>>(),
+            vec!["Hello.", " Bye."]
+        );
+    }
+}
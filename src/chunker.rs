@@ -0,0 +1,123 @@
+use std::{fmt, ops::Range};
+
+use super::SentenceSplitter;
+
+/// Groups the sentences of a text into chunks of at most a maximum size, e. g. to embed documents for retrieval.
+/// Chunks never end inside a sentence, so a sentence longer than the maximum is a chunk of its own.
+///
+/// The size is measured in characters by default, [Chunker::with_size] measures it with another function
+/// such as the number of tokens of a tokenizer.
+///
+/// ```
+/// # #[cfg(feature = "from_xml")]
+/// # fn main() -> Result<(), srx::Error> {
+/// use std::{fs, str::FromStr};
+/// use srx::{Chunker, SRX};
+///
+/// let srx = SRX::from_str(&fs::read_to_string("data/example.srx").unwrap())?;
+/// let chunker = Chunker::new(srx.language_rules("en"), 12);
+///
+/// assert_eq!(chunker.chunks("One. Two. Three. Four."), vec!["One. Two.", " Three.", " Four."]);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "from_xml"))]
+/// # fn main() {}
+/// ```
+pub struct Chunker<S> {
+    splitter: S,
+    max_size: usize,
+    size: Box<dyn Fn(&str) -> usize + Send + Sync>,
+}
+
+impl<S: SentenceSplitter> Chunker<S> {
+    /// Creates a chunker with chunks of at most `max_size` characters.
+    pub fn new(splitter: S, max_size: usize) -> Self {
+        Chunker {
+            splitter,
+            max_size,
+            size: Box::new(|text| text.chars().count()),
+        }
+    }
+
+    /// Measures the size of chunks with the function instead of counting characters.
+    /// The function is called with the text of each candidate chunk, so it does not need to be additive.
+    pub fn with_size<F: Fn(&str) -> usize + Send + Sync + 'static>(mut self, size: F) -> Self {
+        self.size = Box::new(size);
+        self
+    }
+
+    /// The splitter used to find the sentences.
+    pub fn splitter(&self) -> &S {
+        &self.splitter
+    }
+
+    /// Gets the byte ranges of the chunks. The ranges are sorted, at sentence bounds and cover the whole text.
+    pub fn chunk_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut chunks: Vec<Range<usize>> = Vec::new();
+
+        for sentence in self.splitter.sentence_ranges(text) {
+            match chunks.last_mut() {
+                // the sentence is added to the current chunk if the chunk stays within the maximum size
+                Some(chunk) if (self.size)(&text[chunk.start..sentence.end]) <= self.max_size => {
+                    chunk.end = sentence.end;
+                }
+                _ => chunks.push(sentence),
+            }
+        }
+
+        chunks
+    }
+
+    /// Splits the text into chunks.
+    pub fn chunks<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        self.chunk_ranges(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for Chunker<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Chunker")
+            .field("splitter", &self.splitter)
+            .field("max_size", &self.max_size)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+    use crate::SRX;
+
+    #[test]
+    fn chunks_within_budget() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let rules = srx.language_rules("en");
+        let text = "A short one. This sentence is longer than the budget. Tiny. Small.";
+
+        let chunker = Chunker::new(&rules, 20);
+        assert_eq!(
+            chunker.chunks(text),
+            vec![
+                "A short one.",
+                " This sentence is longer than the budget.",
+                " Tiny. Small."
+            ]
+        );
+        assert_eq!(chunker.chunks(text).concat(), text);
+
+        // with a word count as size
+        let chunker = Chunker::new(&rules, 2).with_size(|text| text.split_whitespace().count());
+        assert_eq!(
+            chunker.chunks("One. Two. Three."),
+            vec!["One. Two.", " Three."]
+        );
+        assert!(chunker.chunks("").is_empty());
+    }
+}
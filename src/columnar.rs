@@ -0,0 +1,127 @@
+use std::convert::TryFrom;
+
+use super::Rules;
+
+/// Segments of many documents in the layout of Arrow (and Polars) arrays. Created by [Rules::split_offsets]
+/// and [Rules::split_columnar].
+///
+/// The segments are an Arrow `LargeUtf8` array with `segments` as offsets into the values buffer of the documents,
+/// and `documents` are the offsets of a `LargeList` array into the segments. So the segments of document `i` are
+/// `values[segments[j]..segments[j + 1]]` for `j` in `documents[i]..documents[i + 1]`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SegmentOffsets {
+    /// Offsets of the segments into the values, one more than there are segments.
+    pub segments: Vec<i64>,
+    /// Offsets of the documents into the segments, one more than there are documents.
+    pub documents: Vec<i64>,
+}
+
+impl SegmentOffsets {
+    /// The number of documents.
+    pub fn len(&self) -> usize {
+        self.documents.len().saturating_sub(1)
+    }
+
+    /// Whether there are no documents.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn to_offset(index: usize) -> i64 {
+    i64::try_from(index).expect("offsets fit into i64")
+}
+
+impl Rules {
+    /// Splits the documents of an Arrow `LargeUtf8` array given as its values buffer and offsets, without copying any text.
+    /// The segments share the values buffer of the documents, see [SegmentOffsets].
+    ///
+    /// A null document is represented by equal offsets and has no segments.
+    ///
+    /// # Panics
+    ///
+    /// If `offsets` are negative, decreasing, out of bounds of `values` or not at character boundaries.
+    pub fn split_offsets(&self, values: &str, offsets: &[i64]) -> SegmentOffsets {
+        let mut splitter = self.splitter();
+        let mut segments = Vec::with_capacity(offsets.len());
+        let mut documents = Vec::with_capacity(offsets.len());
+
+        if let Some(&first) = offsets.first() {
+            segments.push(first);
+            documents.push(0);
+        }
+
+        for window in offsets.windows(2) {
+            let [start, end] = [window[0], window[1]]
+                .map(|offset| usize::try_from(offset).expect("offsets are not negative"));
+
+            segments.extend(
+                splitter
+                    .split_ranges(&values[start..end])
+                    .iter()
+                    .map(|range| to_offset(start + range.end)),
+            );
+            documents.push(to_offset(segments.len() - 1));
+        }
+
+        SegmentOffsets {
+            segments,
+            documents,
+        }
+    }
+
+    /// Splits many texts and gets the concatenated texts as values buffer together with the offsets of the segments,
+    /// e. g. to build the arrays of a Polars UDF without allocating a string for each segment.
+    pub fn split_columnar<S: AsRef<str>>(&self, texts: &[S]) -> (String, SegmentOffsets) {
+        let mut values = String::with_capacity(texts.iter().map(|text| text.as_ref().len()).sum());
+        let mut offsets = Vec::with_capacity(texts.len() + 1);
+        offsets.push(0);
+
+        for text in texts {
+            values.push_str(text.as_ref());
+            offsets.push(to_offset(values.len()));
+        }
+
+        let offsets = self.split_offsets(&values, &offsets);
+        (values, offsets)
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use crate::SRX;
+
+    #[test]
+    fn offsets_match_split() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let rules = srx.language_rules("en");
+        let texts = ["Hello. Bye.", "", "Größe. Ja."];
+
+        let (values, offsets) = rules.split_columnar(&texts);
+        assert_eq!(offsets.len(), texts.len());
+        assert_eq!(offsets.documents, vec![0, 2, 2, 4]);
+
+        for (i, text) in texts.iter().enumerate() {
+            let segments: Vec<&str> = (offsets.documents[i]..offsets.documents[i + 1])
+                .map(|j| {
+                    let j = j as usize;
+                    &values[offsets.segments[j] as usize..offsets.segments[j + 1] as usize]
+                })
+                .collect();
+            assert_eq!(segments, rules.split(text).collect::<Vec<_>>());
+        }
+
+        // a sliced array, whose offsets do not start at zero
+        assert_eq!(
+            rules.split_offsets(&values, &[11, 11, 23]),
+            super::SegmentOffsets {
+                segments: vec![11, 19, 23],
+                documents: vec![0, 0, 2],
+            }
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt;
+
+use super::SRX;
+
+/// How far a behavior of the SRX 2.0 spec is supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Support {
+    Supported,
+    /// Supported with deviations, see [Behavior::note].
+    Partial,
+    /// Ignored when loading or splitting.
+    Unsupported,
+}
+
+impl fmt::Display for Support {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Support::Supported => "supported",
+            Support::Partial => "partial",
+            Support::Unsupported => "unsupported",
+        })
+    }
+}
+
+/// A behavior of the SRX 2.0 spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Behavior {
+    pub name: &'static str,
+    pub support: Support,
+    /// How the behavior is (not) implemented.
+    pub note: String,
+}
+
+/// Which behaviors of the SRX 2.0 spec this build supports. Created by [SRX::compliance_report].
+/// Displayed as one line per behavior.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplianceReport {
+    pub behaviors: Vec<Behavior>,
+}
+
+impl ComplianceReport {
+    /// Whether every behavior is fully supported.
+    pub fn is_compliant(&self) -> bool {
+        self.behaviors
+            .iter()
+            .all(|behavior| behavior.support == Support::Supported)
+    }
+}
+
+impl fmt::Display for ComplianceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for behavior in &self.behaviors {
+            writeln!(
+                f,
+                "{}: {} ({})",
+                behavior.name, behavior.support, behavior.note
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl SRX {
+    /// Summarizes which behaviors of the SRX 2.0 spec are supported when splitting with these rules.
+    /// The cases of `data/conformance.txt` check the supported behaviors.
+    pub fn compliance_report(&self) -> ComplianceReport {
+        let skipped: usize = self.errors.values().map(Vec::len).sum();
+
+        let behavior = |name, support, note: &str| Behavior {
+            name,
+            support,
+            note: note.to_owned(),
+        };
+
+        ComplianceReport {
+            behaviors: vec![
+                behavior(
+                    "rule order",
+                    Support::Supported,
+                    "the first rule matching at a position decides whether to break",
+                ),
+                behavior(
+                    "cascade",
+                    Support::Supported,
+                    if self.cascade {
+                        "the rules of all matching <languagemap>s apply in order"
+                    } else {
+                        "only the rules of the first matching <languagemap> apply"
+                    },
+                ),
+                behavior(
+                    "overlapping matches",
+                    Support::Partial,
+                    "overlapping matches of the same rule are not found",
+                ),
+                Behavior {
+                    name: "regular expressions",
+                    support: if skipped == 0 {
+                        Support::Supported
+                    } else {
+                        Support::Partial
+                    },
+                    note: format!(
+                        "the syntax of the `regex` crate is used, {} rule(s) with unsupported syntax (e. g. look-around) are skipped",
+                        skipped
+                    ),
+                },
+                behavior(
+                    "formathandle",
+                    Support::Unsupported,
+                    "only plaintext is split, <formathandle> is ignored",
+                ),
+                behavior(
+                    "segmentsubflows",
+                    Support::Unsupported,
+                    "only plaintext is split, segmentsubflows is ignored",
+                ),
+            ],
+        }
+    }
+}
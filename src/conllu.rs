@@ -0,0 +1,61 @@
+//! Output of segments as sentences in the [CoNLL-U](https://universaldependencies.org/format.html) format,
+//! e. g. to hand them to a parser like UDPipe.
+//!
+//! Every sentence is a block with its `# sent_id` and `# text` followed by one line per token. Tokens are only
+//! separated at whitespace and all other fields are empty (`_`), so tools which tokenize themselves should use
+//! the `# text` of the sentence.
+use std::io::{self, Write};
+
+/// Writes the comment starting a new document, `# newdoc id = <id>`. Written before the first sentence of the document.
+pub fn write_document<W: Write + ?Sized>(output: &mut W, id: &str) -> io::Result<()> {
+    writeln!(output, "# newdoc id = {}", single_line(id))
+}
+
+/// Writes a sentence as a block of comments and token lines, followed by the blank line ending the block.
+///
+/// Leading and trailing whitespace of the text is removed and line breaks are replaced by spaces since
+/// `# text` must be on a single line. Nothing is written if the text is only whitespace since sentences must
+/// have at least one token.
+pub fn write_sentence<W: Write + ?Sized>(output: &mut W, id: &str, text: &str) -> io::Result<()> {
+    let text = single_line(text.trim());
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(output, "# sent_id = {}", single_line(id))?;
+    writeln!(output, "# text = {}", text)?;
+    for (i, token) in text.split_whitespace().enumerate() {
+        writeln!(output, "{}\t{}\t_\t_\t_\t_\t_\t_\t_\t_", i + 1, token)?;
+    }
+    writeln!(output)
+}
+
+/// Replaces line breaks with spaces.
+fn single_line(text: &str) -> String {
+    text.split(['\r', '\n']).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sentences_written() {
+        let mut output = Vec::new();
+        write_document(&mut output, "doc").expect("writing to a vector succeeds");
+        write_sentence(&mut output, "doc-1", " Hello  big\nworld. ")
+            .expect("writing to a vector succeeds");
+        write_sentence(&mut output, "doc-2", " \n").expect("writing to a vector succeeds");
+
+        assert_eq!(
+            String::from_utf8(output).expect("output is valid UTF-8"),
+            "# newdoc id = doc\n\
+             # sent_id = doc-1\n\
+             # text = Hello  big world.\n\
+             1\tHello\t_\t_\t_\t_\t_\t_\t_\t_\n\
+             2\tbig\t_\t_\t_\t_\t_\t_\t_\t_\n\
+             3\tworld.\t_\t_\t_\t_\t_\t_\t_\t_\n\
+             \n"
+        );
+    }
+}
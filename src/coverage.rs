@@ -0,0 +1,143 @@
+use std::fmt;
+
+use super::{trace::context, Rules};
+
+/// How one rule fared on a corpus. Part of a [Coverage].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleCoverage {
+    /// The index of the rule.
+    pub rule: usize,
+    /// The pattern of the rule, see [Rules::rule_pattern].
+    pub pattern: String,
+    /// Whether the rule splits the text where it decides.
+    pub do_break: bool,
+    /// The number of positions the rule decided, i. e. breaks it produced or suppressed.
+    pub decided: usize,
+    /// The number of positions the rule matched but an earlier rule decided.
+    pub shadowed: usize,
+    /// The contexts of the first decisions of the rule, with a `|` at the position.
+    pub examples: Vec<String>,
+}
+
+impl RuleCoverage {
+    /// Whether the rule never decided, so removing it would not change the segmentation of the corpus.
+    pub fn is_dead(&self) -> bool {
+        self.decided == 0
+    }
+}
+
+/// How often each rule produced or suppressed a break on a corpus, with example contexts.
+/// Created by [Rules::coverage] to find rules which are dead or decide more often than expected.
+///
+/// Displayed as one line per rule with its counts and pattern, followed by its examples.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    /// The coverage of each rule, in rule order.
+    pub rules: Vec<RuleCoverage>,
+}
+
+impl Coverage {
+    /// The rules which never decided.
+    pub fn dead(&self) -> impl Iterator<Item = &RuleCoverage> {
+        self.rules.iter().filter(|rule| rule.is_dead())
+    }
+
+    /// The rules sorted by the number of positions they decided, most first.
+    pub fn by_decisions(&self) -> Vec<&RuleCoverage> {
+        let mut rules: Vec<_> = self.rules.iter().collect();
+        rules.sort_by_key(|rule| std::cmp::Reverse(rule.decided));
+        rules
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rule in &self.rules {
+            writeln!(
+                f,
+                "rule {} ({}): {} decided, {} shadowed: {}",
+                rule.rule,
+                if rule.do_break { "break" } else { "no break" },
+                rule.decided,
+                rule.shadowed,
+                rule.pattern
+            )?;
+            for example in &rule.examples {
+                writeln!(f, "    \"{}\"", example)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Rules {
+    /// Runs the rules on a corpus and reports for each rule how many breaks it produced or suppressed
+    /// and up to `max_examples` contexts of its decisions.
+    ///
+    /// Like [Rules::trace], this evaluates every rule at every position, so it is slower than splitting.
+    pub fn coverage<'a, I: IntoIterator<Item = &'a str>>(
+        &self,
+        texts: I,
+        max_examples: usize,
+    ) -> Coverage {
+        let mut rules: Vec<RuleCoverage> = (0..self.len())
+            .map(|rule| RuleCoverage {
+                rule,
+                pattern: self.rules[rule].regex.as_str().to_owned(),
+                do_break: self.rules[rule].do_break(),
+                decided: 0,
+                shadowed: 0,
+                examples: Vec::new(),
+            })
+            .collect();
+
+        for text in texts {
+            for decision in self.trace(text) {
+                let rule = &mut rules[decision.rule];
+                rule.decided += 1;
+                if rule.examples.len() < max_examples {
+                    rule.examples.push(context(text, decision.position));
+                }
+
+                for shadowed in decision.shadowed {
+                    rules[shadowed].shadowed += 1;
+                }
+            }
+        }
+
+        Coverage { rules }
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use crate::SRX;
+
+    #[test]
+    fn decisions_counted() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let rules = srx.language_rules("en");
+        let texts = ["Hello. Bye.", "Ask Mr. Smith. Now."];
+
+        let coverage = rules.coverage(texts, 1);
+        assert_eq!(coverage.rules.len(), rules.len());
+        assert_eq!(
+            coverage
+                .rules
+                .iter()
+                .map(|rule| rule.decided)
+                .collect::<Vec<_>>(),
+            rules.profile(texts).claims()
+        );
+
+        let breaking = coverage.by_decisions()[0];
+        assert!(breaking.do_break);
+        assert_eq!(breaking.examples, vec!["Hello.| Bye."]);
+        assert!(coverage.dead().count() < rules.len());
+        assert!(coverage.to_string().starts_with("rule 0 ("));
+    }
+}
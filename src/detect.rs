@@ -0,0 +1,182 @@
+use whatlang::Lang;
+
+use super::{Rules, SRX};
+
+/// Number of bytes at the start of a text which [detect_language] uses to detect its language.
+pub const DETECTION_SAMPLE_SIZE: usize = 4096;
+
+/// Language code used if the language can not be detected, resolves to the default rules of most SRX files.
+pub const UNDETERMINED: &str = "und";
+
+/// Gets the ISO 639-1 code of the language, which is what `<languagemap>` patterns usually match.
+fn iso_639_1(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Epo => "eo",
+        Lang::Eng => "en",
+        Lang::Rus => "ru",
+        Lang::Cmn => "zh",
+        Lang::Spa => "es",
+        Lang::Por => "pt",
+        Lang::Ita => "it",
+        Lang::Ben => "bn",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Ukr => "uk",
+        Lang::Kat => "ka",
+        Lang::Ara => "ar",
+        Lang::Hin => "hi",
+        Lang::Jpn => "ja",
+        Lang::Heb => "he",
+        Lang::Yid => "yi",
+        Lang::Pol => "pl",
+        Lang::Amh => "am",
+        Lang::Jav => "jv",
+        Lang::Kor => "ko",
+        Lang::Nob => "nb",
+        Lang::Dan => "da",
+        Lang::Swe => "sv",
+        Lang::Fin => "fi",
+        Lang::Tur => "tr",
+        Lang::Nld => "nl",
+        Lang::Hun => "hu",
+        Lang::Ces => "cs",
+        Lang::Ell => "el",
+        Lang::Bul => "bg",
+        Lang::Bel => "be",
+        Lang::Mar => "mr",
+        Lang::Kan => "kn",
+        Lang::Ron => "ro",
+        Lang::Slv => "sl",
+        Lang::Hrv => "hr",
+        Lang::Srp => "sr",
+        Lang::Mkd => "mk",
+        Lang::Lit => "lt",
+        Lang::Lav => "lv",
+        Lang::Est => "et",
+        Lang::Tam => "ta",
+        Lang::Vie => "vi",
+        Lang::Urd => "ur",
+        Lang::Tha => "th",
+        Lang::Guj => "gu",
+        Lang::Uzb => "uz",
+        Lang::Pan => "pa",
+        Lang::Aze => "az",
+        Lang::Ind => "id",
+        Lang::Tel => "te",
+        Lang::Pes => "fa",
+        Lang::Mal => "ml",
+        Lang::Ori => "or",
+        Lang::Mya => "my",
+        Lang::Nep => "ne",
+        Lang::Sin => "si",
+        Lang::Khm => "km",
+        Lang::Tuk => "tk",
+        Lang::Aka => "ak",
+        Lang::Zul => "zu",
+        Lang::Sna => "sn",
+        Lang::Afr => "af",
+        Lang::Lat => "la",
+        Lang::Slk => "sk",
+        Lang::Cat => "ca",
+        Lang::Tgl => "tl",
+        Lang::Hye => "hy",
+    }
+}
+
+/// Detects the language of the text from its first few kilobytes.
+/// Gets its ISO 639-1 code, or `und` if it can not be detected.
+pub fn detect_language(text: &str) -> &'static str {
+    let mut end = text.len().min(DETECTION_SAMPLE_SIZE);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    whatlang::detect_lang(&text[..end]).map_or(UNDETERMINED, iso_639_1)
+}
+
+impl SRX {
+    /// Detects the language of the text (see [detect_language]) and gets the rules for it,
+    /// resolved via the `<languagemap>` like [SRX::language_rules].
+    pub fn language_rules_auto(&self, text: &str) -> Rules {
+        self.language_rules(detect_language(text))
+    }
+}
+
+impl Rules {
+    /// Split text into segments and detect the language of each segment (see [detect_language]),
+    /// e. g. to route the segments of documents which switch between languages.
+    ///
+    /// Every segment is detected on its own, so short segments are often `und` or detected wrongly.
+    pub fn split_with_languages<'t, 'b>(
+        &self,
+        text: &'t str,
+    ) -> impl Iterator<Item = (&'t str, &'static str)> + 'b
+    where
+        't: 'b,
+    {
+        self.split(text)
+            .map(|segment| (segment, detect_language(segment)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn languages_detected() {
+        assert_eq!(
+            detect_language("This is a sentence in English. It should be detected as such."),
+            "en"
+        );
+        assert_eq!(
+            detect_language("Das ist ein Satz auf Deutsch. Er sollte als solcher erkannt werden."),
+            "de"
+        );
+        assert_eq!(detect_language(""), UNDETERMINED);
+        assert_eq!(
+            detect_language(&"ü".repeat(DETECTION_SAMPLE_SIZE)),
+            detect_language("ü")
+        );
+    }
+
+    #[cfg(feature = "from_xml")]
+    #[test]
+    fn rules_resolved() {
+        use std::{fs, str::FromStr};
+
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let text = "Ceci est une phrase en français. Elle devrait être reconnue comme telle.";
+
+        assert_eq!(
+            srx.language_rules_auto(text).len(),
+            srx.language_rules("fr").len()
+        );
+        assert_ne!(
+            srx.language_rules_auto(text).len(),
+            srx.language_rules("en").len()
+        );
+    }
+
+    #[cfg(feature = "from_xml")]
+    #[test]
+    fn segment_languages_detected() {
+        use std::{fs, str::FromStr};
+
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let text = "This is a sentence in English, it should be detected as such. \
+                    Das ist ein Satz auf Deutsch, er sollte als solcher erkannt werden.";
+
+        assert_eq!(
+            srx.language_rules("en")
+                .split_with_languages(text)
+                .map(|(_, lang)| lang)
+                .collect::<Vec<_>>(),
+            vec!["en", "de"]
+        );
+    }
+}
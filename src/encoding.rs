@@ -0,0 +1,136 @@
+use std::io::{self, Chain, Cursor, Read};
+
+pub use encoding_rs::Encoding;
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
+
+/// Number of bytes at the start of the input which are used to detect the encoding.
+const PREFIX_LEN: usize = 8 * 1024;
+
+/// A reader transcoding text in another encoding (e. g. UTF-16, Latin-1 or Windows-1252) to UTF-8, so it can be
+/// split with [Rules::split_reader](crate::Rules::split_reader) or read by other consumers expecting UTF-8.
+///
+/// A byte order mark at the start of the input takes precedence over the given or detected encoding.
+/// Bytes which are invalid in the encoding are replaced with U+FFFD.
+///
+/// ```
+/// # #[cfg(feature = "from_xml")]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::{fs, str::FromStr};
+/// use srx::{Encoding, TranscodingReader, SRX};
+///
+/// let rules = SRX::from_str(&fs::read_to_string("data/example.srx")?)?.language_rules("de");
+/// let latin1 = b"Gr\xfc\xdfe. Tsch\xfcss." as &[u8];
+///
+/// let reader = TranscodingReader::new(latin1, Encoding::for_label(b"latin1").unwrap())?;
+/// assert_eq!(reader.encoding().name(), "windows-1252");
+///
+/// let segments: Vec<String> = rules.split_reader(reader).collect::<Result<_, _>>()?;
+/// assert_eq!(segments, vec!["Grüße.", " Tschüss."]);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "from_xml"))]
+/// # fn main() {}
+/// ```
+pub struct TranscodingReader<R> {
+    inner: DecodeReaderBytes<Chain<Cursor<Vec<u8>>, R>, Vec<u8>>,
+    encoding: &'static Encoding,
+}
+
+impl<R: Read> TranscodingReader<R> {
+    /// Transcodes the input from the encoding, unless it starts with a byte order mark.
+    ///
+    /// # Errors
+    ///
+    /// If reading the first bytes of the input fails.
+    pub fn new(reader: R, encoding: &'static Encoding) -> io::Result<Self> {
+        Self::with_encoding(reader, |_| encoding)
+    }
+
+    /// Detects the encoding from the first bytes of the input (see [TranscodingReader::encoding]) and transcodes
+    /// the input from it. Detection is a guess, so prefer [TranscodingReader::new] if the encoding is known.
+    ///
+    /// # Errors
+    ///
+    /// If reading the first bytes of the input fails.
+    #[cfg(feature = "encoding-detection")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encoding-detection")))]
+    pub fn detect(reader: R) -> io::Result<Self> {
+        Self::with_encoding(reader, |prefix| {
+            let mut detector = chardetng::EncodingDetector::new();
+            // the prefix might end inside the text, so it is not the last piece of input unless it is short
+            detector.feed(prefix, prefix.len() < PREFIX_LEN);
+            detector.guess(None, true)
+        })
+    }
+
+    fn with_encoding<F>(mut reader: R, encoding: F) -> io::Result<Self>
+    where
+        F: FnOnce(&[u8]) -> &'static Encoding,
+    {
+        let mut prefix = Vec::with_capacity(PREFIX_LEN);
+        (&mut reader)
+            .take(PREFIX_LEN as u64)
+            .read_to_end(&mut prefix)?;
+
+        let encoding = match Encoding::for_bom(&prefix) {
+            Some((encoding, _)) => encoding,
+            None => encoding(&prefix),
+        };
+        let inner = DecodeReaderBytesBuilder::new()
+            .encoding(Some(encoding))
+            .build(Cursor::new(prefix).chain(reader));
+
+        Ok(TranscodingReader { inner, encoding })
+    }
+
+    /// The encoding the input is transcoded from: the encoding of the byte order mark if there is one,
+    /// otherwise the given or detected encoding.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+}
+
+impl<R: Read> Read for TranscodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcoded<R: Read>(mut reader: TranscodingReader<R>) -> (String, &'static str) {
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .expect("transcoded text is UTF-8");
+        (text, reader.encoding().name())
+    }
+
+    #[test]
+    fn bom_takes_precedence() {
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("Hi. Bye.".encode_utf16().flat_map(u16::to_le_bytes));
+        let reader = TranscodingReader::new(utf16.as_slice(), encoding_rs::WINDOWS_1252)
+            .expect("reading a slice succeeds");
+
+        assert_eq!(transcoded(reader), ("Hi. Bye.".into(), "UTF-16LE"));
+    }
+
+    #[cfg(feature = "encoding-detection")]
+    #[test]
+    fn encoding_detected() {
+        let (bytes, _, _) =
+            encoding_rs::WINDOWS_1252.encode("Le café était très, très chaud. Voilà à côté.");
+        let reader = TranscodingReader::detect(bytes.as_ref()).expect("reading a slice succeeds");
+
+        assert_eq!(
+            transcoded(reader),
+            (
+                "Le café était très, très chaud. Voilà à côté.".into(),
+                "windows-1252"
+            )
+        );
+    }
+}
@@ -0,0 +1,352 @@
+use std::{borrow::Borrow, ops::Range};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use regex::CaptureLocations;
+
+#[cfg(feature = "metrics")]
+use super::metrics::Counting;
+#[cfg(feature = "metrics")]
+pub(crate) use super::metrics::RuleMetrics;
+use super::{
+    hook::BoundaryHook,
+    mask::{BreakMask, Claims},
+    prefilter::windows_into,
+    preprocess::{restore_ranges, Preprocessing},
+    Prefilter, Rule,
+};
+
+/// Metrics can not be recorded without the `metrics` feature.
+#[cfg(not(feature = "metrics"))]
+#[derive(Debug)]
+pub(crate) enum RuleMetrics {}
+
+/// Minimum text length in bytes for which rules are evaluated in parallel.
+/// Below this, the overhead of distributing work outweighs the gains.
+#[cfg(feature = "rayon")]
+const PARALLEL_THRESHOLD: usize = 4096;
+
+/// Buffers which can be reused between splitting texts with the same rules.
+/// Buffers are only allocated once they are needed, so creating a scratch is free.
+#[derive(Debug, Clone)]
+pub(crate) struct Scratch {
+    mask: BreakMask,
+    candidates: Vec<usize>,
+    windows: Vec<Range<usize>>,
+    // one for each rule, created when rules are first evaluated
+    locations: Vec<CaptureLocations>,
+    // the preprocessed text and the index in the original text of each of its bytes
+    stripped: String,
+    offsets: Vec<usize>,
+    pub ranges: Vec<Range<usize>>,
+}
+
+impl Scratch {
+    /// Creates empty buffers. Once used, the scratch must only be used with the same rules.
+    pub fn new() -> Self {
+        Scratch {
+            mask: BreakMask::new(0),
+            candidates: Vec::new(),
+            windows: Vec::new(),
+            locations: Vec::new(),
+            stripped: String::new(),
+            offsets: Vec::new(),
+            ranges: Vec::new(),
+        }
+    }
+}
+
+/// Creates capture locations for each rule.
+pub(crate) fn capture_locations<R: Borrow<Rule>>(rules: &[R]) -> Vec<CaptureLocations> {
+    rules
+        .iter()
+        .map(|rule| rule.borrow().regex.capture_locations())
+        .collect()
+}
+
+/// Candidate positions found by a [Prefilter] in a text.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Candidates<'a> {
+    pub positions: &'a [usize],
+    // radius for rules with unbounded match length
+    pub radius: usize,
+}
+
+impl<'a> Candidates<'a> {
+    /// The radius of the windows around the candidates the rule is evaluated on.
+    fn radius(&self, rule: &Rule) -> usize {
+        rule.max_len.unwrap_or(self.radius)
+    }
+}
+
+/// Evaluates the rules in order. Each byte index is claimed with `value` of the index and the
+/// first rule matching there. If there are candidates, rules are only evaluated on windows around them.
+/// Only matches starting at or after `from` are considered, the text before it is context.
+///
+/// Disabled rules (see [Rules::with_tag_disabled](crate::Rules::with_tag_disabled)) are not evaluated at all.
+///
+/// `locations` must contain capture locations for each rule, `windows` is a buffer for the windows.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn evaluate<R: Borrow<Rule> + Sync, C: Claims>(
+    rules: &[R],
+    text: &str,
+    from: usize,
+    candidates: Option<Candidates>,
+    windows: &mut Vec<Range<usize>>,
+    locations: &mut [CaptureLocations],
+    claims: &mut C,
+    value: impl Fn(usize, &Rule) -> C::Value,
+) {
+    // rules are matched independently in parallel, only claiming the indices has to respect rule order
+    #[cfg(feature = "rayon")]
+    if text.len() >= PARALLEL_THRESHOLD {
+        let indices: Vec<(usize, Vec<usize>)> = rules
+            .par_iter()
+            .zip(locations.par_iter_mut())
+            .enumerate()
+            .filter(|&(_, (rule, _))| {
+                let rule: &Rule = rule.borrow();
+                !rule.disabled
+            })
+            .map(|(i, (rule, locations))| {
+                let rule = rule.borrow();
+
+                let indices = match candidates {
+                    Some(candidates) => {
+                        let mut windows = Vec::new();
+                        windows_into(
+                            text,
+                            from,
+                            candidates.positions,
+                            candidates.radius(rule),
+                            &mut windows,
+                        );
+                        rule.indices(text, from, Some(&windows), locations)
+                            .collect()
+                    }
+                    None => rule.indices(text, from, None, locations).collect(),
+                };
+                (i, indices)
+            })
+            .collect();
+
+        for (i, indices) in indices {
+            for byte_index in indices {
+                claims.claim(byte_index, value(i, rules[i].borrow()));
+            }
+        }
+
+        return;
+    }
+
+    // once every position a rule could claim has been claimed, later rules can not change the outcome
+    let mut unclaimed = match candidates {
+        Some(candidates) => {
+            // the widest windows contain all positions any rule could claim
+            let radius = rules
+                .iter()
+                .map(Borrow::borrow)
+                .filter(|rule| !rule.disabled)
+                .map(|rule| candidates.radius(rule))
+                .max()
+                .unwrap_or(0);
+            windows_into(text, from, candidates.positions, radius, windows);
+
+            windows
+                .iter()
+                .map(|window| {
+                    text[window.clone()].chars().count() + usize::from(window.end < text.len())
+                })
+                .sum()
+        }
+        None => text[from..].chars().count(),
+    };
+
+    let mut windows_radius = None;
+    for (i, (rule, locations)) in rules.iter().zip(locations.iter_mut()).enumerate() {
+        if unclaimed == 0 {
+            break;
+        }
+
+        let rule = rule.borrow();
+        if rule.disabled {
+            continue;
+        }
+        let rule_windows = match candidates {
+            Some(candidates) => {
+                // consecutive rules often have the same radius, so only recompute the windows if it changes
+                let radius = candidates.radius(rule);
+                if windows_radius != Some(radius) {
+                    windows_into(text, from, candidates.positions, radius, windows);
+                    windows_radius = Some(radius);
+                }
+
+                Some(windows.as_slice())
+            }
+            None => None,
+        };
+
+        for byte_index in rule.indices(text, from, rule_windows, locations) {
+            if claims.claim(byte_index, value(i, rule)) {
+                unclaimed = unclaimed.saturating_sub(1);
+            }
+        }
+    }
+}
+
+/// Obtain the ranges for text segments. Guaranteed to be at character bounds.
+/// If `ascii` is set, boundaries are read from the break mask directly instead of checking every character.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn split_ranges<R: Borrow<Rule> + Sync>(
+    rules: &[R],
+    prefilter: Option<&Prefilter>,
+    ascii: bool,
+    metrics: Option<&RuleMetrics>,
+    preprocessing: Preprocessing,
+    hook: Option<&BoundaryHook>,
+    text: &str,
+) -> Vec<Range<usize>> {
+    let mut scratch = Scratch::new();
+    split_ranges_with(
+        rules,
+        prefilter,
+        ascii,
+        metrics,
+        preprocessing,
+        hook,
+        text,
+        0,
+        &mut scratch,
+    );
+    scratch.ranges
+}
+
+/// Like [split_ranges] but reuses the buffers of the scratch. The ranges are stored in `scratch.ranges`.
+/// If there are metrics, the claims of each rule are counted. The rules are matched on the preprocessed text,
+/// the boundaries are mapped back to the text before the hook (if any) adjusts them.
+///
+/// Rules are only matched starting at `from` (a character boundary), so there are no boundaries before it.
+/// The text before `from` is context, e. g. anchors like `^` do not match at `from` if it is not zero.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn split_ranges_with<R: Borrow<Rule> + Sync>(
+    rules: &[R],
+    prefilter: Option<&Prefilter>,
+    ascii: bool,
+    metrics: Option<&RuleMetrics>,
+    preprocessing: Preprocessing,
+    hook: Option<&BoundaryHook>,
+    text: &str,
+    from: usize,
+    scratch: &mut Scratch,
+) {
+    let mut stripped = std::mem::take(&mut scratch.stripped);
+    let mut offsets = std::mem::take(&mut scratch.offsets);
+    if preprocessing.strip_into(text, &mut stripped, &mut offsets) {
+        // the first character kept at or after `from`
+        let from = offsets.partition_point(|&offset| offset < from);
+        split_ranges_unadjusted(rules, prefilter, ascii, metrics, &stripped, from, scratch);
+        restore_ranges(&offsets, text.len(), &mut scratch.ranges);
+    } else {
+        split_ranges_unadjusted(rules, prefilter, ascii, metrics, text, from, scratch);
+    }
+    scratch.stripped = stripped;
+    scratch.offsets = offsets;
+
+    if let Some(hook) = hook {
+        hook.adjust(text, &mut scratch.ranges);
+    }
+}
+
+fn split_ranges_unadjusted<R: Borrow<Rule> + Sync>(
+    rules: &[R],
+    prefilter: Option<&Prefilter>,
+    ascii: bool,
+    metrics: Option<&RuleMetrics>,
+    text: &str,
+    from: usize,
+    scratch: &mut Scratch,
+) {
+    let segments = &mut scratch.ranges;
+    segments.clear();
+
+    let candidates = match prefilter {
+        Some(prefilter) => {
+            prefilter.candidates_into(&text[from..], &mut scratch.candidates);
+            for position in &mut scratch.candidates {
+                *position += from;
+            }
+
+            if scratch.candidates.is_empty() {
+                // no candidates, so no rule can break. This is checked before any other buffer is allocated
+                // since most lines in line-oriented corpora contain at most one sentence
+                if !text.is_empty() {
+                    segments.push(0..text.len());
+                }
+                return;
+            }
+
+            Some(Candidates {
+                positions: &scratch.candidates,
+                radius: prefilter.radius(),
+            })
+        }
+        None => None,
+    };
+
+    if scratch.locations.len() != rules.len() {
+        scratch.locations = capture_locations(rules);
+    }
+
+    let mask = &mut scratch.mask;
+    mask.reset(text.len());
+    match metrics {
+        None => evaluate(
+            rules,
+            text,
+            from,
+            candidates,
+            &mut scratch.windows,
+            &mut scratch.locations,
+            mask,
+            |_, rule| rule.do_break(),
+        ),
+        #[cfg(feature = "metrics")]
+        Some(metrics) => evaluate(
+            rules,
+            text,
+            from,
+            candidates,
+            &mut scratch.windows,
+            &mut scratch.locations,
+            &mut Counting::new(mask, metrics),
+            |i, rule| (i, rule.do_break()),
+        ),
+        #[cfg(not(feature = "metrics"))]
+        Some(metrics) => match *metrics {},
+    }
+
+    let mut prev_byte_pos = 0;
+
+    if ascii {
+        // rules only claim indices where a match starts, which are always at character bounds
+        for byte_pos in mask.breaks() {
+            segments.push(prev_byte_pos..byte_pos);
+            prev_byte_pos = byte_pos;
+        }
+    } else {
+        // Iterate over characters, we don't want no half characters in the output ranges
+        for (byte_pos, _c) in text[from..].char_indices() {
+            let byte_pos = from + byte_pos;
+            if mask.is_break(byte_pos) {
+                segments.push(prev_byte_pos..byte_pos);
+                prev_byte_pos = byte_pos;
+            }
+        }
+    }
+
+    // Deal with the trailing element, which is by definition
+    // not required to be suffixed by a gap char.
+    if text[prev_byte_pos..].chars().next().is_some() {
+        segments.push(prev_byte_pos..text.len());
+    }
+}
@@ -0,0 +1,180 @@
+//! C bindings, the header `include/srx.h` is generated from this module with `cbindgen`.
+//! The library is built with `cargo rustc --release --lib --features ffi --crate-type cdylib`
+//! (or `--crate-type staticlib` for linking it statically).
+//!
+//! Every object returned by a `srx_*` function is owned by the caller and must be released with
+//! the matching free function. Offsets are byte offsets in the UTF-8 text.
+use std::{
+    ffi::{c_char, CStr},
+    ptr, slice,
+    str::{self, FromStr},
+};
+
+use super::{Rules, SRX};
+
+/// A segment of the text as byte offsets.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SrxRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Loads rules from an XML string in SRX format.
+/// Returns `NULL` if the string is not valid UTF-8 or not a valid SRX file.
+///
+/// # Safety
+///
+/// `xml` must point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn srx_load(xml: *const c_char) -> *mut SRX {
+    if xml.is_null() {
+        return ptr::null_mut();
+    }
+
+    match CStr::from_ptr(xml).to_str().map(SRX::from_str) {
+        Ok(Ok(srx)) => Box::into_raw(Box::new(srx)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Frees rules returned by [srx_load]. Does nothing if `srx` is `NULL`.
+///
+/// # Safety
+///
+/// `srx` must have been returned by [srx_load] and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn srx_free(srx: *mut SRX) {
+    if !srx.is_null() {
+        drop(Box::from_raw(srx));
+    }
+}
+
+/// Gets the rules for a language code, see [SRX::language_rules].
+/// Returns `NULL` if `srx` is `NULL` or the language code is not valid UTF-8.
+///
+/// # Safety
+///
+/// `srx` must have been returned by [srx_load] and `lang_code` must point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn srx_language_rules(
+    srx: *const SRX,
+    lang_code: *const c_char,
+) -> *mut Rules {
+    if srx.is_null() || lang_code.is_null() {
+        return ptr::null_mut();
+    }
+
+    match CStr::from_ptr(lang_code).to_str() {
+        Ok(lang_code) => Box::into_raw(Box::new((*srx).language_rules(lang_code))),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Frees rules returned by [srx_language_rules]. Does nothing if `rules` is `NULL`.
+///
+/// # Safety
+///
+/// `rules` must have been returned by [srx_language_rules] and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn srx_rules_free(rules: *mut Rules) {
+    if !rules.is_null() {
+        drop(Box::from_raw(rules));
+    }
+}
+
+/// Splits the text of `length` bytes into segments and stores their number in `count`.
+/// Returns `NULL` (and a count of 0) if the text is not valid UTF-8 or has no segments.
+/// The ranges must be freed with [srx_ranges_free].
+///
+/// # Safety
+///
+/// `rules` must have been returned by [srx_language_rules], `text` must point to `length` readable bytes
+/// and `count` must point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn srx_split(
+    rules: *const Rules,
+    text: *const c_char,
+    length: usize,
+    count: *mut usize,
+) -> *mut SrxRange {
+    if count.is_null() {
+        return ptr::null_mut();
+    }
+    *count = 0;
+    if rules.is_null() || text.is_null() {
+        return ptr::null_mut();
+    }
+
+    let text = match str::from_utf8(slice::from_raw_parts(text.cast::<u8>(), length)) {
+        Ok(text) => text,
+        Err(_) => return ptr::null_mut(),
+    };
+    let ranges: Box<[SrxRange]> = (*rules)
+        .split_ranges(text)
+        .into_iter()
+        .map(|range| SrxRange {
+            start: range.start,
+            end: range.end,
+        })
+        .collect();
+    if ranges.is_empty() {
+        return ptr::null_mut();
+    }
+
+    *count = ranges.len();
+    Box::into_raw(ranges).cast::<SrxRange>()
+}
+
+/// Frees ranges returned by [srx_split]. Does nothing if `ranges` is `NULL`.
+///
+/// # Safety
+///
+/// `ranges` and `count` must have been returned by the same call of [srx_split] and the ranges must not be
+/// used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn srx_ranges_free(ranges: *mut SrxRange, count: usize) {
+    if !ranges.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ranges, count)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::CString, fs};
+
+    use super::*;
+
+    #[test]
+    fn text_split_through_ffi() {
+        let xml =
+            CString::new(fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file has no NUL bytes");
+        let lang_code = CString::new("en").expect("language code has no NUL bytes");
+        let text = "Hello. Bye.";
+
+        unsafe {
+            let srx = srx_load(xml.as_ptr());
+            assert!(!srx.is_null());
+            let rules = srx_language_rules(srx, lang_code.as_ptr());
+            assert!(!rules.is_null());
+
+            let mut count = 0;
+            let ranges = srx_split(rules, text.as_ptr().cast(), text.len(), &mut count);
+            assert_eq!(
+                slice::from_raw_parts(ranges, count),
+                [
+                    SrxRange { start: 0, end: 6 },
+                    SrxRange { start: 6, end: 11 }
+                ]
+            );
+
+            srx_ranges_free(ranges, count);
+            srx_rules_free(rules);
+            srx_free(srx);
+
+            let invalid = CString::new("<srx>").expect("string has no NUL bytes");
+            assert!(srx_load(invalid.as_ptr()).is_null());
+        }
+    }
+}
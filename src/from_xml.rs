@@ -1,6 +1,13 @@
-use std::{collections::HashMap, convert::TryFrom, io::Read, str::FromStr};
-
-use super::{utils, Language, LanguageRegex, Rule, SRX};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    io::{self, Read, Write},
+    str::FromStr,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+};
+
+use super::{utils, Language, LanguageRegex, Rule, RuleError, RuleErrorKind, RuleHalf, Rules, SRX};
 use regex::Regex;
 use thiserror::Error;
 
@@ -14,6 +21,11 @@ pub fn string_to_bool(string: &str) -> Result<bool, Error> {
     }
 }
 
+/// Process-wide cache of compiled rules keyed by the SRX content and the language code.
+type RulesCache = Mutex<HashMap<Arc<str>, HashMap<String, Arc<Rules>>>>;
+
+static RULES_CACHE: OnceLock<RulesCache> = OnceLock::new();
+
 #[derive(Debug, Error)]
 #[cfg_attr(docsrs, doc(cfg(feature = "from_xml")))]
 #[cfg(feature = "from_xml")]
@@ -44,17 +56,66 @@ impl Rule {
             });
         }
 
+        let pattern = format!(
+            "{}({})",
+            before_break.as_ref().map_or("", |x| x.as_ref()),
+            after_break.as_ref().map_or("", |x| x.as_ref())
+        );
+        let regex = Regex::new(&pattern)?;
+        // the pattern was just parsed successfully by `Regex::new`, so this only fails in pathological cases
+        let max_len = regex_syntax::parse(&pattern)
+            .ok()
+            .and_then(|hir| hir.properties().maximum_len());
+
         Ok(Rule {
-            regex: Regex::new(&format!(
-                "{}({})",
-                before_break.as_ref().map_or("", |x| x.as_ref()),
-                after_break.as_ref().map_or("", |x| x.as_ref())
-            ))?,
+            regex,
             do_break,
+            max_len,
+            before_break: before_break.map(|x| x.as_ref().to_owned()),
+            after_break: after_break.map(|x| x.as_ref().to_owned()),
+            tags: Vec::new(),
+            disabled: false,
         })
     }
 }
 
+/// Describes why a rule could not be created, compiling each pattern on its own to find the one which is invalid.
+fn rule_error(
+    language: Language,
+    index: usize,
+    before_break: Option<String>,
+    after_break: Option<String>,
+    error: Error,
+) -> RuleError {
+    let kind = match &error {
+        Error::RegexError(regex::Error::CompiledTooBig(_)) => RuleErrorKind::TooBig,
+        Error::RegexError(_) => RuleErrorKind::Syntax,
+        _ => RuleErrorKind::Missing,
+    };
+    let is_invalid = |pattern: &Option<String>| {
+        pattern
+            .as_ref()
+            .is_some_and(|pattern| Regex::new(pattern).is_err())
+    };
+    let half = if is_invalid(&before_break) {
+        Some(RuleHalf::BeforeBreak)
+    } else if is_invalid(&after_break) {
+        Some(RuleHalf::AfterBreak)
+    } else {
+        None
+    };
+
+    RuleError {
+        language,
+        index,
+        half,
+        before_break,
+        after_break,
+        kind,
+        message: error.to_string(),
+    }
+}
+
 impl SRX {
     /// Creates a new SRX struct from a reader.
     ///
@@ -68,6 +129,140 @@ impl SRX {
             .map_err(Error::from)
             .and_then(SRX::try_from)
     }
+
+    /// Gets the rules for a language code from SRX content (as passed to [SRX::from_str]).
+    /// Rules are cached process-wide by the content and the language code, so all consumers
+    /// loading the same SRX file and language share one compiled rule set.
+    ///
+    /// The cache is opt-in: rules obtained via [SRX::language_rules] are never cached.
+    ///
+    /// # Errors
+    ///
+    /// If the content is not valid SRX, see [SRX::from_reader].
+    #[cfg_attr(docsrs, doc(cfg(feature = "from_xml")))]
+    pub fn cached_language_rules<S: AsRef<str>>(
+        content: &str,
+        lang_code: S,
+    ) -> Result<Arc<Rules>, Error> {
+        let lang_code = lang_code.as_ref();
+
+        let cache = RULES_CACHE.get_or_init(Default::default);
+        if let Some(rules) = cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(content)
+            .and_then(|languages| languages.get(lang_code))
+        {
+            return Ok(Arc::clone(rules));
+        }
+
+        // compile without holding the lock, if another thread was faster its rules are kept
+        let rules = Arc::new(SRX::from_str(content)?.language_rules(lang_code));
+        Ok(Arc::clone(
+            cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(Arc::from(content))
+                .or_default()
+                .entry(lang_code.to_owned())
+                .or_insert(rules),
+        ))
+    }
+
+    /// Writes the rules in SRX XML format, so that [SRX::from_reader] loads the same rules again.
+    ///
+    /// Rules are written from the patterns they were created from. Rules which could not be parsed
+    /// (see [SRX::errors]) and the default language (see [SRX::with_default_language]) are not part of SRX
+    /// and are not written.
+    ///
+    /// # Errors
+    ///
+    /// * If writing fails.
+    /// * If a rule was deserialized from an older version of this crate which did not keep its patterns.
+    #[cfg_attr(docsrs, doc(cfg(feature = "from_xml")))]
+    pub fn write_xml<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<srx version="2.0" xmlns="http://www.lisa.org/srx20">"#
+        )?;
+        writeln!(
+            writer,
+            r#"    <header segmentsubflows="yes" cascade="{}"/>"#,
+            if self.cascade { "yes" } else { "no" }
+        )?;
+        writeln!(writer, "    <body>")?;
+
+        writeln!(writer, "        <languagerules>")?;
+        for (language, rules) in &self.rules {
+            writeln!(
+                writer,
+                r#"            <languagerule languagerulename="{}">"#,
+                escape(&language.0)
+            )?;
+            for rule in rules {
+                if rule.before_break.is_none() && rule.after_break.is_none() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "the patterns of rule '{}' of language '{}' are unknown",
+                            rule.regex.as_str(),
+                            language.0
+                        ),
+                    ));
+                }
+
+                write!(
+                    writer,
+                    r#"                <rule break="{}""#,
+                    if rule.do_break { "yes" } else { "no" }
+                )?;
+                if !rule.tags.is_empty() {
+                    write!(writer, r#" tags="{}""#, escape(&rule.tags.join(" ")))?;
+                }
+                writeln!(writer, ">")?;
+                for (name, pattern) in [
+                    ("beforebreak", &rule.before_break),
+                    ("afterbreak", &rule.after_break),
+                ] {
+                    if let Some(pattern) = pattern {
+                        writeln!(
+                            writer,
+                            "                    <{0}>{1}</{0}>",
+                            name,
+                            escape(pattern)
+                        )?;
+                    }
+                }
+                writeln!(writer, "                </rule>")?;
+            }
+            writeln!(writer, "            </languagerule>")?;
+        }
+        writeln!(writer, "        </languagerules>")?;
+
+        writeln!(writer, "        <maprules>")?;
+        for (pattern, language) in self.language_patterns() {
+            writeln!(
+                writer,
+                r#"            <languagemap languagepattern="{}" languagerulename="{}"/>"#,
+                escape(pattern),
+                escape(&language.0)
+            )?;
+        }
+        writeln!(writer, "        </maprules>")?;
+
+        writeln!(writer, "    </body>")?;
+        writeln!(writer, "</srx>")?;
+        writer.flush()
+    }
+
+    /// Removes all rules from the cache used by [SRX::cached_language_rules].
+    #[cfg_attr(docsrs, doc(cfg(feature = "from_xml")))]
+    pub fn clear_cache() {
+        if let Some(cache) = RULES_CACHE.get() {
+            cache.lock().unwrap_or_else(PoisonError::into_inner).clear();
+        }
+    }
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "from_xml")))]
@@ -80,6 +275,25 @@ impl FromStr for SRX {
     }
 }
 
+/// Escapes the text for XML element content and attribute values.
+fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>', '"']) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
 impl TryFrom<schema::SRX> for SRX {
     type Error = Error;
 
@@ -92,15 +306,19 @@ impl TryFrom<schema::SRX> for SRX {
             .maps
             .into_iter()
             .map(|lang| {
+                let pattern = utils::full_pattern(&lang.pattern);
+                utils::validate_pattern(&pattern)?;
+
                 Ok(LanguageRegex {
-                    regex: utils::full_regex(&lang.pattern)?,
+                    pattern,
+                    regex: OnceLock::new(),
                     language: Language(lang.name),
                 })
             })
             .collect();
         let map = map?;
 
-        let mut errors: HashMap<_, _> = data
+        let mut errors: BTreeMap<_, _> = data
             .body
             .languagerules
             .rules
@@ -108,7 +326,7 @@ impl TryFrom<schema::SRX> for SRX {
             .map(|lang| (Language(lang.name.clone()), Vec::new()))
             .collect();
 
-        let rules: Result<HashMap<_, _>, Error> = data
+        let rules: Result<BTreeMap<_, _>, Error> = data
             .body
             .languagerules
             .rules
@@ -124,20 +342,39 @@ impl TryFrom<schema::SRX> for SRX {
                             rule.beforebreak,
                             rule.afterbreak,
                             string_to_bool(&rule.do_break)?,
+                            rule.tags,
                         ))
                     })
                     .collect::<Result<Vec<_>, Error>>()?
                     .into_iter()
-                    .filter_map(|(before_break, after_break, do_break)| {
-                        let rule = Rule::new(before_break, after_break, do_break);
+                    .enumerate()
+                    .filter_map(|(index, (before_break, after_break, do_break, tags))| {
+                        let rule = Rule::new(before_break.as_ref(), after_break.as_ref(), do_break);
 
                         match rule {
-                            Ok(rule) => Some(rule),
+                            Ok(mut rule) => {
+                                rule.tags = tags
+                                    .as_deref()
+                                    .unwrap_or_default()
+                                    .split_whitespace()
+                                    .map(str::to_owned)
+                                    .collect();
+                                Some(rule)
+                            }
                             Err(error) => {
+                                let error = rule_error(
+                                    key.clone(),
+                                    index,
+                                    before_break,
+                                    after_break,
+                                    error,
+                                );
+                                #[cfg(feature = "log")]
+                                log::warn!("skipping a rule of language '{}': {}", key.0, error);
                                 errors
                                     .get_mut(&key)
                                     .expect("error map has a key for each language")
-                                    .push(format!("{}", error));
+                                    .push(error);
                                 None
                             }
                         }
@@ -156,11 +393,22 @@ impl TryFrom<schema::SRX> for SRX {
             return Err(Error::SRXError { reason: format!("<languagerules> must have an entry for each language in <languagemap>. Did not find entry for {}", entry.language.0)});
         }
 
+        #[cfg(feature = "log")]
+        for language in rules.keys() {
+            if !map.iter().any(|entry| entry.language == *language) {
+                log::warn!(
+                    "the rules of language '{}' are not used by any <languagemap>",
+                    language.0
+                );
+            }
+        }
+
         Ok(SRX {
             cascade,
             map,
             rules,
             errors,
+            default_language: None,
         })
     }
 }
@@ -169,6 +417,7 @@ mod schema {
     use serde::Deserialize;
     use std::io::Read;
 
+    #[allow(clippy::upper_case_acronyms)]
     #[derive(Debug, Clone, Deserialize)]
     #[serde(crate = "serde_crate", rename_all = "lowercase")]
     pub struct SRX {
@@ -233,6 +482,9 @@ mod schema {
         pub do_break: String,
         pub beforebreak: Option<String>,
         pub afterbreak: Option<String>,
+        // extension attribute (in any namespace), whitespace separated
+        #[serde(default)]
+        pub tags: Option<String>,
     }
 
     #[derive(Debug, Clone, Deserialize)]
@@ -279,6 +531,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cached_rules_are_shared() {
+        let content = fs::read_to_string("data/example.srx").expect("example file exists");
+
+        let rules = SRX::cached_language_rules(&content, "en").expect("example file is valid");
+        let same_rules = SRX::cached_language_rules(&content, "en").expect("example file is valid");
+        let other_rules =
+            SRX::cached_language_rules(&content, "fr").expect("example file is valid");
+
+        assert!(Arc::ptr_eq(&rules, &same_rules));
+        assert!(!Arc::ptr_eq(&rules, &other_rules));
+    }
+
+    #[test]
+    fn written_xml_loads_same_rules() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid");
+
+        let mut xml = Vec::new();
+        srx.write_xml(&mut xml).expect("writing to memory succeeds");
+        let written = SRX::from_reader(xml.as_slice()).expect("written XML is valid");
+
+        assert_eq!(written.cascade, srx.cascade);
+        assert!(written.language_patterns().eq(srx.language_patterns()));
+        for language in srx.languages() {
+            assert!(written
+                .rule_patterns(language)
+                .expect("language is written")
+                .eq(srx.rule_patterns(language).expect("language exists")));
+        }
+        assert!(written.errors().values().all(Vec::is_empty));
+    }
+
     #[test]
     fn serde_works() -> Result<(), bincode::Error> {
         let srx =
@@ -295,4 +581,53 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn serialization_is_deterministic() -> Result<(), bincode::Error> {
+        let content = fs::read_to_string("data/segment.srx").expect("segment file exists");
+        let load = || SRX::from_str(&content).expect("segment file is valid");
+
+        assert_eq!(bincode::serialize(&load())?, bincode::serialize(&load())?);
+        Ok(())
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn skipped_rules_logged() {
+        struct Collect(Mutex<Vec<String>>);
+
+        impl log::Log for Collect {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Warn
+            }
+
+            fn log(&self, record: &log::Record) {
+                if self.enabled(record.metadata()) {
+                    self.0
+                        .lock()
+                        .expect("messages are not poisoned")
+                        .push(record.args().to_string());
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOGGER: Collect = Collect(Mutex::new(Vec::new()));
+        log::set_logger(&LOGGER).expect("no other logger is set");
+        log::set_max_level(log::LevelFilter::Warn);
+
+        SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+            .expect("segment file is valid");
+
+        // other tests can load rules at the same time
+        let messages = LOGGER.0.lock().expect("messages are not poisoned");
+        assert!(
+            messages
+                .iter()
+                .filter(|message| message.starts_with("skipping a rule"))
+                .count()
+                >= 49
+        );
+    }
 }
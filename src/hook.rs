@@ -0,0 +1,91 @@
+use std::{fmt, ops::Range, sync::Arc};
+
+/// A boundary proposed by the rules, passed to the hook set with [Rules::with_boundary_hook](crate::Rules::with_boundary_hook).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Boundary<'t> {
+    /// The text which is split.
+    pub text: &'t str,
+    /// The byte index of the boundary in the text, i. e. the start of the next segment.
+    pub position: usize,
+}
+
+impl<'t> Boundary<'t> {
+    /// The text before the boundary.
+    pub fn before(&self) -> &'t str {
+        &self.text[..self.position]
+    }
+
+    /// The text after the boundary.
+    pub fn after(&self) -> &'t str {
+        &self.text[self.position..]
+    }
+}
+
+/// What to do with a proposed [Boundary].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Adjustment {
+    /// Split the text at the boundary.
+    Accept,
+    /// Do not split the text at the boundary.
+    Reject,
+    /// Split the text at another byte index instead. Ignored (i. e. the boundary is kept) if the index is
+    /// not inside the text (including its start, which would produce an empty segment) or not at a character boundary.
+    MoveTo(usize),
+}
+
+type HookFn = dyn Fn(&Boundary) -> Adjustment + Send + Sync;
+
+/// A callback adjusting the boundaries found by the rules. Shared by clones of the rules.
+#[derive(Clone)]
+pub(crate) struct BoundaryHook(Arc<HookFn>);
+
+impl BoundaryHook {
+    pub fn new<F: Fn(&Boundary) -> Adjustment + Send + Sync + 'static>(hook: F) -> Self {
+        BoundaryHook(Arc::new(hook))
+    }
+
+    /// Passes the boundaries between the ranges to the hook and replaces the ranges with the adjusted segments.
+    /// The ranges must be contiguous and cover the text.
+    pub fn adjust(&self, text: &str, ranges: &mut Vec<Range<usize>>) {
+        let mut boundaries: Vec<usize> = ranges
+            .iter()
+            .skip(1)
+            .filter_map(|range| {
+                let boundary = Boundary {
+                    text,
+                    position: range.start,
+                };
+                match (self.0)(&boundary) {
+                    Adjustment::Accept => Some(range.start),
+                    Adjustment::Reject => None,
+                    Adjustment::MoveTo(position)
+                        if position > 0
+                            && position < text.len()
+                            && text.is_char_boundary(position) =>
+                    {
+                        Some(position)
+                    }
+                    Adjustment::MoveTo(_) => Some(range.start),
+                }
+            })
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        ranges.clear();
+        let mut start = 0;
+        for boundary in boundaries {
+            ranges.push(start..boundary);
+            start = boundary;
+        }
+        if start < text.len() {
+            ranges.push(start..text.len());
+        }
+    }
+}
+
+impl fmt::Debug for BoundaryHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BoundaryHook")
+    }
+}
@@ -0,0 +1,183 @@
+//! An HTTP service for splitting texts, to mount in an existing `axum` (or other `tower`) server.
+//!
+//! The service answers `POST /segment` with the same JSON API as `srx-split serve`: the request body is an object
+//! with the `text` and the `lang` code, the response contains the `segments` with their `text` and byte offsets.
+//!
+//! ```
+//! # fn main() -> Result<(), srx::Error> {
+//! use std::{fs, str::FromStr};
+//! use axum::Router;
+//! use srx::{http::SegmentService, SRX};
+//!
+//! let srx = SRX::from_str(&fs::read_to_string("data/example.srx").unwrap())?;
+//! let app: Router = Router::new().nest("/srx", SegmentService::new(srx).into_router());
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    convert::Infallible,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::{rejection::JsonRejection, Request, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{future::RouteFuture, post},
+    Json, Router,
+};
+use serde::Serialize;
+
+use super::{
+    service::{SegmentRequest, Segmentation},
+    SRX,
+};
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "serde_crate")]
+struct ErrorResponse<'a> {
+    error: &'a str,
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(ErrorResponse { error: message })).into_response()
+}
+
+async fn segment(
+    State(segmentation): State<Arc<Segmentation>>,
+    request: Result<Json<SegmentRequest>, JsonRejection>,
+) -> Response {
+    let Json(request) = match request {
+        Ok(request) => request,
+        Err(rejection) => return error_response(rejection.status(), &rejection.body_text()),
+    };
+
+    Json(segmentation.segment(&request)).into_response()
+}
+
+/// A `tower` service answering `POST /segment` with the segments of the text in the request, see the [module](self) docs.
+///
+/// Rules are compiled once per requested language and shared by all requests. Texts are split on the task
+/// handling the request, so servers accepting very long texts should limit the size of request bodies.
+#[derive(Debug, Clone)]
+pub struct SegmentService {
+    router: Router,
+}
+
+impl SegmentService {
+    /// Creates a service splitting with the rules.
+    pub fn new(srx: SRX) -> Self {
+        let segmentation = Arc::new(Segmentation::new(srx));
+
+        let router = Router::new()
+            .route(
+                "/segment",
+                post(segment).fallback(|| async {
+                    error_response(
+                        StatusCode::METHOD_NOT_ALLOWED,
+                        "method not allowed, use POST /segment",
+                    )
+                }),
+            )
+            .fallback(|| async {
+                error_response(StatusCode::NOT_FOUND, "not found, use POST /segment")
+            })
+            .with_state(segmentation);
+        SegmentService { router }
+    }
+
+    /// Gets an `axum` router with the `POST /segment` route, e. g. to [nest](Router::nest) under a path
+    /// or [merge](Router::merge) into the router of an application.
+    pub fn into_router(self) -> Router {
+        self.router
+    }
+}
+
+impl tower_service::Service<Request> for SegmentService {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = RouteFuture<Infallible>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        tower_service::Service::<Request>::poll_ready(&mut self.router, cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        self.router.call(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use axum::body::{to_bytes, Body};
+    use futures::executor::block_on;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn respond(service: &SegmentService, method: &str, uri: &str, body: &str) -> (u16, String) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_owned()))
+            .expect("request is valid");
+
+        block_on(async {
+            let response = service
+                .clone()
+                .oneshot(request)
+                .await
+                .expect("service is infallible");
+            let status = response.status().as_u16();
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("body can be read");
+            (
+                status,
+                String::from_utf8(body.to_vec()).expect("body is UTF-8"),
+            )
+        })
+    }
+
+    #[test]
+    fn requests_answered() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let service = SegmentService::new(srx);
+
+        assert_eq!(
+            respond(
+                &service,
+                "POST",
+                "/segment",
+                r#"{"text": "Ask Mr. Smith. Now.", "lang": "en"}"#
+            ),
+            (
+                200,
+                r#"{"segments":[{"text":"Ask Mr. Smith.","start":0,"end":14},{"text":" Now.","start":14,"end":19}]}"#
+                    .to_owned()
+            )
+        );
+        assert_eq!(respond(&service, "GET", "/segment", "").0, 405);
+        assert_eq!(respond(&service, "POST", "/split", "").0, 404);
+        assert_eq!(
+            respond(&service, "POST", "/segment", r#"{"text": "A."}"#).0,
+            422
+        );
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/srx/segment")
+            .header("Content-Type", "application/json")
+            .body(Body::from(r#"{"text": "A.", "lang": "en"}"#))
+            .expect("request is valid");
+        let nested = Router::new().nest("/srx", service.into_router());
+        let response = block_on(nested.oneshot(request)).expect("router is infallible");
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
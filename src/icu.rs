@@ -0,0 +1,54 @@
+use std::ops::Range;
+
+use icu_segmenter::{
+    options::SentenceBreakInvariantOptions, SentenceSegmenter, SentenceSegmenterBorrowed,
+};
+
+use super::SentenceSplitter;
+
+/// A [SentenceSplitter] backed by the sentence segmenter of `icu_segmenter`, which finds the sentence boundaries
+/// of [Unicode Standard Annex #29](https://www.unicode.org/reports/tr29/#Sentence_Boundaries) for any language.
+///
+/// Trailing whitespace belongs to the preceding sentence, unlike with most SRX rules.
+#[derive(Debug, Clone, Copy)]
+pub struct IcuSentenceSplitter {
+    segmenter: SentenceSegmenterBorrowed<'static>,
+}
+
+impl IcuSentenceSplitter {
+    pub fn new() -> Self {
+        IcuSentenceSplitter {
+            segmenter: SentenceSegmenter::new(SentenceBreakInvariantOptions::default()),
+        }
+    }
+}
+
+impl Default for IcuSentenceSplitter {
+    fn default() -> Self {
+        IcuSentenceSplitter::new()
+    }
+}
+
+impl SentenceSplitter for IcuSentenceSplitter {
+    fn sentence_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let breaks: Vec<usize> = self.segmenter.segment_str(text).collect();
+
+        breaks.windows(2).map(|pair| pair[0]..pair[1]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sentences_split() {
+        let splitter = IcuSentenceSplitter::new();
+
+        assert_eq!(
+            splitter.sentences("Ceci tuera cela. Le livre tuera l’édifice."),
+            vec!["Ceci tuera cela. ", "Le livre tuera l’édifice."]
+        );
+        assert!(splitter.sentence_ranges("").is_empty());
+    }
+}
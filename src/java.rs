@@ -0,0 +1,132 @@
+//! Bindings for Java and other JVM languages with JNI, for the classes in `java/` (package `io.github.bminixhofer.srx`).
+//!
+//! The library is loaded with `System.loadLibrary("srx")` after building it with
+//! `cargo rustc --release --lib --features jni --crate-type cdylib`:
+//!
+//! ```java
+//! try (SRX srx = new SRX(xml); Rules rules = srx.languageRules("en")) {
+//!     String[] segments = rules.split(text);
+//! }
+//! ```
+//!
+//! Objects are passed to Java as handles which own the boxed Rust value until they are freed by `close()`.
+//! Offsets are returned in UTF-16 code units so they can be used with `String.substring` directly.
+use std::str::FromStr;
+
+use jni::{
+    objects::{JClass, JString},
+    sys::{jintArray, jlong},
+    JNIEnv,
+};
+
+use super::{utils::utf16_spans, Rules, SRX};
+
+/// Gets a Java string, throwing an exception and returning `None` if that fails.
+fn get_string(env: &mut JNIEnv, string: &JString) -> Option<String> {
+    match env.get_string(string) {
+        Ok(string) => Some(string.into()),
+        Err(error) => {
+            // an exception is pending already if the string could not be read
+            if !env.exception_check().unwrap_or(true) {
+                let _ = env.throw_new("java/lang/IllegalArgumentException", error.to_string());
+            }
+            None
+        }
+    }
+}
+
+/// Loads rules from an XML string in SRX format. Throws an `IllegalArgumentException` if the string is not a valid SRX file.
+#[no_mangle]
+pub extern "system" fn Java_io_github_bminixhofer_srx_SRX_load(
+    mut env: JNIEnv,
+    _class: JClass,
+    xml: JString,
+) -> jlong {
+    let xml = match get_string(&mut env, &xml) {
+        Some(xml) => xml,
+        None => return 0,
+    };
+
+    match SRX::from_str(&xml) {
+        Ok(srx) => Box::into_raw(Box::new(srx)) as jlong,
+        Err(error) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", error.to_string());
+            0
+        }
+    }
+}
+
+/// Frees rules returned by `load`.
+#[no_mangle]
+pub extern "system" fn Java_io_github_bminixhofer_srx_SRX_free(
+    _env: JNIEnv,
+    _class: JClass,
+    srx: jlong,
+) {
+    if srx != 0 {
+        // SAFETY: the handle was returned by `load` and is freed once by `SRX.close`
+        drop(unsafe { Box::from_raw(srx as *mut SRX) });
+    }
+}
+
+/// Gets the rules for a language code, see [SRX::language_rules].
+#[no_mangle]
+pub extern "system" fn Java_io_github_bminixhofer_srx_SRX_languageRules(
+    mut env: JNIEnv,
+    _class: JClass,
+    srx: jlong,
+    lang_code: JString,
+) -> jlong {
+    let lang_code = match get_string(&mut env, &lang_code) {
+        Some(lang_code) => lang_code,
+        None => return 0,
+    };
+
+    // SAFETY: the handle was returned by `load` and is not freed while the `SRX` object is open
+    let srx = unsafe { &*(srx as *const SRX) };
+    Box::into_raw(Box::new(srx.language_rules(lang_code))) as jlong
+}
+
+/// Frees rules returned by `languageRules`.
+#[no_mangle]
+pub extern "system" fn Java_io_github_bminixhofer_srx_Rules_free(
+    _env: JNIEnv,
+    _class: JClass,
+    rules: jlong,
+) {
+    if rules != 0 {
+        // SAFETY: the handle was returned by `languageRules` and is freed once by `Rules.close`
+        drop(unsafe { Box::from_raw(rules as *mut Rules) });
+    }
+}
+
+/// Splits the text and returns the UTF-16 offsets of the segments as `[start, end, start, end, ...]`.
+#[no_mangle]
+pub extern "system" fn Java_io_github_bminixhofer_srx_Rules_splitSpans(
+    mut env: JNIEnv,
+    _class: JClass,
+    rules: jlong,
+    text: JString,
+) -> jintArray {
+    let text = match get_string(&mut env, &text) {
+        Some(text) => text,
+        None => return std::ptr::null_mut(),
+    };
+
+    // SAFETY: the handle was returned by `languageRules` and is not freed while the `Rules` object is open
+    let rules = unsafe { &*(rules as *const Rules) };
+    // Java strings are at most `i32::MAX` UTF-16 code units long
+    let spans: Vec<i32> = utf16_spans(&text, &rules.split_ranges(&text))
+        .into_iter()
+        .map(|offset| offset as i32)
+        .collect();
+
+    // an `OutOfMemoryError` is pending if the array can not be created
+    match env.new_int_array(spans.len() as i32) {
+        Ok(array) => match env.set_int_array_region(&array, 0, &spans) {
+            Ok(()) => array.into_raw(),
+            Err(_) => std::ptr::null_mut(),
+        },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
@@ -6,11 +6,13 @@
 //! is ignored.
 //!
 //! Not complying with the SRX spec, overlapping matches of the same `<rule>` are not found which could
-//! lead to different behavior in a few edge cases.
+//! lead to different behavior in a few edge cases. [SRX::compliance_report] summarizes the supported behaviors.
 //!
 //! ## Example
 //!
 //! ```
+//! # #[cfg(feature = "from_xml")]
+//! # fn main() -> Result<(), srx::Error> {
 //! use std::{fs, str::FromStr};
 //! use srx::SRX;
 //!
@@ -21,13 +23,57 @@
 //!     english_rules.split("e.g. U.K. and Mr. do not split. SRX is a rule-based format.").collect::<Vec<_>>(),
 //!     vec!["e.g. U.K. and Mr. do not split. ", "SRX is a rule-based format."]
 //! );
-//! # Ok::<(), srx::Error>(())
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "from_xml"))]
+//! # fn main() {}
 //! ```
 //!
 //! ## Features
 //!
 //! - `serde`: Serde serialization and deserialization support for [SRX].
 //! - `from_xml`: [SRX::from_reader] method and [std::str::FromStr] implementation to load from an XML file in SRX format.
+//! - `log`: Warnings with the `log` crate about rules skipped when loading, in addition to [SRX::errors]. They are also
+//!   received by `tracing` subscribers with the `tracing-log` bridge.
+//! - `rayon`: Evaluates rules in parallel when splitting long texts, and splits many texts in parallel
+//!   with [Rules::par_split_batch] and [ParallelSegmentExt].
+//! - `icu_segmenter`: [IcuSentenceSplitter], a [SentenceSplitter] with the Unicode sentence boundaries of ICU4X,
+//!   e. g. as the fallback of [WithFallback] for languages without rules.
+//! - `metrics`: [Rules::with_metrics] to count how often each rule breaks or prevents breaking while splitting.
+//! - `futures-core`: [Rules::segment_stream] to split text arriving as an asynchronous stream of chunks.
+//! - `encoding`: [TranscodingReader] to split text in encodings other than UTF-8, e. g. UTF-16 or Windows-1252.
+//! - `encoding-detection`: [TranscodingReader::detect] to guess the encoding of the input.
+//! - `tokenizers`: [SentencePreTokenizer], a pre-tokenizer for the `tokenizers` crate splitting at segment boundaries.
+//! - `charabia`: [CharabiaSegmenter], a segmenter for `charabia` (the tokenizer of Meilisearch) splitting at segment boundaries.
+//! - `cli`: The `srx-split` command line tool, e. g. `cat corpus.txt | srx-split -l en -s segment.srx`, and `segment-compat`
+//!   which accepts the options of [loomchild/segment](https://github.com/loomchild/segment) as a drop-in replacement.
+//! - `whatlang`: [SRX::language_rules_auto] and [detect_language] to get the rules for untagged text.
+//! - `language-detection`: Detecting the language of each input with `srx-split --lang auto`.
+//! - `serve`: Serving a JSON API for splitting texts over HTTP with `srx-split serve`.
+//! - `http-service`: [http::SegmentService], the JSON API of `srx-split serve` as a `tower` service or `axum` router
+//!   to mount in an existing web service.
+//! - `json-rpc`: [rpc::RpcServer], a JSON-RPC server speaking newline-delimited JSON over stdio or a Unix socket.
+//! - `wasm`: JavaScript bindings for the browser and Node.js in the [wasm] module, with offsets in UTF-16 code units.
+//! - `ffi`: C bindings in the [ffi] module, declared in the header `include/srx.h`.
+//! - `jni`: Java bindings in the [java] module, for the classes in `java/`.
+//! - `uniffi`: Swift and Kotlin bindings with UniFFI in the [mobile] module, `uniffi-bindgen` builds the tool
+//!   generating them.
+//! - `default-rules`: Embeds `segment.srx` from LanguageTool in `srx-split`, which uses it if no SRX file is given.
+//!
+//! ## Concurrency
+//!
+//! [SRX], [Rules] and all other types of this crate are `Send + Sync`. Splitting only needs a shared reference
+//! to the rules, so one [Rules] can be wrapped in an [std::sync::Arc] (e. g. in a web service) or borrowed by all
+//! workers of a thread pool instead of being cloned per thread. A [Segmenter] or [Splitter] holds buffers which are mutated
+//! while splitting, so each thread should create its own from the shared rules.
+//!
+//! For splitting many texts, [Segmenter] is recommended since it reuses its buffers between calls.
+//! Rules can be tested against texts with their expected segments with the [testkit] module.
+//! Segments can be written as CoNLL-U sentences for parsers with the [conllu] module.
+//! Translation memories and XLIFF files are resegmented with [resegment_tmx] and [resegment_xliff].
+//! To group sentences into chunks of a maximum size, e. g. for retrieval-augmented generation, use a [Chunker].
+//! For dataframe libraries, [Rules::split_offsets] splits an Arrow string column into offsets of the segments
+//! which share the buffer of the column.
 //!
 //! ## A note on regular expressions
 //!
@@ -48,16 +94,162 @@ extern crate serde_crate as serde;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use std::{collections::HashMap, ops::Range};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Read,
+    ops::Range,
+    slice,
+    sync::OnceLock,
+};
 
-use regex::Regex;
+use regex::{CaptureLocations, Regex, RegexBuilder};
 
+#[cfg(feature = "from_xml")]
+mod abbreviations;
+#[cfg(feature = "from_xml")]
+mod analysis;
+#[cfg(feature = "charabia")]
+mod charabia_segmenter;
+mod chunker;
+mod columnar;
+mod compliance;
+pub mod conllu;
+mod coverage;
+#[cfg(feature = "whatlang")]
+mod detect;
+#[cfg(feature = "encoding")]
+mod encoding;
+mod engine;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 #[cfg(feature = "from_xml")]
 mod from_xml;
+mod hook;
+#[cfg(feature = "http-service")]
+pub mod http;
+#[cfg(feature = "icu_segmenter")]
+mod icu;
+#[cfg(feature = "jni")]
+pub mod java;
+mod mask;
+mod memory;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "uniffi")]
+pub mod mobile;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod pipeline;
+#[cfg(feature = "tokenizers")]
+mod pre_tokenizer;
+mod prefilter;
+mod preprocess;
+mod profile;
+mod reader;
+#[cfg(feature = "from_xml")]
+mod report;
+mod resegment;
+#[cfg(feature = "json-rpc")]
+pub mod rpc;
+mod rule_error;
+mod segmenter;
+mod sentence_splitter;
+#[cfg(any(feature = "http-service", feature = "json-rpc"))]
+mod service;
+mod splitter;
+mod stats;
+mod str_ext;
+#[cfg(feature = "futures-core")]
+mod stream;
+mod tags;
+pub mod testkit;
+mod trace;
 #[cfg(feature = "from_xml")]
 mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "from_xml")]
+pub use analysis::Shadowing;
+#[cfg(feature = "charabia")]
+pub use charabia_segmenter::CharabiaSegmenter;
+pub use chunker::Chunker;
+pub use columnar::SegmentOffsets;
+pub use compliance::{Behavior, ComplianceReport, Support};
+pub use coverage::{Coverage, RuleCoverage};
+#[cfg(feature = "whatlang")]
+pub use detect::{detect_language, DETECTION_SAMPLE_SIZE, UNDETERMINED};
+#[cfg(feature = "encoding")]
+pub use encoding::{Encoding, TranscodingReader};
 #[cfg(feature = "from_xml")]
 pub use from_xml::Error;
+pub use hook::{Adjustment, Boundary};
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+#[cfg(feature = "icu_segmenter")]
+pub use icu::IcuSentenceSplitter;
+#[cfg(feature = "rayon")]
+pub use parallel::ParallelSegmentExt;
+pub use pipeline::Pipeline;
+#[cfg(feature = "tokenizers")]
+pub use pre_tokenizer::SentencePreTokenizer;
+pub use prefilter::Prefilter;
+pub use preprocess::Preprocessing;
+pub use profile::{Profile, RuleOrder};
+pub use reader::SplitReader;
+#[cfg(feature = "from_xml")]
+pub use report::{Diagnostic, DiagnosticKind, Report, Severity};
+pub use resegment::{resegment_tmx, resegment_xliff};
+pub use rule_error::{RuleError, RuleErrorKind, RuleHalf};
+pub use segmenter::Segmenter;
+pub use sentence_splitter::{SentenceSplitter, WithFallback};
+pub use splitter::Splitter;
+pub use stats::{CorpusStats, Lengths};
+pub use str_ext::StrSplitExt;
+#[cfg(feature = "futures-core")]
+pub use stream::SegmentStream;
+pub use trace::Decision;
+
+// all public types must stay shareable across threads, see the "Concurrency" section above
+const _: () = {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[allow(dead_code)]
+    fn assert_all() {
+        assert_send_sync::<SRX>();
+        assert_send_sync::<Rules>();
+        assert_send_sync::<RulesView>();
+        assert_send_sync::<Segmenter>();
+        assert_send_sync::<Splitter>();
+        assert_send_sync::<SplitReader<&[u8]>>();
+        #[cfg(feature = "futures-core")]
+        assert_send_sync::<
+            SegmentStream<
+                std::pin::Pin<Box<dyn futures_core::Stream<Item = String> + Send + Sync>>,
+            >,
+        >();
+        assert_send_sync::<Prefilter>();
+        assert_send_sync::<Profile>();
+        assert_send_sync::<Coverage>();
+        assert_send_sync::<SegmentOffsets>();
+        assert_send_sync::<Chunker<Rules>>();
+        assert_send_sync::<Pipeline>();
+        assert_send_sync::<RuleOrder>();
+        assert_send_sync::<Decision>();
+        assert_send_sync::<Language>();
+        #[cfg(feature = "from_xml")]
+        assert_send_sync::<Error>();
+        #[cfg(feature = "tokenizers")]
+        assert_send_sync::<SentencePreTokenizer>();
+        #[cfg(feature = "charabia")]
+        assert_send_sync::<CharabiaSegmenter>();
+        #[cfg(feature = "icu_segmenter")]
+        assert_send_sync::<IcuSentenceSplitter>();
+        #[cfg(feature = "http-service")]
+        assert_send_sync::<http::SegmentService>();
+        #[cfg(feature = "json-rpc")]
+        assert_send_sync::<rpc::RpcServer>();
+    }
+};
 
 /// Newtype denoting a language (`languagerulename` attribute in SRX).
 #[cfg_attr(
@@ -82,18 +274,83 @@ struct Rule {
     #[cfg_attr(feature = "serde", serde(with = "serde_regex"))]
     regex: Regex,
     do_break: bool,
+    // maximum length of a match in bytes, if it is bounded
+    max_len: Option<usize>,
+    // the patterns as written in the SRX file, the regex is compiled from them
+    #[cfg_attr(feature = "serde", serde(default))]
+    before_break: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    after_break: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tags: Vec<String>,
+    // set if any of the tags is disabled, see `Rules::with_tag_disabled`
+    #[cfg_attr(feature = "serde", serde(default))]
+    disabled: bool,
 }
 
 impl Rule {
     /// Gets all byte indices in the text at which this rule matches.
     /// Contrary to the SRX 2.0 spec this does not find overlapping matches.
-    fn match_indices<'a>(&'a self, text: &'a str) -> impl Iterator<Item = usize> + 'a {
-        self.regex.captures_iter(text).filter_map(|x| {
-            // generally it is guaranteed that a regex has
-            // at least one match, but be lenient about
-            // errors in the srx xml files and drop those without
-            x.get(1).map(|x| x.start())
-        })
+    ///
+    /// `locations` must have been created from the regex of this rule.
+    fn match_indices<'a>(
+        &'a self,
+        text: &'a str,
+        locations: &'a mut CaptureLocations,
+    ) -> Indices<'a> {
+        Indices {
+            rule: self,
+            text,
+            windows: [].iter(),
+            start: 0,
+            end: text.len(),
+            last_end: None,
+            locations,
+        }
+    }
+
+    /// Gets all byte indices in the text at which this rule matches, only considering
+    /// matches which start and break within one of the windows. `windows` must be sorted.
+    /// The rest of the text is still visible to the regex, so anchors and word boundaries at the edges
+    /// of a window behave like when searching the entire text.
+    fn window_match_indices<'a>(
+        &'a self,
+        text: &'a str,
+        windows: &'a [Range<usize>],
+        locations: &'a mut CaptureLocations,
+    ) -> Indices<'a> {
+        Indices {
+            rule: self,
+            text,
+            windows: windows.iter(),
+            // start after the end so that the first window is taken immediately
+            start: 1,
+            end: 0,
+            last_end: None,
+            locations,
+        }
+    }
+
+    /// Gets the byte indices inside the text (i. e. excluding the end of the text) at which this rule matches,
+    /// restricted to the windows if there are any. Without windows, only matches starting at or after `from`
+    /// are considered (windows must not start before it).
+    fn indices<'a>(
+        &'a self,
+        text: &'a str,
+        from: usize,
+        windows: Option<&'a [Range<usize>]>,
+        locations: &'a mut CaptureLocations,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let indices = match windows {
+            Some(windows) => self.window_match_indices(text, windows, locations),
+            None => Indices {
+                start: from,
+                ..self.match_indices(text, locations)
+            },
+        };
+
+        // indices are ascending so we can stop at the first one at the end of the text
+        indices.take_while(move |&index| index < text.len())
     }
 
     /// Whether this rule breaks or prevents breaking.
@@ -102,6 +359,77 @@ impl Rule {
     }
 }
 
+/// Iterator over the indices at which a [Rule] matches in a text, see [Rule::match_indices].
+/// Reuses one set of capture locations for all matches to avoid allocating in the hot loop.
+struct Indices<'a> {
+    rule: &'a Rule,
+    text: &'a str,
+    // windows after the current one
+    windows: slice::Iter<'a, Range<usize>>,
+    // where to continue searching in the current window
+    start: usize,
+    // end of the current window
+    end: usize,
+    // end of the previous match in the current window
+    last_end: Option<usize>,
+    locations: &'a mut CaptureLocations,
+}
+
+impl<'a> Iterator for Indices<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if self.start <= self.end {
+                if let Some(found) =
+                    self.rule
+                        .regex
+                        .captures_read_at(self.locations, self.text, self.start)
+                {
+                    let is_empty = found.start() == found.end();
+                    // like `Regex::captures_iter`, empty matches directly after the previous match are skipped
+                    let is_skipped = is_empty && self.last_end == Some(found.end());
+
+                    // empty matches must advance by one character to guarantee progress
+                    self.start = if is_empty {
+                        found.end()
+                            + self.text[found.end()..]
+                                .chars()
+                                .next()
+                                .map_or(1, char::len_utf8)
+                    } else {
+                        found.end()
+                    };
+
+                    if !is_skipped {
+                        self.last_end = Some(found.end());
+
+                        // generally it is guaranteed that a regex has
+                        // at least one match, but be lenient about
+                        // errors in the srx xml files and drop those without
+                        if let Some((index, _)) = self.locations.get(1) {
+                            // the match breaks after the window (it might start in the next one,
+                            // but then it is found again from there)
+                            if index > self.end {
+                                self.start = self.end + 1;
+                                continue;
+                            }
+                            return Some(index);
+                        }
+                    }
+
+                    continue;
+                }
+            }
+
+            let window = self.windows.next()?;
+            self.start = window.start;
+            self.end = window.end;
+            self.last_end = None;
+        }
+    }
+}
+
 /// An ordered set of rules.
 /// Rules are executed in order.
 /// Once a rule matches on an index, no other rule can match at the same index.
@@ -114,45 +442,121 @@ impl Rule {
 #[derive(Debug, Clone, Default)]
 pub struct Rules {
     rules: Vec<Rule>,
+    prefilter: Option<Prefilter>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    ascii: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    preprocessing: Preprocessing,
+    #[cfg_attr(feature = "serde", serde(default))]
+    disabled_tags: BTreeSet<String>,
+    // shared by clones, see `Rules::with_metrics`
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    metrics: Option<std::sync::Arc<engine::RuleMetrics>>,
+    // shared by clones, see `Rules::with_boundary_hook`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    hook: Option<hook::BoundaryHook>,
 }
 
 impl Rules {
-    /// Obtain the ranges for text segments. Guaranteed to be at character bounds.
-    pub fn split_ranges(&self, text: &str) -> Vec<Range<usize>> {
-        let mut segments = Vec::new();
+    /// Creates rules which evaluate the given rules in order, without a prefilter, preprocessing or hook.
+    pub(crate) fn from_rules(rules: Vec<Rule>) -> Self {
+        Rules {
+            rules,
+            prefilter: None,
+            ascii: false,
+            preprocessing: Preprocessing::default(),
+            disabled_tags: BTreeSet::new(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            hook: None,
+        }
+    }
 
-        // TODO use a proper tri-state enum here
-        let mut masked_bytes: Vec<Option<bool>> = vec![None; text.len()];
+    /// Restricts rule evaluation to windows around the candidates of the given [Prefilter].
+    /// Trades exactness for speed, see [Prefilter] for details.
+    pub fn with_prefilter(mut self, prefilter: Prefilter) -> Self {
+        self.prefilter = Some(prefilter);
+        self
+    }
 
-        'outer: for rule in &self.rules {
-            for byte_index in rule.match_indices(text) {
-                if byte_index >= text.len() {
-                    continue 'outer;
-                }
+    /// Passes every boundary found by the rules to the hook, which can accept it, reject it or move it
+    /// (see [Adjustment]). This allows application-specific adjustments, e. g. never breaking before a closing
+    /// quote, without writing regexes. The hook applies to splitting but not to [Rules::trace].
+    pub fn with_boundary_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Boundary) -> Adjustment + Send + Sync + 'static,
+    {
+        self.hook = Some(hook::BoundaryHook::new(hook));
+        self
+    }
 
-                if masked_bytes[byte_index].is_none() {
-                    masked_bytes[byte_index] = Some(rule.do_break());
-                }
-            }
-        }
+    /// Removes invisible characters (see [Preprocessing]) from the text before the rules are matched.
+    /// Segments are still ranges of the original text. Preprocessing applies to splitting but not to [Rules::trace].
+    pub fn with_preprocessing(mut self, preprocessing: Preprocessing) -> Self {
+        self.preprocessing = preprocessing;
+        self
+    }
 
-        let mut prev_byte_pos = 0;
+    /// The preprocessing applied before the rules are matched.
+    pub fn preprocessing(&self) -> Preprocessing {
+        self.preprocessing
+    }
 
-        // Iterate over characters, we don't want no half characters in the output ranges
-        for (byte_pos, _c) in text.char_indices() {
-            if let Some(true) = masked_bytes[byte_pos] {
-                segments.push(prev_byte_pos..byte_pos);
-                prev_byte_pos = byte_pos;
+    /// The prefilter used by this rule set, if any.
+    pub fn prefilter(&self) -> Option<&Prefilter> {
+        self.prefilter.as_ref()
+    }
+
+    /// Recompiles the rules for text which is known to be ASCII.
+    ///
+    /// Rules are compiled with Unicode mode disabled where the pattern permits it (e. g. `\s` and `\w` then only match
+    /// ASCII characters, which makes the regexes smaller and faster), other rules are kept as they are. Boundaries are also
+    /// resolved without decoding characters.
+    ///
+    /// Segmentation of ASCII text is the same as with the original rules. Non-ASCII text can be segmented differently.
+    pub fn with_ascii(mut self) -> Self {
+        for rule in &mut self.rules {
+            if let Ok(regex) = RegexBuilder::new(rule.regex.as_str())
+                .unicode(false)
+                .build()
+            {
+                rule.regex = regex;
             }
         }
 
-        // Deal with the trailing element, which is by definition
-        // not required to be suffixed by a gap char.
-        if text[prev_byte_pos..].chars().next().is_some() {
-            segments.push(prev_byte_pos..text.len());
-        }
+        self.ascii = true;
+        self
+    }
+
+    /// The counters updated while splitting, if the rules record metrics.
+    fn recorded_metrics(&self) -> Option<&engine::RuleMetrics> {
+        #[cfg(feature = "metrics")]
+        return self.metrics.as_deref();
 
-        segments
+        #[cfg(not(feature = "metrics"))]
+        None
+    }
+
+    /// Whether the rules were recompiled for ASCII text with [Rules::with_ascii].
+    pub fn is_ascii(&self) -> bool {
+        self.ascii
+    }
+
+    /// Obtain the ranges for text segments. Guaranteed to be at character bounds.
+    ///
+    /// The ranges are contiguous and cover the whole text, so concatenating the segments always reproduces
+    /// the text byte for byte, regardless of the rules.
+    pub fn split_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        engine::split_ranges(
+            &self.rules,
+            self.prefilter.as_ref(),
+            self.ascii,
+            self.recorded_metrics(),
+            self.preprocessing,
+            self.hook.as_ref(),
+            text,
+        )
     }
 
     /// Split text into segments.
@@ -165,6 +569,73 @@ impl Rules {
             .map(move |range| &text[range])
     }
 
+    /// Creates a [Splitter] to split many texts with these rules, reusing buffers between calls.
+    /// See [Segmenter] for a variant which owns the rules.
+    pub fn splitter(&self) -> Splitter<'_> {
+        Splitter::new(self)
+    }
+
+    /// Split text from a reader into segments, reading it in chunks so that memory usage does not grow
+    /// with the length of the input. See [SplitReader] for details.
+    pub fn split_reader<R: Read>(&self, reader: R) -> SplitReader<'_, R> {
+        SplitReader::new(self, reader)
+    }
+
+    /// Split text arriving as a stream of chunks into a stream of segments, see [SegmentStream] for details.
+    #[cfg(feature = "futures-core")]
+    pub fn segment_stream<S>(&self, stream: S) -> SegmentStream<'_, S> {
+        SegmentStream::new(self, stream)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+}
+
+/// The rules for a language borrowed from an [SRX]. Created by [SRX::rules_view].
+///
+/// Behaves like [Rules] but does not clone the rules, so creating it is cheap.
+#[derive(Debug, Clone)]
+pub struct RulesView<'a> {
+    rules: Vec<&'a Rule>,
+}
+
+impl<'a> RulesView<'a> {
+    /// Obtain the ranges for text segments. Guaranteed to be at character bounds.
+    ///
+    /// The ranges are contiguous and cover the whole text, so concatenating the segments always reproduces
+    /// the text byte for byte, regardless of the rules.
+    pub fn split_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        engine::split_ranges(
+            &self.rules,
+            None,
+            false,
+            None,
+            Preprocessing::default(),
+            None,
+            text,
+        )
+    }
+
+    /// Split text into segments.
+    pub fn split<'t, 'b>(&self, text: &'t str) -> impl Iterator<Item = &'t str> + 'b
+    where
+        't: 'b,
+    {
+        self.split_ranges(text)
+            .into_iter()
+            .map(move |range| &text[range])
+    }
+
+    /// Clones the rules into an owned [Rules].
+    pub fn to_rules(&self) -> Rules {
+        Rules::from_rules(self.rules.iter().map(|&rule| rule.clone()).collect())
+    }
+
     pub fn is_empty(&self) -> bool {
         self.rules.is_empty()
     }
@@ -183,11 +654,31 @@ impl Rules {
 )]
 #[derive(Debug, Clone)]
 struct LanguageRegex {
-    #[cfg_attr(feature = "serde", serde(with = "serde_regex"))]
-    regex: Regex,
+    // anchored pattern, compiled on first use since usually only few languages are requested
+    #[cfg_attr(feature = "serde", serde(rename = "regex"))]
+    pattern: String,
+    // `None` if the pattern does not compile
+    #[cfg_attr(feature = "serde", serde(skip))]
+    regex: OnceLock<Option<Regex>>,
     language: Language,
 }
 
+impl LanguageRegex {
+    /// Gets the compiled regex, compiling it if this is the first call.
+    ///
+    /// Patterns are only parsed when loading (and not at all when deserialized), so compiling can still fail,
+    /// e. g. if the compiled regex exceeds the size limit. Such patterns match no language code.
+    fn regex(&self) -> Option<&Regex> {
+        self.regex
+            .get_or_init(|| Regex::new(&self.pattern).ok())
+            .as_ref()
+    }
+
+    fn is_match(&self, lang_code: &str) -> bool {
+        self.regex().is_some_and(|regex| regex.is_match(lang_code))
+    }
+}
+
 /// The SRX root.
 /// Does not execute rules on is own.
 #[cfg_attr(
@@ -199,8 +690,11 @@ struct LanguageRegex {
 pub struct SRX {
     cascade: bool,
     map: Vec<LanguageRegex>,
-    rules: HashMap<Language, Vec<Rule>>,
-    errors: HashMap<Language, Vec<String>>,
+    rules: BTreeMap<Language, Vec<Rule>>,
+    errors: BTreeMap<Language, Vec<RuleError>>,
+    // language code used if no form of a language code matches, see `SRX::with_default_language`
+    #[cfg_attr(feature = "serde", serde(default))]
+    default_language: Option<String>,
 }
 
 impl SRX {
@@ -208,33 +702,149 @@ impl SRX {
     /// - aggregating rules from all [Language]s with a matching `<languagepattern>` (if the SRX is set to be cascading)
     /// - finding the first matching `<languagepattern>` (if the SRX is set to be not cascading)
     ///
+    /// If no `<languagepattern>` matches, the language code is retried without its last subtag until one matches
+    /// (e. g. `zh-Hant-TW`, then `zh-Hant`, then `zh`), and finally the default language code
+    /// (see [SRX::with_default_language]) is used.
+    ///
     /// Result should be cached instead of calling this repeatedly as it clones the rules.
+    /// See [SRX::rules_view] to borrow the rules instead.
     pub fn language_rules<S: AsRef<str>>(&self, lang_code: S) -> Rules {
-        let mut rules = Vec::new();
+        self.rules_view(lang_code).to_rules()
+    }
+
+    /// Gets the rules for a language code like [SRX::language_rules] but borrows them instead of cloning them.
+    pub fn rules_view<S: AsRef<str>>(&self, lang_code: S) -> RulesView<'_> {
+        let lang_code = lang_code.as_ref();
+        // `en-US`, `en`, then the default language code
+        let fallbacks = std::iter::successors(Some(lang_code), |code| {
+            code.rfind(&['-', '_'][..]).map(|index| &code[..index])
+        })
+        .chain(self.default_language.as_deref());
 
-        for item in &self.map {
-            if item.regex.is_match(lang_code.as_ref()) {
-                rules.extend(self.rules.get(&item.language).expect("languagerulename in <languagemap> must have a corresponding entry in <languagerules>").iter().cloned());
-                if !self.cascade {
-                    break;
+        for code in fallbacks {
+            let mut matched = false;
+            let mut rules = Vec::new();
+
+            for item in &self.map {
+                if item.is_match(code) {
+                    matched = true;
+                    rules.extend(self.rules.get(&item.language).expect("languagerulename in <languagemap> must have a corresponding entry in <languagerules>").iter());
+                    if !self.cascade {
+                        break;
+                    }
                 }
             }
+
+            if matched {
+                return RulesView { rules };
+            }
         }
 
-        Rules { rules }
+        RulesView { rules: Vec::new() }
     }
 
-    /// Maps [Language]s to a vector of string representations of errors which occured during parsing regular expressions for this language.
-    pub fn errors(&self) -> &HashMap<Language, Vec<String>> {
+    /// Sets the language code whose rules are used if neither a language code nor any of its less specific
+    /// forms match a `<languagepattern>`, see [SRX::language_rules].
+    pub fn with_default_language<S: Into<String>>(mut self, lang_code: S) -> Self {
+        self.default_language = Some(lang_code.into());
+        self
+    }
+
+    /// The language code set with [SRX::with_default_language], if any.
+    pub fn default_language(&self) -> Option<&str> {
+        self.default_language.as_deref()
+    }
+
+    /// Maps [Language]s to the rules of this language which were skipped because their regular expressions could not be parsed.
+    /// Languages are sorted by name and errors are in the order of the rules, so the listing is the same across runs.
+    pub fn errors(&self) -> &BTreeMap<Language, Vec<RuleError>> {
         &self.errors
     }
+
+    /// Gets all [Language]s with rules (`<languagerule>` elements), sorted by name.
+    pub fn languages(&self) -> Vec<&Language> {
+        self.rules.keys().collect()
+    }
+
+    /// Gets the `<languagemap>` entries in order as pairs of `languagepattern` and the [Language] it maps to.
+    pub fn language_patterns(&self) -> impl Iterator<Item = (&str, &Language)> {
+        self.map.iter().map(|item| {
+            // patterns are anchored when loading
+            let pattern = item.pattern.strip_prefix('^').unwrap_or(&item.pattern);
+            let pattern = pattern.strip_suffix('$').unwrap_or(pattern);
+
+            (pattern, &item.language)
+        })
+    }
+
+    /// Gets the rules registered under a [Language] (i. e. one `<languagerule>`), bypassing the `<languagemap>`.
+    /// `None` if there is no `<languagerule>` with this name.
+    pub fn rules_for(&self, language: &Language) -> Option<Rules> {
+        self.rules.get(language).map(|rules| {
+            RulesView {
+                rules: rules.iter().collect(),
+            }
+            .to_rules()
+        })
+    }
+
+    /// Gets the number of rules of a [Language] (without rules which could not be parsed).
+    pub fn rule_count(&self, language: &Language) -> Option<usize> {
+        self.rules.get(language).map(Vec::len)
+    }
+
+    /// Gets the rules of a [Language] as pairs of their pattern (in the form `before_break(after_break)`, see
+    /// [Rules::rule_pattern]) and whether they break.
+    pub fn rule_patterns(&self, language: &Language) -> Option<impl Iterator<Item = (&str, bool)>> {
+        self.rules.get(language).map(|rules| {
+            rules
+                .iter()
+                .map(|rule| (rule.regex.as_str(), rule.do_break()))
+        })
+    }
+
+    /// Layers the rules of `overlay` on top of these rules:
+    /// - rules of [Language]s present in both come first from `overlay`, followed by the rules from `self`,
+    ///   so exceptions in `overlay` take precedence
+    /// - `<languagemap>` entries of `overlay` come before those of `self`, entries present in both are kept once
+    /// - [Language]s present in only one of them are kept as they are
+    ///
+    /// Whether the rules cascade is kept from `self`, as is the default language unless only `overlay` has one.
+    pub fn merge(mut self, overlay: SRX) -> SRX {
+        if self.default_language.is_none() {
+            self.default_language = overlay.default_language;
+        }
+
+        for (language, rules) in overlay.rules {
+            let base = self.rules.remove(&language).unwrap_or_default();
+            self.rules
+                .insert(language, rules.into_iter().chain(base).collect());
+        }
+
+        for (language, errors) in overlay.errors {
+            self.errors.entry(language).or_default().extend(errors);
+        }
+
+        let base = std::mem::replace(&mut self.map, overlay.map);
+        for item in base {
+            if !self
+                .map
+                .iter()
+                .any(|other| other.pattern == item.pattern && other.language == item.language)
+            {
+                self.map.push(item);
+            }
+        }
+
+        self
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "from_xml"))]
 mod tests {
     use super::*;
     use quickcheck_macros::quickcheck;
-    use std::{fs, str::FromStr};
+    use std::{fs, str::FromStr, sync::Arc, thread};
 
     #[quickcheck]
     fn length_invariant(text: String) {
@@ -249,16 +859,116 @@ mod tests {
         );
     }
 
+    /// Regex fragments arbitrary rules are built from, so that every generated pattern is valid.
+    const FRAGMENTS: &[&str] = &[
+        "",
+        "a",
+        "A",
+        "\\.",
+        "\\s",
+        "\\s+",
+        "[A-Z]",
+        "[\\.\\?!]+",
+        ".",
+        "^",
+        "$",
+        "\\b",
+        "\\w*",
+        "é",
+        "👒",
+        "(?:x|)",
+        "\\p{Lu}",
+    ];
+
+    // few, short rules to keep compiling them fast
+    fn arbitrary_rules(rules: Vec<(Vec<u8>, Vec<u8>, bool)>) -> Rules {
+        let pattern = |fragments: Vec<u8>| -> String {
+            fragments
+                .into_iter()
+                .take(4)
+                .map(|i| FRAGMENTS[i as usize % FRAGMENTS.len()])
+                .collect()
+        };
+
+        Rules::from_rules(
+            rules
+                .into_iter()
+                .take(8)
+                .map(|(before, after, do_break)| {
+                    Rule::new(Some(&pattern(before)), Some(&pattern(after)), do_break)
+                        .expect("fragments form a valid pattern")
+                })
+                .collect(),
+        )
+    }
+
+    fn assert_lossless(rules: &Rules, text: &str) {
+        let ranges = rules.split_ranges(text);
+
+        let mut end = 0;
+        for range in &ranges {
+            assert_eq!(range.start, end);
+            assert!(text.is_char_boundary(range.end));
+            end = range.end;
+        }
+        assert_eq!(end, text.len());
+        assert_eq!(rules.split(text).collect::<String>(), text);
+    }
+
+    #[quickcheck]
+    fn arbitrary_rules_lossless(rules: Vec<(Vec<u8>, Vec<u8>, bool)>, text: String) {
+        let rules = arbitrary_rules(rules);
+
+        assert_lossless(&rules, &text);
+        assert_lossless(&rules.clone().with_prefilter(Prefilter::default()), &text);
+
+        // ASCII rules are only guaranteed to segment ASCII text the same
+        let text: String = text.chars().filter(char::is_ascii).collect();
+        assert_lossless(&rules.with_ascii(), &text);
+    }
+
     #[test]
     fn match_indices_correct() {
         let rule = Rule::new(Some("abc"), Some("d+fg"), true).expect("test rule is valid");
 
         assert_eq!(
-            rule.match_indices("abcddfgxxx").collect::<Vec<_>>(),
+            rule.match_indices("abcddfgxxx", &mut rule.regex.capture_locations())
+                .collect::<Vec<_>>(),
             vec![3_usize]
         );
     }
 
+    #[test]
+    fn match_indices_agree_with_captures_iter() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid")
+                .language_rules("en");
+
+        let text =
+            "e.g. U.K. and Mr. do not split. SRX is a 👒🍏🍱-based format 🐱! \n\nNo. 5 is... ok?";
+        for rule in &rules.rules {
+            assert_eq!(
+                rule.match_indices(text, &mut rule.regex.capture_locations())
+                    .collect::<Vec<_>>(),
+                rule.regex
+                    .captures_iter(text)
+                    .filter_map(|x| x.get(1).map(|x| x.start()))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn max_len_correct() {
+        let rule = Rule::new(Some("\\sMr\\."), Some("\\s"), false).expect("test rule is valid");
+        // `\s` matches up to three bytes in UTF-8
+        assert_eq!(rule.max_len, Some(9));
+
+        let rule = Rule::new(Some("[\\.\\?!]+"), Some("\\s"), true).expect("test rule is valid");
+        assert_eq!(rule.max_len, None);
+    }
+
     #[test]
     fn example_splits_correct() {
         let rules =
@@ -304,6 +1014,507 @@ mod tests {
         let _ = rules.split("Hello! ").collect::<Vec<_>>();
     }
 
+    #[test]
+    fn long_text_splits_correct() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en");
+
+        let text =
+            "The U.K. Prime Minister, Mr. Blair, was seen out with his family today. He is well. "
+                .repeat(500);
+        assert_eq!(rules.split(text.trim_end()).count(), 1000);
+    }
+
+    #[test]
+    fn later_rules_skipped_once_all_claimed() {
+        let rules = Rules::from_rules(vec![
+            Rule::new(Some(""), Some(""), false).expect("test rule is valid"),
+            Rule::new(Some("\\."), Some("\\s"), true).expect("test rule is valid"),
+        ]);
+
+        assert_eq!(
+            rules.split("Hello. World.").collect::<Vec<_>>(),
+            vec!["Hello. World."]
+        );
+    }
+
+    #[test]
+    fn windows_see_entire_text() {
+        let rules = Rules::from_rules(vec![
+            Rule::new(Some("\\."), Some("\\s\\w+$"), true).expect("test rule is valid")
+        ])
+        .with_prefilter(Prefilter::new(['.'], 2));
+
+        // the window around the dot ends after "b", which is not the end of the text
+        assert_eq!(rules.split_ranges("A. bcdef ghi"), vec![0..12]);
+        assert_eq!(rules.split_ranges("A. bcdef"), vec![0..2, 2..8]);
+    }
+
+    #[test]
+    fn split_reader_matches_split() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid")
+                .language_rules("en");
+
+        let text = "e.g. U.K. and Mr. do not split. SRX is a 👒🍏🍱-based format 🐱. ".repeat(20);
+        let segments = rules
+            .split_reader(text.as_bytes())
+            .with_chunk_size(50, 25)
+            .collect::<Result<Vec<_>, _>>()
+            .expect("text is valid UTF-8");
+
+        assert_eq!(segments, rules.split(&text).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn split_reader_anchors_at_input_start() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en");
+
+        // `^\s*[0-9]+\.` prevents the break after "1.", but must not match where drained buffers start
+        let text = format!("1. List.{}", "          \n5. Item. ".repeat(20));
+        for (chunk_size, overlap) in [(7, 8), (16, 8), (50, 25)] {
+            let segments = rules
+                .split_reader(text.as_bytes())
+                .with_chunk_size(chunk_size, overlap)
+                .collect::<Result<Vec<_>, _>>()
+                .expect("text is valid UTF-8");
+
+            assert_eq!(segments, rules.split(&text).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn profiled_order_splits_correct() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid")
+                .language_rules("en");
+
+        let text = "e.g. U.K. and Mr. do not split. SRX is a rule-based format! Is it? Yes.";
+        let profile = rules.profile(vec![text]);
+        assert_eq!(profile.claims().len(), rules.len());
+        assert!(profile.claims().iter().sum::<usize>() > 0);
+
+        let order = profile.order(&rules);
+        let reordered_rules = rules.reordered(&order).expect("learned order is valid");
+        assert_eq!(
+            reordered_rules.split(text).collect::<Vec<_>>(),
+            rules.split(text).collect::<Vec<_>>()
+        );
+
+        let mut invalid_order: Vec<_> = (0..rules.len()).collect();
+        invalid_order.reverse();
+        assert!(rules.reordered(&RuleOrder(invalid_order)).is_none());
+    }
+
+    #[test]
+    fn segmenter_splits_correct() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid")
+                .language_rules("en");
+        let mut segmenter = Segmenter::from(rules.clone());
+
+        for text in [
+            "e.g. U.K. and Mr. do not split. SRX is a rule-based format.",
+            "",
+            "Short. Text.",
+            "e.g. U.K. and Mr. do not split. SRX is a rule-based format.",
+        ] {
+            assert_eq!(
+                segmenter.split(text).collect::<Vec<_>>(),
+                rules.split(text).collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(segmenter.into_rules().len(), rules.len());
+    }
+
+    #[test]
+    fn rules_view_splits_correct() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid");
+        let view = srx.rules_view("en");
+
+        let text = "e.g. U.K. and Mr. do not split. SRX is a rule-based format.";
+        assert_eq!(view.len(), srx.language_rules("en").len());
+        assert_eq!(
+            view.split(text).collect::<Vec<_>>(),
+            vec![
+                "e.g. U.K. and Mr. do not split. ",
+                "SRX is a rule-based format."
+            ]
+        );
+    }
+
+    #[test]
+    fn ascii_splits_correct() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid")
+                .language_rules("en");
+        let ascii_rules = rules.clone().with_ascii();
+        assert!(ascii_rules.is_ascii());
+
+        let text =
+            "e.g. U.K. and Mr. do not split. SRX is a rule-based format.\n\nNew paragraph! Or not?";
+        for text in [text, "", "No boundary", ". Starts with a dot."] {
+            assert_eq!(
+                ascii_rules.split_ranges(text),
+                rules.split_ranges(text),
+                "{:?}",
+                text
+            );
+            assert_eq!(
+                ascii_rules.splitter().split_ranges(text),
+                rules.split_ranges(text)
+            );
+        }
+    }
+
+    #[test]
+    fn boundary_hook_adjusts_splits() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en")
+                .with_boundary_hook(|boundary| {
+                    if boundary.after().starts_with(" \"") {
+                        Adjustment::Reject
+                    } else if boundary.before().ends_with("Yes.") {
+                        Adjustment::MoveTo(boundary.position + 1)
+                    } else {
+                        Adjustment::Accept
+                    }
+                });
+
+        let text = "One. He said: Stop. \"Why?\" Yes. No.";
+        assert_eq!(
+            rules.split(text).collect::<Vec<_>>(),
+            vec!["One.", " He said: Stop. \"Why?\" Yes. ", "No."]
+        );
+        assert_eq!(
+            rules.splitter().split_ranges(text),
+            rules.split_ranges(text).as_slice()
+        );
+        assert_eq!(
+            Segmenter::from(rules.clone()).split_ranges(text),
+            rules.split_ranges(text).as_slice()
+        );
+        assert_eq!(rules.split("").count(), 0);
+    }
+
+    #[test]
+    fn boundary_hook_never_moves_to_start() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en")
+                .with_boundary_hook(|_| Adjustment::MoveTo(0));
+
+        let ranges = rules.split_ranges("One. Two.");
+        assert!(ranges.iter().all(|range| !range.is_empty()), "{:?}", ranges);
+        assert_eq!(ranges, vec![0..4, 4..9]);
+    }
+
+    #[test]
+    fn invalid_language_pattern_never_matches() {
+        let language_regex = LanguageRegex {
+            pattern: "^(?:en".into(),
+            regex: OnceLock::new(),
+            language: Language("English".into()),
+        };
+
+        assert!(!language_regex.is_match("en"));
+    }
+
+    #[test]
+    fn rules_found_by_language() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+
+        let english = srx
+            .rules_for(&Language("English".into()))
+            .expect("example file has English rules");
+        assert_eq!(english.len(), 3);
+        assert_eq!(
+            english.split("Ask Mr. Smith. Now.").collect::<Vec<_>>(),
+            vec!["Ask Mr. Smith. Now."]
+        );
+        assert!(srx.rules_for(&Language("en".into())).is_none());
+    }
+
+    #[test]
+    fn locale_falls_back() {
+        let content = fs::read_to_string("data/example.srx")
+            .expect("example file exists")
+            .replace(
+                "languagepattern=\"[Ee][Nn].*\"",
+                "languagepattern=\"[Ee][Nn]\"",
+            )
+            .replace("languagepattern=\".*\"", "languagepattern=\"xx\"");
+        let srx = SRX::from_str(&content).expect("example file is valid");
+
+        assert_eq!(srx.rules_view("en-US").len(), 3);
+        assert_eq!(srx.rules_view("en_GB").len(), 3);
+        assert!(srx.rules_view("de-DE").is_empty());
+
+        let srx = srx.with_default_language("xx");
+        assert_eq!(srx.default_language(), Some("xx"));
+        assert_eq!(srx.rules_view("de-DE").len(), 3);
+        assert_eq!(srx.rules_view("en-US").len(), 3);
+        assert_eq!(
+            srx.language_rules("de")
+                .split("Hallo. Welt.")
+                .collect::<Vec<_>>(),
+            vec!["Hallo.", " Welt."]
+        );
+    }
+
+    #[test]
+    fn language_patterns_compiled_lazily() {
+        let content = fs::read_to_string("data/example.srx")
+            .expect("example file exists")
+            .replace("cascade=\"yes\"", "cascade=\"no\"");
+        let srx = SRX::from_str(&content).expect("example file is valid");
+        assert!(srx.map.iter().all(|item| item.regex.get().is_none()));
+
+        // without cascading, only the patterns up to the first match are needed
+        assert!(!srx.language_rules("en").is_empty());
+        let compiled: Vec<_> = srx
+            .map
+            .iter()
+            .map(|item| item.regex.get().is_some())
+            .collect();
+        assert_eq!(compiled, vec![true, false, false, false]);
+
+        let invalid = content.replacen("languagepattern=\"", "languagepattern=\"(", 1);
+        assert!(SRX::from_str(&invalid).is_err());
+    }
+
+    #[test]
+    fn memory_usage_grows_with_rules() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let rules = srx.language_rules("en");
+
+        assert_eq!(Rules::default().approx_memory_usage(), 0);
+        assert!(rules.approx_memory_usage() > 0);
+        assert!(
+            rules
+                .clone()
+                .with_prefilter(Prefilter::default())
+                .approx_memory_usage()
+                > rules.approx_memory_usage()
+        );
+        assert!(srx.approx_memory_usage() > rules.approx_memory_usage());
+        // `\s` and `\w` only match ASCII characters, so the regexes are smaller
+        assert!(rules.clone().with_ascii().approx_memory_usage() < rules.approx_memory_usage());
+    }
+
+    #[test]
+    fn splitter_reuse_splits_correct() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid")
+                .language_rules("en")
+                .with_prefilter(Prefilter::default());
+        let mut splitter = rules.splitter();
+
+        for text in &[
+            "e.g. U.K. and Mr. do not split. SRX is a rule-based format.",
+            "",
+            "No boundaries here",
+            "Short. Lines. 👒🍏🍱!",
+        ] {
+            assert_eq!(
+                splitter.split(text).collect::<Vec<_>>(),
+                rules.split(text).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn rules_shared_across_threads() {
+        let rules = Arc::new(
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en"),
+        );
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let rules = Arc::clone(&rules);
+                thread::spawn(move || {
+                    rules
+                        .split("The U.K. Prime Minister, Mr. Blair, was seen out with his family today. He is well.")
+                        .count()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().expect("thread does not panic"), 2);
+        }
+    }
+
+    #[test]
+    fn prefilter_splits_correct() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid");
+        let rules = srx.language_rules("en");
+        let prefiltered_rules = srx
+            .language_rules("en")
+            .with_prefilter(Prefilter::default());
+
+        let text = "e.g. U.K. and Mr. do not split. SRX is a rule-based format! Is it? Yes.";
+        assert_eq!(
+            prefiltered_rules.split(text).collect::<Vec<_>>(),
+            rules.split(text).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            prefiltered_rules.split_ranges("no punctuation here"),
+            vec![0..19]
+        );
+        assert!(prefiltered_rules.split_ranges("").is_empty());
+    }
+
+    #[test]
+    fn trace_explains_splits() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid")
+                .language_rules("en");
+        let text = "e.g. U.K. and Mr. do not split. SRX is a rule-based format.";
+
+        let decisions = rules.trace(text);
+        let breaks: Vec<_> = decisions
+            .iter()
+            .filter(|decision| decision.do_break)
+            .map(|decision| decision.position)
+            .collect();
+        let starts: Vec<_> = rules
+            .split_ranges(text)
+            .iter()
+            .skip(1)
+            .map(|range| range.start)
+            .collect();
+        assert_eq!(breaks, starts);
+
+        for decision in &decisions {
+            assert_eq!(rules.rule_breaks(decision.rule), Some(decision.do_break));
+            assert!(decision.shadowed.iter().all(|&i| i > decision.rule));
+        }
+        // "Mr. do" is matched by the general break rule but an exception comes first
+        assert!(decisions
+            .iter()
+            .any(|decision| !decision.do_break && !decision.shadowed.is_empty()));
+        assert!(rules.rule_pattern(0).is_some());
+        assert_eq!(rules.rule_pattern(rules.len()), None);
+        assert_eq!(rules.rule_source(rules.len()), None);
+    }
+
+    #[test]
+    fn languages_listed() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let english = Language("English".into());
+
+        assert_eq!(
+            srx.languages(),
+            vec![
+                &Language("Default".into()),
+                &english,
+                &Language("French".into()),
+                &Language("Japanese".into())
+            ]
+        );
+        assert_eq!(
+            srx.language_patterns().next(),
+            Some(("[Ee][Nn].*", &english))
+        );
+        assert_eq!(srx.language_patterns().count(), 4);
+        assert!(srx.rule_count(&english).is_some_and(|count| count > 0));
+        assert_eq!(srx.rule_count(&Language("German".into())), None);
+        assert_eq!(
+            srx.rule_patterns(&english)
+                .and_then(|mut patterns| patterns.next()),
+            Some(("\\s[Ee][Tt][Cc]\\.(\\s[a-z])", false))
+        );
+    }
+
+    #[test]
+    fn overlay_merged() {
+        let base =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let overlay = SRX::from_str(
+            r#"<?xml version="1.0"?>
+<srx version="2.0" xmlns="http://www.lisa.org/srx20">
+    <header cascade="yes"/>
+    <body>
+        <languagerules>
+            <languagerule languagerulename="English">
+                <rule break="no">
+                    <beforebreak>\sDr\.</beforebreak>
+                    <afterbreak>\s</afterbreak>
+                </rule>
+            </languagerule>
+            <languagerule languagerulename="German">
+                <rule break="no">
+                    <beforebreak>\sbzw\.</beforebreak>
+                    <afterbreak>\s</afterbreak>
+                </rule>
+            </languagerule>
+        </languagerules>
+        <maprules>
+            <languagemap languagepattern="[Dd][Ee].*" languagerulename="German"/>
+            <languagemap languagepattern="[Ee][Nn].*" languagerulename="English"/>
+        </maprules>
+    </body>
+</srx>"#,
+        )
+        .expect("overlay is valid");
+
+        let english = Language("English".into());
+        let english_count = base.rule_count(&english).expect("base has English rules");
+        let merged = base.merge(overlay);
+
+        assert_eq!(merged.rule_count(&english), Some(english_count + 1));
+        assert_eq!(merged.languages().len(), 5);
+        assert_eq!(
+            merged
+                .language_patterns()
+                .map(|(pattern, _)| pattern)
+                .collect::<Vec<_>>(),
+            vec!["[Dd][Ee].*", "[Ee][Nn].*", "[Ff][Rr].*", "[Jj][Aa].*", ".*"]
+        );
+        assert_eq!(
+            merged
+                .language_rules("en")
+                .split("Ask Dr. Smith. Mr. Jones too.")
+                .collect::<Vec<_>>(),
+            vec!["Ask Dr. Smith.", " Mr. Jones too."]
+        );
+        assert_eq!(
+            merged
+                .language_rules("de")
+                .split("Eins bzw. zwei. Drei.")
+                .collect::<Vec<_>>(),
+            vec!["Eins bzw. zwei.", " Drei."]
+        );
+    }
+
     #[test]
     fn errors_reported() {
         let srx =
@@ -312,5 +1523,76 @@ mod tests {
 
         assert!(!srx.errors().is_empty());
         assert_eq!(srx.errors().values().flatten().count(), 49);
+
+        let errors: Vec<_> = srx.errors().values().flatten().collect();
+        assert!(errors
+            .iter()
+            .all(|error| error.to_string().contains("(beforebreak: ")));
+        assert!(errors
+            .iter()
+            .all(|error| error.kind == RuleErrorKind::Syntax
+                && error.half.is_some()
+                && srx.errors()[&error.language].contains(error)));
+
+        // the position of the skipped rule is kept
+        let english = &srx.errors()[&Language("English".into())];
+        assert!(english.windows(2).all(|x| x[0].index < x[1].index));
+    }
+
+    #[test]
+    fn conformance_suite_passes() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let suite = testkit::TestSuite::from_str(
+            &fs::read_to_string("data/conformance.txt").expect("conformance suite exists"),
+        )
+        .expect("conformance suite is valid");
+
+        let report = suite.run(&srx);
+        assert!(report.is_success(), "{}", report);
+
+        let compliance = srx.compliance_report();
+        assert!(!compliance.is_compliant());
+        assert!(compliance
+            .behaviors
+            .iter()
+            .any(|behavior| behavior.name == "cascade" && behavior.support == Support::Supported));
+    }
+
+    #[test]
+    fn regression_corpus_unchanged() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/segment.srx").expect("segment file exists"))
+                .expect("segment file is valid");
+        let suite = testkit::TestSuite::from_str(
+            &fs::read_to_string("data/regression.txt").expect("regression corpus exists"),
+        )
+        .expect("regression corpus is valid");
+
+        let report = suite.run(&srx);
+        assert!(report.is_success(), "{}", report);
+        assert_eq!(suite.cases.len(), 6);
+    }
+
+    #[test]
+    fn rule_source_preserved() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en");
+
+        assert_eq!(
+            rules.rule_source(rules.len() - 3),
+            Some((Some("^\\s*[0-9]+\\."), Some("\\s")))
+        );
+        assert_eq!(
+            rules.rule_source(rules.len() - 2),
+            Some((None, Some("\\n")))
+        );
+        assert_eq!(
+            rules.with_ascii().rule_source(0),
+            Some((Some("\\s[Ee][Tt][Cc]\\."), Some("\\s[a-z]")))
+        );
     }
 }
@@ -0,0 +1,126 @@
+use smallvec::{smallvec, SmallVec};
+
+/// Number of blocks stored inline in a [BreakMask], enough for texts up to 1 KiB.
+const INLINE_BLOCKS: usize = 16;
+
+/// Storage for the decisions of the rules which claimed byte indices of a text.
+pub(crate) trait Claims {
+    type Value: Copy;
+
+    /// Claims the index with the value if it has not been claimed before.
+    /// Returns whether the index was newly claimed.
+    fn claim(&mut self, index: usize, value: Self::Value) -> bool;
+}
+
+impl<T: Copy> Claims for Vec<Option<T>> {
+    type Value = T;
+
+    fn claim(&mut self, index: usize, value: T) -> bool {
+        if self[index].is_none() {
+            self[index] = Some(value);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Bitsets of the claimed byte indices and of the indices at which to break.
+/// Stored on the stack for short texts to avoid allocating in line-by-line pipelines.
+#[derive(Debug, Clone)]
+pub(crate) struct BreakMask {
+    claimed: SmallVec<[u64; INLINE_BLOCKS]>,
+    breaks: SmallVec<[u64; INLINE_BLOCKS]>,
+}
+
+impl BreakMask {
+    /// Creates an empty mask for a text with `len` bytes.
+    pub fn new(len: usize) -> Self {
+        let blocks = len.div_ceil(64);
+
+        BreakMask {
+            claimed: smallvec![0; blocks],
+            breaks: smallvec![0; blocks],
+        }
+    }
+
+    /// Clears the mask and resizes it for a text with `len` bytes.
+    pub fn reset(&mut self, len: usize) {
+        let blocks = len.div_ceil(64);
+
+        self.claimed.clear();
+        self.claimed.resize(blocks, 0);
+        self.breaks.clear();
+        self.breaks.resize(blocks, 0);
+    }
+
+    /// Whether the text should be split at the index.
+    pub fn is_break(&self, index: usize) -> bool {
+        self.breaks[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Iterates over the indices at which the text should be split in ascending order.
+    pub fn breaks(&self) -> impl Iterator<Item = usize> + '_ {
+        self.breaks.iter().enumerate().flat_map(|(block, &bits)| {
+            let mut bits = bits;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(block * 64 + bit)
+            })
+        })
+    }
+}
+
+impl Claims for BreakMask {
+    type Value = bool;
+
+    fn claim(&mut self, index: usize, do_break: bool) -> bool {
+        let (block, bit) = (index / 64, 1 << (index % 64));
+
+        if self.claimed[block] & bit != 0 {
+            return false;
+        }
+
+        self.claimed[block] |= bit;
+        if do_break {
+            self.breaks[block] |= bit;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_claim_wins() {
+        for len in [10, 1024, 5000] {
+            let mut mask = BreakMask::new(len);
+
+            assert!(mask.claim(3, true));
+            assert!(!mask.claim(3, false));
+            assert!(mask.claim(len - 1, false));
+            assert!(!mask.claim(len - 1, true));
+
+            assert!(mask.is_break(3));
+            assert!(!mask.is_break(len - 1));
+            assert!(!mask.is_break(0));
+        }
+    }
+
+    #[test]
+    fn breaks_ascending() {
+        let mut mask = BreakMask::new(200);
+        for (index, do_break) in [(130, true), (3, true), (64, false), (63, true), (199, true)] {
+            mask.claim(index, do_break);
+        }
+
+        assert_eq!(mask.breaks().collect::<Vec<_>>(), vec![3, 63, 130, 199]);
+    }
+}
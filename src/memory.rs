@@ -0,0 +1,83 @@
+use std::mem;
+
+use regex::Regex;
+use regex_automata::{meta, util::syntax};
+
+use super::{LanguageRegex, Prefilter, Rule, Rules, SRX};
+
+/// Approximates the heap memory in bytes used by a compiled regex.
+///
+/// `regex` does not expose the size of its compiled programs, so the pattern is compiled again with
+/// the meta regex engine `regex` uses internally and its memory usage is reported. Caches for the lazy DFA
+/// which are allocated while searching are not included.
+///
+/// `ascii` must be set if the regex was compiled by [Rules::with_ascii].
+fn regex_memory_usage(regex: &Regex, ascii: bool) -> usize {
+    let pattern = regex.as_str();
+    let compile = |unicode| {
+        meta::Builder::new()
+            .syntax(syntax::Config::new().unicode(unicode))
+            .build(pattern)
+            .ok()
+    };
+    // like `Rules::with_ascii`, which disables Unicode mode for all patterns which permit it
+    let compiled = if ascii {
+        compile(false).or_else(|| compile(true))
+    } else {
+        compile(true)
+    }
+    .expect("pattern compiles with the settings it was compiled with before");
+
+    compiled.memory_usage() + pattern.len()
+}
+
+fn rules_memory_usage(rules: &[Rule], ascii: bool) -> usize {
+    rules
+        .iter()
+        .map(|rule| mem::size_of::<Rule>() + regex_memory_usage(&rule.regex, ascii))
+        .sum()
+}
+
+fn prefilter_memory_usage(prefilter: &Prefilter) -> usize {
+    mem::size_of_val(prefilter.chars())
+}
+
+impl Rules {
+    /// Approximates the heap memory in bytes used by these rules, mostly the compiled regexes.
+    ///
+    /// Useful for budgeting how many rule sets to keep in memory. This compiles every regex again to measure it,
+    /// so it is about as slow as loading the rules and should not be called on a hot path.
+    pub fn approx_memory_usage(&self) -> usize {
+        rules_memory_usage(&self.rules, self.ascii)
+            + self.prefilter.as_ref().map_or(0, prefilter_memory_usage)
+    }
+}
+
+impl SRX {
+    /// Approximates the heap memory in bytes used by the rules of all languages and the `<languagemap>` patterns
+    /// (only counting the compiled size of patterns which have been compiled already).
+    /// See [Rules::approx_memory_usage] for details.
+    pub fn approx_memory_usage(&self) -> usize {
+        let map: usize = self
+            .map
+            .iter()
+            .map(|item| {
+                // patterns which have not been compiled yet only use memory for their source
+                mem::size_of::<LanguageRegex>()
+                    + item
+                        .regex
+                        .get()
+                        .and_then(Option::as_ref)
+                        .map_or(item.pattern.len(), |regex| regex_memory_usage(regex, false))
+                    + item.language.0.len()
+            })
+            .sum();
+        let rules: usize = self
+            .rules
+            .iter()
+            .map(|(language, rules)| language.0.len() + rules_memory_usage(rules, false))
+            .sum();
+
+        map + rules
+    }
+}
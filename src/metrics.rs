@@ -0,0 +1,118 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use super::{mask::Claims, Profile, Rules};
+
+/// Counters of how often each rule claimed a position while splitting, shared by all users of the rules.
+#[derive(Debug)]
+pub(crate) struct RuleMetrics {
+    claims: Vec<AtomicUsize>,
+}
+
+impl RuleMetrics {
+    fn new(rules: usize) -> Self {
+        RuleMetrics {
+            claims: (0..rules).map(|_| AtomicUsize::new(0)).collect(),
+        }
+    }
+}
+
+/// Claims positions in the wrapped claims and counts each newly claimed position for the claiming rule.
+pub(crate) struct Counting<'a, C> {
+    claims: &'a mut C,
+    metrics: &'a RuleMetrics,
+}
+
+impl<'a, C> Counting<'a, C> {
+    pub fn new(claims: &'a mut C, metrics: &'a RuleMetrics) -> Self {
+        Counting { claims, metrics }
+    }
+}
+
+impl<C: Claims> Claims for Counting<'_, C> {
+    // the index of the rule and the value for the wrapped claims
+    type Value = (usize, C::Value);
+
+    fn claim(&mut self, index: usize, (rule, value): Self::Value) -> bool {
+        let claimed = self.claims.claim(index, value);
+        if claimed {
+            self.metrics.claims[rule].fetch_add(1, Ordering::Relaxed);
+        }
+        claimed
+    }
+}
+
+impl Rules {
+    /// Counts how often each rule claims a position (i. e. breaks or prevents breaking) from now on, e. g. to find out
+    /// which rules matter for a corpus in production. Retrieve the counts with [Rules::metrics].
+    ///
+    /// The counters are shared by clones of these rules and by every [Splitter](crate::Splitter) and
+    /// [Segmenter](crate::Segmenter) using them. Counting costs an atomic increment per claimed position.
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = Some(Arc::new(RuleMetrics::new(self.rules.len())));
+        self
+    }
+
+    /// Gets a snapshot of the counts since [Rules::with_metrics] (or the last [Rules::reset_metrics]) as a [Profile],
+    /// which can also be used to reorder the rules. `None` if the rules do not record metrics.
+    pub fn metrics(&self) -> Option<Profile> {
+        self.metrics.as_ref().map(|metrics| Profile {
+            claims: metrics
+                .claims
+                .iter()
+                .map(|count| count.load(Ordering::Relaxed))
+                .collect(),
+        })
+    }
+
+    /// Sets all counts to zero.
+    pub fn reset_metrics(&self) {
+        for count in self.metrics.iter().flat_map(|metrics| &metrics.claims) {
+            count.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use crate::SRX;
+
+    #[test]
+    fn claims_counted() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let texts = ["Hello. Bye.", "Mr. Smith is here. Yes."];
+        let rules = srx.language_rules("en").with_metrics();
+        assert!(srx.language_rules("en").metrics().is_none());
+
+        let clone = rules.clone();
+        for text in texts {
+            clone.split_ranges(text);
+        }
+        let mut splitter = rules.splitter();
+        splitter.split_ranges(texts[0]);
+
+        let mut expected = rules.profile(texts).claims().to_vec();
+        expected
+            .iter_mut()
+            .zip(rules.profile([texts[0]]).claims())
+            .for_each(|(count, extra)| *count += extra);
+
+        let metrics = rules.metrics().expect("metrics are recorded");
+        assert_eq!(metrics.claims(), expected);
+        assert!(metrics.claims().iter().any(|&count| count > 0));
+
+        rules.reset_metrics();
+        assert!(rules
+            .metrics()
+            .expect("metrics are recorded")
+            .claims()
+            .iter()
+            .all(|&count| count == 0));
+    }
+}
@@ -0,0 +1,122 @@
+//! Bindings for Swift and Kotlin (and Python) with [UniFFI](https://mozilla.github.io/uniffi-rs/).
+//!
+//! The interface is defined by the annotated types below. Bindings are generated from the compiled library, e. g.
+//!
+//! ```text
+//! cargo rustc --release --lib --features uniffi --crate-type cdylib
+//! cargo run --features uniffi-bindgen --bin uniffi-bindgen -- generate \
+//!     --library target/release/libsrx.so --language swift --out-dir bindings
+//! ```
+//!
+//! and used like
+//!
+//! ```swift
+//! let srx = try Srx(xml: xml)
+//! let segments = srx.languageRules(langCode: "en").split(text: text)
+//! ```
+use std::{str::FromStr, sync::Arc};
+
+use super::{Rules, SRX};
+
+/// Errors of the bindings.
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum SrxError {
+    #[error("invalid SRX file: {0}")]
+    InvalidSrx(String),
+}
+
+/// A segment of a text. Offsets are in UTF-8 bytes.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct Segment {
+    pub text: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// SRX rules for all languages, loaded from an XML string.
+#[derive(Debug, uniffi::Object)]
+pub struct Srx {
+    srx: SRX,
+}
+
+#[uniffi::export]
+impl Srx {
+    #[uniffi::constructor]
+    pub fn new(xml: String) -> Result<Arc<Self>, SrxError> {
+        SRX::from_str(&xml)
+            .map(|srx| Arc::new(Srx { srx }))
+            .map_err(|error| SrxError::InvalidSrx(error.to_string()))
+    }
+
+    /// Gets the rules for a language code, see [SRX::language_rules].
+    pub fn language_rules(&self, lang_code: String) -> Arc<LanguageRules> {
+        Arc::new(LanguageRules {
+            rules: self.srx.language_rules(lang_code),
+        })
+    }
+
+    /// Gets the names of the languages with rules.
+    pub fn languages(&self) -> Vec<String> {
+        self.srx
+            .languages()
+            .into_iter()
+            .map(|language| language.0.clone())
+            .collect()
+    }
+}
+
+/// The rules of one language.
+#[derive(Debug, uniffi::Object)]
+pub struct LanguageRules {
+    rules: Rules,
+}
+
+#[uniffi::export]
+impl LanguageRules {
+    /// Splits the text into segments.
+    pub fn split(&self, text: String) -> Vec<String> {
+        self.rules.split(&text).map(str::to_owned).collect()
+    }
+
+    /// Splits the text into segments with their offsets.
+    pub fn segments(&self, text: String) -> Vec<Segment> {
+        self.rules
+            .split_ranges(&text)
+            .into_iter()
+            .map(|range| Segment {
+                text: text[range.clone()].to_owned(),
+                start: range.start as u64,
+                end: range.end as u64,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn text_segmented() {
+        let srx = Srx::new(fs::read_to_string("data/example.srx").expect("example file exists"))
+            .expect("example file is valid");
+        let rules = srx.language_rules("en".into());
+
+        assert_eq!(rules.split("Hello. Bye.".into()), vec!["Hello.", " Bye."]);
+        assert_eq!(
+            rules.segments("Hello. Bye.".into())[1],
+            Segment {
+                text: " Bye.".into(),
+                start: 6,
+                end: 11
+            }
+        );
+        assert!(matches!(
+            Srx::new("<srx>".into()),
+            Err(SrxError::InvalidSrx(_))
+        ));
+    }
+}
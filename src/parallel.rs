@@ -0,0 +1,89 @@
+use rayon::prelude::*;
+
+use super::Rules;
+
+impl Rules {
+    /// Splits many texts in parallel on the rayon thread pool. Gets the segments of every text, in the order of the texts.
+    ///
+    /// The rules are shared by all workers, each of which reuses one [Splitter](crate::Splitter) for all texts it splits.
+    pub fn par_split_batch<'t, S: AsRef<str> + Sync>(&self, texts: &'t [S]) -> Vec<Vec<&'t str>> {
+        texts.par_iter().segment_with(self).collect()
+    }
+}
+
+/// Splitting the texts of a rayon [ParallelIterator] into segments.
+///
+/// ```
+/// # #[cfg(feature = "from_xml")]
+/// # fn main() -> Result<(), srx::Error> {
+/// use std::{fs, str::FromStr};
+/// use rayon::prelude::*;
+/// use srx::{ParallelSegmentExt, SRX};
+///
+/// let srx = SRX::from_str(&fs::read_to_string("data/example.srx").unwrap())?;
+/// let rules = srx.language_rules("en");
+/// let documents = vec!["Hello. Bye.".to_string(), "One.".to_string()];
+///
+/// let segments: Vec<Vec<&str>> = documents.par_iter().segment_with(&rules).collect();
+/// assert_eq!(segments, vec![vec!["Hello.", " Bye."], vec!["One."]]);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "from_xml"))]
+/// # fn main() {}
+/// ```
+pub trait ParallelSegmentExt<'t, T: AsRef<str> + ?Sized + 't>:
+    ParallelIterator<Item = &'t T>
+{
+    /// Maps every text to its segments. Each worker reuses one [Splitter](crate::Splitter) for all texts it splits.
+    fn segment_with<'r>(self, rules: &'r Rules) -> impl ParallelIterator<Item = Vec<&'t str>> + 'r
+    where
+        Self: 'r;
+}
+
+impl<'t, T, I> ParallelSegmentExt<'t, T> for I
+where
+    T: AsRef<str> + ?Sized + Sync + 't,
+    I: ParallelIterator<Item = &'t T>,
+{
+    fn segment_with<'r>(self, rules: &'r Rules) -> impl ParallelIterator<Item = Vec<&'t str>> + 'r
+    where
+        Self: 'r,
+    {
+        self.map_init(
+            move || rules.splitter(),
+            |splitter, text| splitter.split(text.as_ref()).collect(),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+    use crate::SRX;
+
+    #[test]
+    fn batches_match_split() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let rules = srx.language_rules("en");
+        let documents: Vec<String> = (0..100)
+            .map(|i| format!("Document {}. It has two sentences.", i))
+            .collect();
+        let expected: Vec<Vec<&str>> = documents
+            .iter()
+            .map(|document| rules.split(document).collect())
+            .collect();
+
+        assert_eq!(rules.par_split_batch(&documents), expected);
+        assert_eq!(
+            documents
+                .par_iter()
+                .segment_with(&rules)
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
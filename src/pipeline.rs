@@ -0,0 +1,169 @@
+use std::{fmt, ops::Range, sync::Arc};
+
+use regex::Regex;
+
+use super::Rules;
+
+type CustomFn = dyn Fn(&str) -> Option<Range<usize>> + Send + Sync;
+
+#[derive(Clone)]
+enum Step {
+    Trim,
+    DropMatching(Regex),
+    Custom(Arc<CustomFn>),
+}
+
+impl fmt::Debug for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Trim => f.write_str("Trim"),
+            Step::DropMatching(regex) => f.debug_tuple("DropMatching").field(regex).finish(),
+            Step::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+impl Step {
+    /// Applies the step to the segment at the range, gets the range of the processed segment or `None` if it is dropped.
+    fn apply(&self, text: &str, range: Range<usize>) -> Option<Range<usize>> {
+        let segment = &text[range.clone()];
+
+        match self {
+            Step::Trim => {
+                let trimmed = segment.trim_start();
+                let start = range.start + (segment.len() - trimmed.len());
+                let end = start + trimmed.trim_end().len();
+                Some(start..end).filter(|range| !range.is_empty())
+            }
+            Step::DropMatching(regex) => Some(range).filter(|_| !regex.is_match(segment)),
+            Step::Custom(custom) => match custom(segment) {
+                Some(inner) if inner.start <= inner.end && segment.get(inner.clone()).is_some() => {
+                    Some(range.start + inner.start..range.start + inner.end)
+                }
+                // invalid ranges keep the segment
+                Some(_) => Some(range),
+                None => None,
+            },
+        }
+    }
+}
+
+/// Steps applied to every segment after splitting, e. g. to trim segments and drop boilerplate, so consumers
+/// do not have to wrap the split iterator themselves. Segments stay slices of the text, so their offsets are kept.
+///
+/// ```
+/// # #[cfg(feature = "from_xml")]
+/// # fn main() -> Result<(), srx::Error> {
+/// use std::{fs, str::FromStr};
+/// use regex::Regex;
+/// use srx::{Pipeline, SRX};
+///
+/// let rules = SRX::from_str(&fs::read_to_string("data/example.srx").unwrap())?.language_rules("en");
+/// let pipeline = Pipeline::new()
+///     .trim()
+///     .drop_matching(Regex::new("^Page [0-9]+$").unwrap());
+///
+/// assert_eq!(
+///     pipeline.split(&rules, "Hello there. Page 3\n  Bye. ").collect::<Vec<_>>(),
+///     vec!["Hello there.", "Bye."]
+/// );
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "from_xml"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline {
+    steps: Vec<Step>,
+}
+
+impl Pipeline {
+    /// Creates a pipeline without steps, which keeps the segments as they are.
+    pub fn new() -> Self {
+        Pipeline::default()
+    }
+
+    /// Strips whitespace from the start and end of every segment and drops segments which are empty afterwards.
+    pub fn trim(mut self) -> Self {
+        self.steps.push(Step::Trim);
+        self
+    }
+
+    /// Drops segments which the regex matches (anywhere, anchor it to match whole segments).
+    pub fn drop_matching(mut self, regex: Regex) -> Self {
+        self.steps.push(Step::DropMatching(regex));
+        self
+    }
+
+    /// Processes segments with a closure which gets the segment and returns the byte range of the segment to
+    /// keep, or `None` to drop it. Ranges which are not inside the segment or not at character boundaries keep
+    /// the segment unchanged.
+    pub fn custom<F>(mut self, custom: F) -> Self
+    where
+        F: Fn(&str) -> Option<Range<usize>> + Send + Sync + 'static,
+    {
+        self.steps.push(Step::Custom(Arc::new(custom)));
+        self
+    }
+
+    /// Applies the steps in order to the segment at the range, gets the range of the processed segment
+    /// or `None` if it is dropped.
+    pub fn process(&self, text: &str, range: Range<usize>) -> Option<Range<usize>> {
+        self.steps
+            .iter()
+            .try_fold(range, |range, step| step.apply(text, range))
+    }
+
+    /// Splits the text with the rules and processes the segments, gets the ranges of the processed segments.
+    pub fn split_ranges(&self, rules: &Rules, text: &str) -> Vec<Range<usize>> {
+        rules
+            .split_ranges(text)
+            .into_iter()
+            .filter_map(|range| self.process(text, range))
+            .collect()
+    }
+
+    /// Splits the text with the rules and processes the segments.
+    pub fn split<'t, 'b>(&self, rules: &Rules, text: &'t str) -> impl Iterator<Item = &'t str> + 'b
+    where
+        't: 'b,
+    {
+        self.split_ranges(rules, text)
+            .into_iter()
+            .map(move |range| &text[range])
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+    use crate::SRX;
+
+    #[test]
+    fn steps_applied_in_order() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en");
+        let text = "First one. * Second one. Third one.  ";
+
+        let pipeline = Pipeline::new()
+            .trim()
+            .custom(|segment| Some(segment.strip_prefix("* ").map_or(0, |_| 2)..segment.len()))
+            .custom(|segment| Some(0..segment.len() + 1))
+            .drop_matching(Regex::new("^Third").expect("regex is valid"));
+
+        let ranges = pipeline.split_ranges(&rules, text);
+        assert_eq!(ranges, vec![0..10, 13..24]);
+        assert_eq!(
+            pipeline.split(&rules, text).collect::<Vec<_>>(),
+            vec!["First one.", "Second one."]
+        );
+        assert_eq!(
+            Pipeline::new().split(&rules, text).collect::<String>(),
+            text
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use tokenizers::{normalizer::Range, PreTokenizedString, PreTokenizer};
+
+use super::Rules;
+
+/// A pre-tokenizer for the [`tokenizers`](https://docs.rs/tokenizers) crate which splits at the sentence
+/// boundaries found by [Rules], so tokens never span two segments.
+///
+/// Segments keep their whitespace, so it is usually combined with a word-level pre-tokenizer
+/// (e. g. in a `Sequence`) which runs afterwards. Offsets into the original string are tracked as usual.
+#[derive(Debug, Clone)]
+pub struct SentencePreTokenizer {
+    rules: Rules,
+}
+
+impl SentencePreTokenizer {
+    pub fn new(rules: Rules) -> Self {
+        SentencePreTokenizer { rules }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+}
+
+impl PreTokenizer for SentencePreTokenizer {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> tokenizers::Result<()> {
+        pretokenized.split(|_, normalized| {
+            Ok(self
+                .rules
+                .split_ranges(normalized.get())
+                .into_iter()
+                .filter_map(|range| normalized.slice(Range::Normalized(range)))
+                .collect::<Vec<_>>())
+        })
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use tokenizers::{OffsetReferential, OffsetType};
+
+    use super::*;
+    use crate::SRX;
+
+    #[test]
+    fn sentences_pre_tokenized() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let pre_tokenizer = SentencePreTokenizer::new(srx.language_rules("en"));
+
+        let mut pretokenized = PreTokenizedString::from("Hello. Bye.");
+        pre_tokenizer
+            .pre_tokenize(&mut pretokenized)
+            .expect("pre-tokenizing succeeds");
+
+        let splits: Vec<_> = pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .into_iter()
+            .map(|(text, offsets, _)| (text, offsets))
+            .collect();
+        assert_eq!(splits, vec![("Hello.", (0, 6)), (" Bye.", (6, 11))]);
+    }
+}
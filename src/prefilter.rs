@@ -0,0 +1,155 @@
+use std::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+/// Characters which are considered sentence-terminal by [Prefilter::default].
+const DEFAULT_CHARS: &[char] = &[
+    '.', '!', '?', ':', ';', '\n', '\r', '…', '。', '．', '｡', '！', '？', '؟', '।',
+];
+
+/// Radius in bytes used by [Prefilter::default].
+const DEFAULT_RADIUS: usize = 64;
+
+/// Restricts rule evaluation to windows around candidate characters (usually sentence-terminal punctuation).
+///
+/// Candidates are found with `memchr`, which is much faster than running every rule over the entire text.
+/// Text without any candidate is not scanned by the rules at all.
+///
+/// Each rule is evaluated on windows around the candidates which extend as far as the longest possible match of the rule,
+/// so every match containing a candidate is found. If the match length of a rule is unbounded (e. g. because it contains `+`),
+/// windows extend `radius` bytes instead.
+///
+/// This is an approximation: a rule can only produce or prevent a break if its match lies completely within
+/// such a window. Rules which break without any of the candidate characters nearby are never evaluated.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prefilter {
+    chars: Vec<char>,
+    radius: usize,
+}
+
+impl Default for Prefilter {
+    fn default() -> Self {
+        Prefilter::new(DEFAULT_CHARS.iter().copied(), DEFAULT_RADIUS)
+    }
+}
+
+impl Prefilter {
+    /// Creates a new prefilter considering the given candidate characters.
+    /// Rules with unbounded match length are evaluated on windows extending `radius` bytes
+    /// to the left and right of each candidate.
+    pub fn new<I: IntoIterator<Item = char>>(chars: I, radius: usize) -> Self {
+        let mut chars: Vec<_> = chars.into_iter().collect();
+        chars.sort_unstable();
+        chars.dedup();
+
+        Prefilter { chars, radius }
+    }
+
+    /// The candidate characters.
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// The radius around each candidate in bytes for rules with unbounded match length.
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    /// Gets the sorted byte indices of all candidate characters in the text.
+    /// Clears `positions` and stores them in it.
+    pub(crate) fn candidates_into(&self, text: &str, positions: &mut Vec<usize>) {
+        // collected on the stack so that texts without candidates are scanned without allocating
+        let mut lead_bytes: SmallVec<[u8; 16]> = self
+            .chars
+            .iter()
+            .map(|c| c.encode_utf8(&mut [0; 4]).as_bytes()[0])
+            .collect();
+        lead_bytes.dedup();
+
+        let bytes = text.as_bytes();
+        positions.clear();
+
+        // memchr searches for at most three bytes at once, so search chunks of lead bytes
+        // and verify that the full character matches for non-ASCII candidates
+        for chunk in lead_bytes.chunks(3) {
+            let is_candidate = |&i: &usize| {
+                text[i..]
+                    .chars()
+                    .next()
+                    .is_some_and(|c| self.chars.binary_search(&c).is_ok())
+            };
+
+            match *chunk {
+                [a] => positions.extend(memchr::memchr_iter(a, bytes).filter(is_candidate)),
+                [a, b] => positions.extend(memchr::memchr2_iter(a, b, bytes).filter(is_candidate)),
+                [a, b, c] => {
+                    positions.extend(memchr::memchr3_iter(a, b, c, bytes).filter(is_candidate))
+                }
+                _ => unreachable!("chunks have between one and three elements"),
+            }
+        }
+
+        if lead_bytes.len() > 3 {
+            positions.sort_unstable();
+        }
+    }
+}
+
+/// Gets the merged, sorted windows extending `radius` bytes around the candidates in the text.
+/// Clears `windows` and stores them in it. Windows always start and end at character boundaries.
+pub(crate) fn windows_into(
+    text: &str,
+    from: usize,
+    candidates: &[usize],
+    radius: usize,
+    windows: &mut Vec<Range<usize>>,
+) {
+    windows.clear();
+
+    for &position in candidates {
+        let mut start = position.saturating_sub(radius).max(from);
+        while !text.is_char_boundary(start) {
+            start -= 1;
+        }
+        let char_len = text[position..].chars().next().map_or(1, char::len_utf8);
+        let mut end = (position + char_len + radius).min(text.len());
+        while !text.is_char_boundary(end) {
+            end += 1;
+        }
+
+        match windows.last_mut() {
+            Some(last) if last.end >= start => last.end = end,
+            _ => windows.push(start..end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windows(prefilter: &Prefilter, text: &str) -> Vec<Range<usize>> {
+        let mut candidates = Vec::new();
+        let mut windows = Vec::new();
+
+        prefilter.candidates_into(text, &mut candidates);
+        windows_into(text, 0, &candidates, prefilter.radius(), &mut windows);
+        windows
+    }
+
+    #[test]
+    fn windows_are_merged() {
+        let prefilter = Prefilter::new(vec!['.', '。'], 2);
+
+        assert!(windows(&prefilter, "no candidates").is_empty());
+        assert_eq!(windows(&prefilter, "a. b. cdefgh."), vec![0..7, 10..13]);
+        assert_eq!(windows(&prefilter, "ab。cd"), vec![0..7]);
+    }
+}
@@ -0,0 +1,128 @@
+use std::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Invisible characters which are removed from the text before the rules are matched, set with
+/// [Rules::with_preprocessing](crate::Rules::with_preprocessing). Such characters routinely end up in extracted
+/// text and keep patterns like `\.\s` from matching. Boundaries are mapped back to the original text, so the
+/// segments still cover it byte for byte; removed characters stay in the segment before the boundary.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", default)
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Preprocessing {
+    /// Remove control characters (Unicode category `Cc`) except whitespace like `\n` and `\t`.
+    pub control_characters: bool,
+    /// Remove zero-width spaces (U+200B), word joiners (U+2060) and zero-width no-break spaces (U+FEFF).
+    pub zero_width_spaces: bool,
+    /// Remove soft hyphens (U+00AD).
+    pub soft_hyphens: bool,
+}
+
+impl Preprocessing {
+    /// Removes all supported invisible characters.
+    pub fn invisible() -> Self {
+        Preprocessing {
+            control_characters: true,
+            zero_width_spaces: true,
+            soft_hyphens: true,
+        }
+    }
+
+    fn removes(&self, c: char) -> bool {
+        match c {
+            '\u{200B}' | '\u{2060}' | '\u{FEFF}' => self.zero_width_spaces,
+            '\u{AD}' => self.soft_hyphens,
+            c if c.is_control() => self.control_characters && !c.is_whitespace(),
+            _ => false,
+        }
+    }
+
+    /// Writes the text without the removed characters to `stripped` and the byte index in the text of each byte
+    /// of `stripped` to `offsets`. Returns `false` without touching the buffers if nothing would be removed.
+    pub(crate) fn strip_into(
+        &self,
+        text: &str,
+        stripped: &mut String,
+        offsets: &mut Vec<usize>,
+    ) -> bool {
+        if *self == Preprocessing::default() || !text.chars().any(|c| self.removes(c)) {
+            return false;
+        }
+
+        stripped.clear();
+        offsets.clear();
+        for (index, c) in text.char_indices() {
+            if !self.removes(c) {
+                stripped.push(c);
+                offsets.extend(index..index + c.len_utf8());
+            }
+        }
+        true
+    }
+}
+
+/// Maps the ranges of segments of the stripped text to contiguous ranges covering the original text.
+pub(crate) fn restore_ranges(offsets: &[usize], text_len: usize, ranges: &mut Vec<Range<usize>>) {
+    let boundaries: Vec<usize> = ranges
+        .iter()
+        .skip(1)
+        .map(|range| offsets[range.start])
+        .collect();
+
+    ranges.clear();
+    let mut start = 0;
+    for boundary in boundaries {
+        ranges.push(start..boundary);
+        start = boundary;
+    }
+    if start < text_len {
+        ranges.push(start..text_len);
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+    use crate::SRX;
+
+    #[test]
+    fn boundaries_mapped_to_original_text() {
+        let rules =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid")
+                .language_rules("en");
+        let text = "A soft\u{AD}hyphen.\u{200B} Then\u{7} more.\u{FEFF} End.";
+
+        assert_eq!(rules.split(text).count(), 1);
+
+        let rules = rules.with_preprocessing(Preprocessing::invisible());
+        assert_eq!(
+            rules.split(text).collect::<Vec<_>>(),
+            vec![
+                "A soft\u{AD}hyphen.\u{200B}",
+                " Then\u{7} more.\u{FEFF}",
+                " End."
+            ]
+        );
+        assert_eq!(rules.split(text).collect::<String>(), text);
+        assert_eq!(
+            rules
+                .splitter()
+                .split("\u{200B}\u{200B}")
+                .collect::<Vec<_>>(),
+            vec!["\u{200B}\u{200B}"]
+        );
+
+        let rules = rules.with_preprocessing(Preprocessing {
+            soft_hyphens: true,
+            ..Preprocessing::default()
+        });
+        assert_eq!(rules.split(text).count(), 1);
+    }
+}
@@ -0,0 +1,141 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{
+    engine::{self, Candidates},
+    Rules,
+};
+
+/// Counts how often each rule claimed a position (i. e. broke or prevented breaking) on a sample corpus.
+/// Created by [Rules::profile], or from the counts while splitting with the `metrics` feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub(crate) claims: Vec<usize>,
+}
+
+impl Profile {
+    /// The number of claimed positions for each rule, in the order of the profiled rules.
+    pub fn claims(&self) -> &[usize] {
+        &self.claims
+    }
+
+    /// Learns an order of the rules which evaluates frequently claiming rules first.
+    ///
+    /// Reordering rules must not change the segmentation. Rules are only reordered within runs of
+    /// consecutive rules with the same break decision: whichever of them claims a position first,
+    /// the outcome is the same.
+    ///
+    /// # Panics
+    ///
+    /// If the profile was created from a different number of rules.
+    pub fn order(&self, rules: &Rules) -> RuleOrder {
+        assert_eq!(
+            self.claims.len(),
+            rules.len(),
+            "profile must have been created from the same rules"
+        );
+
+        let mut order: Vec<usize> = (0..rules.len()).collect();
+        for run in
+            order.chunk_by_mut(|a, b| rules.rules[*a].do_break() == rules.rules[*b].do_break())
+        {
+            // sort is stable, so ties keep their original order
+            run.sort_by_key(|&i| std::cmp::Reverse(self.claims[i]));
+        }
+
+        RuleOrder(order)
+    }
+}
+
+/// An order of rules, learned by [Profile::order] and applied by [Rules::reordered].
+/// Contains the original index of each rule at its new position.
+///
+/// Can be persisted (e. g. with the `serde` feature) and applied to the same rules loaded again later.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RuleOrder(pub Vec<usize>);
+
+impl Rules {
+    /// Runs the rules on a sample corpus and counts how often each rule claims a position.
+    pub fn profile<'a, I: IntoIterator<Item = &'a str>>(&self, texts: I) -> Profile {
+        let mut claims = vec![0; self.rules.len()];
+        let mut locations = engine::capture_locations(&self.rules);
+        let mut positions = Vec::new();
+        let mut windows = Vec::new();
+
+        for text in texts {
+            let candidates = self.prefilter.as_ref().map(|prefilter| {
+                prefilter.candidates_into(text, &mut positions);
+                Candidates {
+                    positions: &positions,
+                    radius: prefilter.radius(),
+                }
+            });
+
+            let mut claimed_by = vec![None; text.len()];
+            engine::evaluate(
+                &self.rules,
+                text,
+                0,
+                candidates,
+                &mut windows,
+                &mut locations,
+                &mut claimed_by,
+                |i, _| i,
+            );
+
+            for i in claimed_by.into_iter().flatten() {
+                claims[i] += 1;
+            }
+        }
+
+        Profile { claims }
+    }
+
+    /// Gets these rules in a different order, e. g. learned with [Profile::order].
+    ///
+    /// Returns `None` if the order is not valid for these rules i. e. if it is not a permutation of the rules
+    /// or if it moves a rule past another rule with a different break decision.
+    pub fn reordered(&self, order: &RuleOrder) -> Option<Rules> {
+        let order = &order.0;
+        if order.len() != self.rules.len() {
+            return None;
+        }
+
+        let mut seen = vec![false; order.len()];
+        for &i in order {
+            if *seen.get(i)? {
+                return None;
+            }
+            seen[i] = true;
+        }
+
+        // the order is valid iff every position keeps the break decision and each run of equal decisions
+        // keeps its members, which is the case iff the order sorted within each run is the identity
+        let mut sorted = order.clone();
+        for run in
+            sorted.chunk_by_mut(|a, b| self.rules[*a].do_break() == self.rules[*b].do_break())
+        {
+            run.sort_unstable();
+        }
+        if sorted
+            .iter()
+            .enumerate()
+            .any(|(position, &i)| position != i)
+        {
+            return None;
+        }
+
+        // counters of the original order would be attributed to the wrong rules
+        Some(Rules {
+            rules: order.iter().map(|&i| self.rules[i].clone()).collect(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            ..self.clone()
+        })
+    }
+}
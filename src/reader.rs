@@ -0,0 +1,194 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read},
+    str,
+};
+
+use super::{Rules, Splitter};
+
+/// Default number of bytes read at once by [SplitReader].
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Default number of bytes of context kept around chunk edges by [SplitReader].
+pub(crate) const DEFAULT_OVERLAP: usize = 1024;
+
+/// Text received in chunks and the segments which are final already.
+/// Shared by [SplitReader] and the other adapters splitting text which arrives piece by piece.
+pub(crate) struct ChunkBuffer<'r> {
+    splitter: Splitter<'r>,
+    pub(crate) overlap: usize,
+    // bytes which could not be decoded yet because a character was cut at the chunk edge
+    pub(crate) pending: Vec<u8>,
+    buffer: String,
+    // index into the buffer where the next segment starts
+    start: usize,
+    // index into the buffer up to which boundaries are final
+    settled: usize,
+    segments: VecDeque<String>,
+    pub(crate) eof: bool,
+}
+
+impl<'r> ChunkBuffer<'r> {
+    pub(crate) fn new(rules: &'r Rules, overlap: usize) -> Self {
+        ChunkBuffer {
+            splitter: rules.splitter(),
+            overlap,
+            pending: Vec::new(),
+            buffer: String::new(),
+            start: 0,
+            settled: 0,
+            segments: VecDeque::new(),
+            eof: false,
+        }
+    }
+
+    /// Decodes the pending bytes and queues the segments which are final now.
+    /// If `eof` is set, no more bytes follow and all remaining text is queued.
+    pub(crate) fn push_pending(&mut self, eof: bool) -> io::Result<()> {
+        self.eof = eof;
+
+        let valid_up_to = match str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(error) if error.error_len().is_none() && !self.eof => error.valid_up_to(),
+            Err(error) => return Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+        };
+
+        self.buffer
+            .push_str(str::from_utf8(&self.pending[..valid_up_to]).expect("prefix was validated"));
+        self.pending.drain(..valid_up_to);
+
+        self.queue_segments();
+        Ok(())
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<String> {
+        self.segments.pop_front()
+    }
+
+    /// Splits the part of the buffer after the final boundaries and queues all segments before boundaries
+    /// which are final now.
+    fn queue_segments(&mut self) {
+        let trusted_end = if self.eof {
+            self.buffer.len()
+        } else {
+            self.buffer.len().saturating_sub(self.overlap)
+        };
+
+        // matches deciding boundaries after `settled` start at most `overlap` bytes before it
+        let from = floor_char_boundary(&self.buffer, self.settled.saturating_sub(self.overlap));
+        for range in self.splitter.split_ranges_from(&self.buffer, from) {
+            if range.start > self.settled.max(self.start) && range.start <= trusted_end {
+                self.segments
+                    .push_back(self.buffer[self.start..range.start].to_owned());
+                self.start = range.start;
+            }
+        }
+        self.settled = self.settled.max(trusted_end);
+
+        if self.eof {
+            if self.start < self.buffer.len() {
+                self.segments
+                    .push_back(self.buffer[self.start..].to_owned());
+            }
+            self.buffer.clear();
+            self.start = 0;
+            self.settled = 0;
+            return;
+        }
+
+        // only keep `overlap` bytes of context before the next segment, and one more character so that
+        // rules are never matched from the start of the buffer, where `^` would match
+        let keep = floor_char_boundary(&self.buffer, self.start.saturating_sub(self.overlap));
+        let drain_end = self.buffer[..keep]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i);
+        self.buffer.drain(..drain_end);
+        self.start -= drain_end;
+        self.settled -= drain_end;
+    }
+}
+
+/// Gets the largest character boundary in the text which is not after the index.
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Iterator over the segments of text read from a [Read]er in chunks. Created by [Rules::split_reader].
+///
+/// Memory usage is bounded by the chunk size plus the length of the longest segment instead of the length of the input.
+///
+/// # Chunking strategy
+///
+/// Text is read in chunks of `chunk_size` bytes and appended to a buffer which starts `overlap` bytes
+/// before the first segment which has not been emitted yet. Boundaries within the last `overlap` bytes of the buffer
+/// are not trusted yet since rules could need more context to the right; they are reevaluated once the next
+/// chunk has been read. All other boundaries are final and the segments before them are emitted.
+/// Rules are only run on the text after the final boundaries (and `overlap` bytes before them), so splitting
+/// long segments does not take quadratic time. The text before is context, e. g. `^` only matches at the
+/// start of the input.
+///
+/// Boundaries are identical to splitting the entire text at once as long as no rule match is longer than
+/// `overlap` bytes.
+pub struct SplitReader<'r, R> {
+    chunks: ChunkBuffer<'r>,
+    reader: R,
+    chunk_size: usize,
+}
+
+impl<'r, R: Read> SplitReader<'r, R> {
+    pub(crate) fn new(rules: &'r Rules, reader: R) -> Self {
+        SplitReader {
+            chunks: ChunkBuffer::new(rules, DEFAULT_OVERLAP),
+            reader,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Sets the number of bytes read at once (default 64 KiB) and the number of bytes
+    /// of context kept around chunk edges (default 1 KiB).
+    ///
+    /// # Panics
+    ///
+    /// If `chunk_size` is zero.
+    pub fn with_chunk_size(mut self, chunk_size: usize, overlap: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+
+        self.chunk_size = chunk_size;
+        self.chunks.overlap = overlap;
+        self
+    }
+
+    /// Reads the next chunk and queues the segments which are final now. Sets `eof` if the reader is exhausted.
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let read = (&mut self.reader)
+            .take(self.chunk_size as u64)
+            .read_to_end(&mut self.chunks.pending)?;
+
+        self.chunks.push_pending(read == 0)
+    }
+}
+
+impl<'r, R: Read> Iterator for SplitReader<'r, R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(segment) = self.chunks.pop() {
+                return Some(Ok(segment));
+            }
+
+            if self.chunks.eof {
+                return None;
+            }
+
+            if let Err(error) = self.read_chunk() {
+                self.chunks.eof = true;
+                return Some(Err(error));
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::{fmt, str::FromStr};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Error, Language, SRX};
+
+/// How severe a [Diagnostic] is.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "lowercase")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The rules are loaded as written but likely do not behave as intended.
+    Warning,
+    /// Part of the rules could not be loaded.
+    Error,
+}
+
+/// What a [Diagnostic] is about.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "kebab-case")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DiagnosticKind {
+    /// A rule was skipped, see [SRX::errors].
+    SkippedRule,
+    /// The rules of a language are not used by any `<languagemap>`.
+    UnusedLanguage,
+    /// A rule can never decide, see [Rules::shadowed_rules](crate::Rules::shadowed_rules).
+    ShadowedRule,
+    /// A rule can not match any text, see [Rules::never_matching_rules](crate::Rules::never_matching_rules).
+    NeverMatchingRule,
+}
+
+/// A finding about the rules of an SRX file.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    /// The `<languagerule>` the finding is about.
+    pub language: Language,
+    /// The index of the rule within its `<languagerule>` (counting skipped rules), if the finding is about a rule.
+    pub rule: Option<usize>,
+    pub message: String,
+}
+
+/// All diagnostics of loading an SRX file, created by [SRX::load_with_report].
+/// With the `serde` feature, it can be serialized e. g. to JSON for CI systems and editors.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+    /// Ordered by language, then by rule.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    /// Whether there are no diagnostics at all.
+    pub fn is_clean(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Whether any diagnostic is an error.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            let severity = match diagnostic.severity {
+                Severity::Warning => "warning",
+                Severity::Error => "error",
+            };
+            match diagnostic.rule {
+                Some(rule) => write!(f, "{}: {} rule {}: ", severity, diagnostic.language.0, rule)?,
+                None => write!(f, "{}: {}: ", severity, diagnostic.language.0)?,
+            }
+            writeln!(f, "{}", diagnostic.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl SRX {
+    /// Loads SRX content like [SRX::from_str] and collects all diagnostics about it: rules which were skipped,
+    /// language rules which are never used, and rules which are shadowed by earlier rules or can not match.
+    ///
+    /// # Errors
+    ///
+    /// If the content is not valid SRX, see [SRX::from_reader].
+    #[cfg_attr(docsrs, doc(cfg(feature = "from_xml")))]
+    pub fn load_with_report(content: &str) -> Result<(SRX, Report), Error> {
+        let srx = SRX::from_str(content)?;
+        let report = srx.report();
+        Ok((srx, report))
+    }
+
+    fn report(&self) -> Report {
+        let mut diagnostics = Vec::new();
+
+        for (language, rules) in &self.rules {
+            let errors = &self.errors[language];
+            if !self.map.iter().any(|entry| entry.language == *language) {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    kind: DiagnosticKind::UnusedLanguage,
+                    language: language.clone(),
+                    rule: None,
+                    message: "not used by any <languagemap>".into(),
+                });
+            }
+
+            // indices of the loaded rules within the <languagerule>, skipped rules have no loaded rule
+            let source_indices: Vec<usize> = (0..rules.len() + errors.len())
+                .filter(|&index| !errors.iter().any(|error| error.index == index))
+                .collect();
+            let diagnostic = |severity, kind, rule: usize, message| Diagnostic {
+                severity,
+                kind,
+                language: language.clone(),
+                rule: Some(rule),
+                message,
+            };
+
+            let mut language_diagnostics: Vec<_> = errors
+                .iter()
+                .map(|error| {
+                    diagnostic(
+                        Severity::Error,
+                        DiagnosticKind::SkippedRule,
+                        error.index,
+                        error.to_string(),
+                    )
+                })
+                .collect();
+
+            let rules = self
+                .rules_for(language)
+                .expect("rules are iterated by language");
+            language_diagnostics.extend(rules.shadowed_rules().into_iter().map(|shadowing| {
+                diagnostic(
+                    Severity::Warning,
+                    DiagnosticKind::ShadowedRule,
+                    source_indices[shadowing.rule],
+                    format!(
+                        "never decides because rule {} matches at the same positions{}",
+                        source_indices[shadowing.by],
+                        if shadowing.conflicting {
+                            " with a different break decision"
+                        } else {
+                            ""
+                        }
+                    ),
+                )
+            }));
+            language_diagnostics.extend(rules.never_matching_rules().into_iter().map(|rule| {
+                diagnostic(
+                    Severity::Warning,
+                    DiagnosticKind::NeverMatchingRule,
+                    source_indices[rule],
+                    "can not match any text".into(),
+                )
+            }));
+
+            language_diagnostics.sort_by_key(|diagnostic| diagnostic.rule);
+            diagnostics.extend(language_diagnostics);
+        }
+
+        Report { diagnostics }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics_collected() {
+        let content = r#"<?xml version="1.0"?>
+<srx version="2.0" xmlns="http://www.lisa.org/srx20">
+    <header segmentsubflows="yes" cascade="yes"/>
+    <body>
+        <languagerules>
+            <languagerule languagerulename="Default">
+                <rule break="yes">
+                    <beforebreak>(?&lt;=a)\.</beforebreak>
+                    <afterbreak>\s</afterbreak>
+                </rule>
+                <rule break="yes">
+                    <beforebreak>[\.\?!]</beforebreak>
+                    <afterbreak>\s</afterbreak>
+                </rule>
+                <rule break="no">
+                    <beforebreak>Mr\.</beforebreak>
+                    <afterbreak>\s</afterbreak>
+                </rule>
+                <rule break="no">
+                    <beforebreak>a^</beforebreak>
+                    <afterbreak>b</afterbreak>
+                </rule>
+            </languagerule>
+            <languagerule languagerulename="Unused">
+                <rule break="yes">
+                    <beforebreak>\.</beforebreak>
+                </rule>
+            </languagerule>
+        </languagerules>
+        <maprules>
+            <languagemap languagepattern=".*" languagerulename="Default"/>
+        </maprules>
+    </body>
+</srx>"#;
+
+        let (srx, report) = SRX::load_with_report(content).expect("content is valid");
+        assert_eq!(srx.rule_count(&Language("Default".into())), Some(3));
+        assert!(report.has_errors());
+
+        let found: Vec<_> = report
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.kind,
+                    diagnostic.language.0.as_str(),
+                    diagnostic.rule,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (DiagnosticKind::SkippedRule, "Default", Some(0)),
+                (DiagnosticKind::ShadowedRule, "Default", Some(2)),
+                (DiagnosticKind::NeverMatchingRule, "Default", Some(3)),
+                (DiagnosticKind::UnusedLanguage, "Unused", None),
+            ]
+        );
+        assert!(report.to_string().contains(
+            "warning: Default rule 2: never decides because rule 1 matches at the same positions with a different break decision\n"
+        ));
+    }
+}
@@ -0,0 +1,434 @@
+//! Resegmentation of translation memories (TMX) and XLIFF 1.2 files.
+//!
+//! Files are scanned leniently instead of being parsed, so everything except the split units is kept byte for byte.
+//! Inline codes (`<bpt>`, `<ph>`, ...) are not seen by the rules and segments never end inside a paired code.
+use std::{collections::HashMap, ops::Range, sync::OnceLock};
+
+use regex::{Captures, Regex};
+
+use super::{Rules, SRX};
+
+/// Elements of TMX and XLIFF whose content is native code instead of text.
+const CODE_ELEMENTS: [&str; 5] = ["bpt", "ept", "ph", "it", "ut"];
+
+macro_rules! regex {
+    ($pattern:expr) => {{
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        REGEX.get_or_init(|| Regex::new($pattern).expect("pattern is valid"))
+    }};
+}
+
+/// The rules of each language, compiled once they are needed.
+struct LanguageRules<'a> {
+    srx: &'a SRX,
+    rules: HashMap<String, Rules>,
+}
+
+impl<'a> LanguageRules<'a> {
+    fn new(srx: &'a SRX) -> Self {
+        LanguageRules {
+            srx,
+            rules: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, lang: &str) -> &Rules {
+        let srx = self.srx;
+        self.rules
+            .entry(lang.to_owned())
+            .or_insert_with(|| srx.language_rules(lang))
+    }
+}
+
+/// Decodes the predefined XML entities and character references, other references are kept as they are.
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let code = match entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => entity.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Gets the text of inline XML content as the rules see it and, for each character of the text,
+/// its offset in the text, its offset in the content and whether a segment may start there.
+fn plain_text(content: &str) -> (String, Vec<(usize, usize, bool)>) {
+    let mut text = String::new();
+    let mut positions = Vec::new();
+    // depth of elements containing code, of other elements, and number of open paired codes
+    let (mut code, mut depth, mut pairs) = (0usize, 0usize, 0usize);
+
+    let mut i = 0;
+    while i < content.len() {
+        let rest = &content[i..];
+
+        // comments, processing instructions and CDATA sections are not seen by the rules
+        if let Some(terminator) = [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>")]
+            .iter()
+            .find_map(|&(opener, terminator)| rest.starts_with(opener).then_some(terminator))
+        {
+            i += rest
+                .find(terminator)
+                .map_or(rest.len(), |end| end + terminator.len());
+            continue;
+        }
+
+        if rest.starts_with('<') {
+            let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            let tag = &rest[..end];
+            let closing = tag.starts_with("</");
+            let empty = tag.ends_with("/>");
+            let name = tag
+                .trim_start_matches(['<', '/'])
+                .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .next()
+                .unwrap_or_default();
+
+            if CODE_ELEMENTS.contains(&name) {
+                match (closing, empty) {
+                    (false, false) => code += 1,
+                    (true, _) => code = code.saturating_sub(1),
+                    _ => {}
+                }
+                // the native code of a paired code is in `<bpt>` and `<ept>`
+                match (name, closing) {
+                    ("bpt", false) => pairs += 1,
+                    ("ept", false) => pairs = pairs.saturating_sub(1),
+                    _ => {}
+                }
+            } else {
+                match (name, closing, empty) {
+                    ("bx", _, _) => pairs += 1,
+                    ("ex", _, _) => pairs = pairs.saturating_sub(1),
+                    (_, false, false) => depth += 1,
+                    (_, true, _) => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
+
+            i += end;
+            continue;
+        }
+
+        let (c, len) = match rest
+            .strip_prefix('&')
+            .and_then(|entity| Some((entity, entity.find(';')?)))
+        {
+            Some((entity, end)) => match decode_entity(&entity[..end]) {
+                Some(c) => (c, end + 2),
+                None => ('&', 1),
+            },
+            None => {
+                let c = rest.chars().next().expect("rest is not empty");
+                (c, c.len_utf8())
+            }
+        };
+
+        if code == 0 {
+            positions.push((text.len(), i, depth == 0 && pairs == 0));
+            text.push(c);
+        }
+        i += len;
+    }
+
+    (text, positions)
+}
+
+/// Splits inline XML content into pieces. Whitespace between the pieces is removed.
+fn split_content(rules: &Rules, content: &str) -> Vec<Range<usize>> {
+    let (text, positions) = plain_text(content);
+
+    let mut cuts: Vec<usize> = rules
+        .split_ranges(&text)
+        .into_iter()
+        .skip(1)
+        .filter_map(|range| {
+            let index = positions
+                .binary_search_by_key(&range.start, |&(start, _, _)| start)
+                .ok()?;
+            let (_, offset, allowed) = positions[index];
+            Some(offset).filter(|_| allowed)
+        })
+        .collect();
+    cuts.insert(0, 0);
+    cuts.push(content.len());
+
+    let last = cuts.len() - 2;
+    cuts.windows(2)
+        .enumerate()
+        .map(|(i, window)| {
+            let piece = &content[window[0]..window[1]];
+            let start = if i == 0 {
+                window[0]
+            } else {
+                window[1] - piece.trim_start().len()
+            };
+            let end = if i == last {
+                window[1]
+            } else {
+                start + content[start..window[1]].trim_end().len()
+            };
+            start..end
+        })
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+/// The whitespace before the line the element at `start` is on, used to indent copies of the element.
+fn indentation(text: &str, start: usize) -> &str {
+    let line = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    let indentation = &text[line..start];
+    if indentation.trim().is_empty() {
+        indentation
+    } else {
+        ""
+    }
+}
+
+/// Replaces each match of `element` in the unit with the piece of the same index of its content, for each piece.
+/// Returns `None` if the contents split into a different number of pieces.
+fn split_unit(
+    unit: &str,
+    element: &Regex,
+    mut split: impl FnMut(&Captures) -> Vec<Range<usize>>,
+) -> Option<Vec<String>> {
+    let pieces: Vec<(Captures, Vec<Range<usize>>)> = element
+        .captures_iter(unit)
+        .map(|captures| {
+            let pieces = split(&captures);
+            (captures, pieces)
+        })
+        .collect();
+
+    let count = pieces.first()?.1.len();
+    if count < 2 || pieces.iter().any(|(_, ranges)| ranges.len() != count) {
+        return None;
+    }
+
+    let units = (0..count)
+        .map(|i| {
+            let mut unit_piece = String::new();
+            let mut last = 0;
+            for (captures, ranges) in &pieces {
+                let content = captures.name("content").expect("elements have content");
+                unit_piece.push_str(&unit[last..content.start()]);
+                unit_piece.push_str(&content.as_str()[ranges[i].clone()]);
+                last = content.end();
+            }
+            unit_piece.push_str(&unit[last..]);
+            unit_piece
+        })
+        .collect();
+    Some(units)
+}
+
+/// Appends `-n` to the ID captured by `id` in the `n`-th copy of a unit, so the copies have unique IDs.
+fn suffix_ids(units: Vec<String>, id: &Regex) -> Vec<String> {
+    units
+        .into_iter()
+        .enumerate()
+        .map(|(i, unit)| {
+            id.replace(&unit, |captures: &Captures| {
+                format!("{}{}-{}", &captures[1], &captures[2], i + 1)
+            })
+            .into_owned()
+        })
+        .collect()
+}
+
+/// Replaces each unit matched by `unit` in the text with the units returned by `split`, on separate lines.
+fn replace_units(
+    text: &str,
+    unit: &Regex,
+    mut split: impl FnMut(usize, &str) -> Option<Vec<String>>,
+) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for found in unit.find_iter(text) {
+        output.push_str(&text[last..found.start()]);
+        match split(found.start(), found.as_str()) {
+            Some(units) => {
+                let separator = format!("\n{}", indentation(text, found.start()));
+                output.push_str(&units.join(&separator));
+            }
+            None => output.push_str(found.as_str()),
+        }
+        last = found.end();
+    }
+
+    output.push_str(&text[last..]);
+    output
+}
+
+/// Splits the translation units of a TMX file into one unit per segment.
+///
+/// The `<seg>` of every `<tuv>` is split with the rules of its `xml:lang`. A unit is only split if all its variants
+/// have the same number of segments, since segments could not be aligned otherwise. The copies of a unit keep
+/// its attributes, properties and notes, the `tuid` of the `n`-th copy gets the suffix `-n`.
+pub fn resegment_tmx(srx: &SRX, tmx: &str) -> String {
+    let tu = regex!(r"(?s)<tu\b[^>]*>.*?</tu>");
+    let tuid = regex!(r#"^(<tu\b[^>]*?\btuid\s*=\s*["'])([^"']*)"#);
+    let seg = regex!(
+        r#"(?s)<tuv\b[^>]*?\b(?:xml:)?lang\s*=\s*["'](?P<lang>[^"']*)["'][^>]*>.*?<seg\b[^>]*>(?P<content>.*?)</seg>"#
+    );
+    let mut rules = LanguageRules::new(srx);
+
+    replace_units(tmx, tu, |_, unit| {
+        let units = split_unit(unit, seg, |captures| {
+            split_content(rules.get(&captures["lang"]), &captures["content"])
+        })?;
+        Some(suffix_ids(units, tuid))
+    })
+}
+
+/// Splits the `<trans-unit>`s of an XLIFF 1.2 file into one unit per segment.
+///
+/// `<source>` and `<target>` are split with the rules of the `source-language` and `target-language` of the
+/// enclosing `<file>`. A unit is only split if its target (if any) has as many segments as its source.
+/// The `id` of the `n`-th copy of a unit gets the suffix `-n`.
+pub fn resegment_xliff(srx: &SRX, xliff: &str) -> String {
+    let trans_unit = regex!(r"(?s)<trans-unit\b[^>]*>.*?</trans-unit>");
+    let content =
+        regex!(r"(?s)<(?P<element>source|target)\b[^>]*>(?P<content>.*?)</(?:source|target)>");
+    let file = regex!(r"<file\b[^>]*>");
+    let source_language = regex!(r#"\bsource-language\s*=\s*["']([^"']*)["']"#);
+    let target_language = regex!(r#"\btarget-language\s*=\s*["']([^"']*)["']"#);
+    let language = |tag: &str, attribute: &Regex| -> String {
+        attribute
+            .captures(tag)
+            .map_or_else(String::new, |captures| captures[1].to_owned())
+    };
+    let id = regex!(r#"^(<trans-unit\b[^>]*?\bid\s*=\s*["'])([^"']*)"#);
+    let mut rules = LanguageRules::new(srx);
+    // the languages of the last `<file>` before the unit, and where the search for the next one starts
+    let (mut source, mut target) = (String::new(), String::new());
+    let mut scanned = 0;
+
+    replace_units(xliff, trans_unit, |start, unit| {
+        if let Some(file) = file.find_iter(&xliff[scanned..start]).last() {
+            source = language(file.as_str(), source_language);
+            target = language(file.as_str(), target_language);
+        }
+        scanned = start + unit.len();
+
+        let units = split_unit(unit, content, |captures| {
+            let lang = match &captures["element"] {
+                "source" => &source,
+                _ => &target,
+            };
+            split_content(rules.get(lang), &captures["content"])
+        })?;
+
+        Some(suffix_ids(units, id))
+    })
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+
+    fn srx() -> SRX {
+        SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+            .expect("example file is valid")
+    }
+
+    #[test]
+    fn content_split_at_text() {
+        let rules = srx().language_rules("en");
+        let content = "Click <bpt i=\"1\">&lt;b&gt;</bpt>OK. Now<ept i=\"1\">&lt;/b&gt;</ept>. Then &amp; <ph x=\"1\"/>go.";
+        let pieces: Vec<&str> = split_content(&rules, content)
+            .into_iter()
+            .map(|range| &content[range])
+            .collect();
+
+        // no segment ends inside the paired code
+        assert_eq!(
+            pieces,
+            vec![
+                "Click <bpt i=\"1\">&lt;b&gt;</bpt>OK. Now<ept i=\"1\">&lt;/b&gt;</ept>.",
+                "Then &amp; <ph x=\"1\"/>go."
+            ]
+        );
+    }
+
+    #[test]
+    fn markup_declarations_skipped() {
+        let rules = srx().language_rules("en");
+
+        for content in [
+            "<!-- note -->Hello. Bye.",
+            "<?pi x?>Hello. Bye.",
+            "Hello.<![CDATA[ ]]> Bye.",
+        ] {
+            assert_eq!(split_content(&rules, content).len(), 2, "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn tmx_resegmented() {
+        let tmx = r#"<body>
+  <tu tuid="1">
+    <tuv xml:lang="en"><seg>Hello. Bye.</seg></tuv>
+    <tuv xml:lang="fr"><seg>Bonjour. Au revoir.</seg></tuv>
+  </tu>
+  <tu tuid="2">
+    <tuv xml:lang="en"><seg>One. Two.</seg></tuv>
+    <tuv xml:lang="fr"><seg>Un, deux.</seg></tuv>
+  </tu>
+</body>"#;
+
+        assert_eq!(
+            resegment_tmx(&srx(), tmx),
+            r#"<body>
+  <tu tuid="1-1">
+    <tuv xml:lang="en"><seg>Hello.</seg></tuv>
+    <tuv xml:lang="fr"><seg>Bonjour.</seg></tuv>
+  </tu>
+  <tu tuid="1-2">
+    <tuv xml:lang="en"><seg>Bye.</seg></tuv>
+    <tuv xml:lang="fr"><seg>Au revoir.</seg></tuv>
+  </tu>
+  <tu tuid="2">
+    <tuv xml:lang="en"><seg>One. Two.</seg></tuv>
+    <tuv xml:lang="fr"><seg>Un, deux.</seg></tuv>
+  </tu>
+</body>"#
+        );
+    }
+
+    #[test]
+    fn xliff_resegmented() {
+        let xliff = r#"<file source-language="en" target-language="fr">
+  <body>
+    <trans-unit id="a"><source>Hello. Bye.</source><target>Bonjour. Au revoir.</target></trans-unit>
+    <trans-unit id="b"><source>One. Two.</source></trans-unit>
+  </body>
+</file>"#;
+
+        assert_eq!(
+            resegment_xliff(&srx(), xliff),
+            r#"<file source-language="en" target-language="fr">
+  <body>
+    <trans-unit id="a-1"><source>Hello.</source><target>Bonjour.</target></trans-unit>
+    <trans-unit id="a-2"><source>Bye.</source><target>Au revoir.</target></trans-unit>
+    <trans-unit id="b-1"><source>One.</source></trans-unit>
+    <trans-unit id="b-2"><source>Two.</source></trans-unit>
+  </body>
+</file>"#
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! A JSON-RPC 2.0 server speaking newline-delimited JSON over stdio or a Unix socket, so other processes on the
+//! same host can split texts without the overhead of HTTP.
+//!
+//! Each line is a request, answered by one line (requests without an `id` are notifications and not answered).
+//! The method `segment` takes the `text` and the `lang` code and returns the `segments` with their `text` and
+//! byte offsets, like `srx-split serve`:
+//!
+//! ```text
+//! --> {"jsonrpc": "2.0", "id": 1, "method": "segment", "params": {"text": "Hello. Bye.", "lang": "en"}}
+//! <-- {"jsonrpc":"2.0","id":1,"result":{"segments":[{"text":"Hello.","start":0,"end":6},{"text":" Bye.","start":6,"end":11}]}}
+//! ```
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    service::{SegmentRequest, Segmentation},
+    SRX,
+};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "serde_crate")]
+struct Request {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "serde_crate")]
+struct ErrorObject {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "serde_crate")]
+struct Response<'a, T> {
+    jsonrpc: &'static str,
+    id: &'a Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorObject>,
+}
+
+fn error_response(id: &Value, code: i64, message: String) -> String {
+    serde_json::to_string(&Response::<()> {
+        jsonrpc: "2.0",
+        id,
+        result: None,
+        error: Some(ErrorObject { code, message }),
+    })
+    .expect("errors can be serialized")
+}
+
+/// A JSON-RPC server splitting texts, see the [module](self) docs. Clones share the compiled rules.
+#[derive(Debug, Clone)]
+pub struct RpcServer {
+    segmentation: Arc<Segmentation>,
+}
+
+impl RpcServer {
+    /// Creates a server splitting with the rules. Rules are compiled once per requested language.
+    pub fn new(srx: SRX) -> Self {
+        RpcServer {
+            segmentation: Arc::new(Segmentation::new(srx)),
+        }
+    }
+
+    /// Answers one line of a request. Returns `None` for notifications and blank lines.
+    pub fn handle(&self, line: &str) -> Option<String> {
+        if line.trim().is_empty() {
+            return None;
+        }
+
+        let request: Request = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(error) if error.is_data() => {
+                return Some(error_response(
+                    &Value::Null,
+                    INVALID_REQUEST,
+                    error.to_string(),
+                ))
+            }
+            Err(error) => {
+                return Some(error_response(&Value::Null, PARSE_ERROR, error.to_string()))
+            }
+        };
+        let id = request.id?;
+
+        if request.jsonrpc != "2.0" {
+            return Some(error_response(
+                &id,
+                INVALID_REQUEST,
+                "only JSON-RPC 2.0 is supported".into(),
+            ));
+        }
+        if request.method != "segment" {
+            return Some(error_response(
+                &id,
+                METHOD_NOT_FOUND,
+                format!("unknown method '{}', use 'segment'", request.method),
+            ));
+        }
+
+        let params: SegmentRequest = match serde_json::from_value(request.params) {
+            Ok(params) => params,
+            Err(error) => return Some(error_response(&id, INVALID_PARAMS, error.to_string())),
+        };
+        let response = Response {
+            jsonrpc: "2.0",
+            id: &id,
+            result: Some(self.segmentation.segment(&params)),
+            error: None,
+        };
+        Some(serde_json::to_string(&response).expect("segments can be serialized"))
+    }
+
+    /// Answers the requests read from the input line by line until it ends. Each response is flushed immediately.
+    pub fn serve<R: BufRead, W: Write>(&self, input: R, mut output: W) -> io::Result<()> {
+        for line in input.lines() {
+            if let Some(response) = self.handle(&line?) {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Answers requests on stdin until it is closed.
+    pub fn serve_stdio(&self) -> io::Result<()> {
+        self.serve(io::stdin().lock(), io::stdout().lock())
+    }
+
+    /// Listens on a Unix socket at the path and answers the requests of each connection on its own thread.
+    /// Runs until accepting a connection fails.
+    ///
+    /// # Errors
+    ///
+    /// If the socket can not be created, e. g. because the path exists already.
+    #[cfg(unix)]
+    pub fn serve_unix<P: AsRef<std::path::Path>>(&self, path: P) -> io::Result<()> {
+        let listener = std::os::unix::net::UnixListener::bind(path)?;
+
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = self.clone();
+            std::thread::spawn(move || {
+                let input = BufReader::new(&stream);
+                // the client going away only ends its own connection
+                let _ = server.serve(input, &stream);
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+
+    fn server() -> RpcServer {
+        RpcServer::new(
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid"),
+        )
+    }
+
+    #[test]
+    fn requests_answered() {
+        let server = server();
+
+        assert_eq!(
+            server.handle(r#"{"jsonrpc": "2.0", "id": 1, "method": "segment", "params": {"text": "Hello. Bye.", "lang": "en"}}"#),
+            Some(r#"{"jsonrpc":"2.0","id":1,"result":{"segments":[{"text":"Hello.","start":0,"end":6},{"text":" Bye.","start":6,"end":11}]}}"#.to_owned())
+        );
+        assert_eq!(
+            server.handle(
+                r#"{"jsonrpc": "2.0", "method": "segment", "params": {"text": "A.", "lang": "en"}}"#
+            ),
+            None
+        );
+
+        let error_code = |line| -> i64 {
+            let response: Value =
+                serde_json::from_str(&server.handle(line).expect("request is answered"))
+                    .expect("response is JSON");
+            response["error"]["code"]
+                .as_i64()
+                .expect("response is an error")
+        };
+        assert_eq!(error_code("{"), PARSE_ERROR);
+        assert_eq!(error_code(r#"{"id": 1}"#), INVALID_REQUEST);
+        assert_eq!(
+            error_code(r#"{"jsonrpc": "2.0", "id": 1, "method": "split"}"#),
+            METHOD_NOT_FOUND
+        );
+        assert_eq!(
+            error_code(
+                r#"{"jsonrpc": "2.0", "id": "a", "method": "segment", "params": {"text": "A."}}"#
+            ),
+            INVALID_PARAMS
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_socket_served() {
+        use std::os::unix::net::UnixStream;
+
+        let path = std::env::temp_dir().join(format!("srx-rpc-{}.sock", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let server = server();
+        let listening = path.clone();
+        std::thread::spawn(move || server.serve_unix(listening));
+
+        let stream = (0..100)
+            .find_map(|_| {
+                UnixStream::connect(&path).ok().or_else(|| {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                    None
+                })
+            })
+            .expect("server is listening");
+        writeln!(&stream, r#"{{"jsonrpc": "2.0", "id": 7, "method": "segment", "params": {{"text": "A. B.", "lang": "en"}}}}"#)
+            .expect("request can be written");
+
+        let mut line = String::new();
+        BufReader::new(&stream)
+            .read_line(&mut line)
+            .expect("response can be read");
+        let response: Value = serde_json::from_str(&line).expect("response is JSON");
+        assert_eq!(response["id"], 7);
+        assert_eq!(
+            response["result"]["segments"].as_array().map(Vec::len),
+            Some(2)
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+}
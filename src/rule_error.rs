@@ -0,0 +1,83 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::Language;
+
+/// The pattern of a rule which failed to compile.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuleHalf {
+    BeforeBreak,
+    AfterBreak,
+}
+
+impl fmt::Display for RuleHalf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RuleHalf::BeforeBreak => "beforebreak",
+            RuleHalf::AfterBreak => "afterbreak",
+        })
+    }
+}
+
+/// Why a rule was skipped.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RuleErrorKind {
+    /// The rule has neither a `<beforebreak>` nor an `<afterbreak>`.
+    Missing,
+    /// A pattern is invalid or uses syntax which is not supported by the `regex` crate (e. g. look-around).
+    Syntax,
+    /// The compiled regex exceeds the size limit of the `regex` crate.
+    TooBig,
+}
+
+/// A rule which was skipped when loading, see [SRX::errors](crate::SRX::errors).
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RuleError {
+    /// The [Language] of the `<languagerule>` the rule belongs to.
+    pub language: Language,
+    /// The index of the rule within its `<languagerule>`, counting skipped rules.
+    pub index: usize,
+    /// The pattern which failed to compile on its own. `None` if both compile on their own
+    /// (i. e. only the combined pattern fails) or the rule has no patterns.
+    pub half: Option<RuleHalf>,
+    /// The `<beforebreak>` pattern as written, if any.
+    pub before_break: Option<String>,
+    /// The `<afterbreak>` pattern as written, if any.
+    pub after_break: Option<String>,
+    pub kind: RuleErrorKind,
+    /// The error message, errors of the `regex` crate span multiple lines with the pattern and the reason.
+    pub message: String,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (beforebreak: {:?}, afterbreak: {:?})",
+            self.message,
+            self.before_break.as_deref().unwrap_or_default(),
+            self.after_break.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+impl std::error::Error for RuleError {}
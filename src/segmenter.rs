@@ -0,0 +1,57 @@
+use std::ops::Range;
+
+use super::{engine::Scratch, splitter::split_ranges_into, Rules};
+
+/// Owns [Rules] together with all buffers needed while splitting (the break mask, prefilter candidates,
+/// capture locations and output ranges), which are reused between calls.
+///
+/// This is the recommended API for splitting many texts: contrary to [Splitter](crate::Splitter) it does not borrow the
+/// rules, so it can be stored in long-lived structs. The methods on [Rules] allocate new buffers for each call
+/// and are convenient for one-off splitting.
+#[derive(Debug, Clone)]
+pub struct Segmenter {
+    rules: Rules,
+    scratch: Scratch,
+}
+
+impl Segmenter {
+    /// Creates a new segmenter from the rules.
+    pub fn new(rules: Rules) -> Self {
+        Segmenter {
+            rules,
+            scratch: Scratch::new(),
+        }
+    }
+
+    /// The rules used by this segmenter.
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Takes the rules out of the segmenter, dropping the buffers.
+    pub fn into_rules(self) -> Rules {
+        self.rules
+    }
+
+    /// Obtain the ranges for text segments. Guaranteed to be at character bounds.
+    /// The ranges are valid until the next call.
+    pub fn split_ranges(&mut self, text: &str) -> &[Range<usize>] {
+        split_ranges_into(&self.rules, text, 0, &mut self.scratch)
+    }
+
+    /// Split text into segments.
+    pub fn split<'s, 't>(&'s mut self, text: &'t str) -> impl Iterator<Item = &'t str> + 's
+    where
+        't: 's,
+    {
+        self.split_ranges(text)
+            .iter()
+            .map(move |range| &text[range.clone()])
+    }
+}
+
+impl From<Rules> for Segmenter {
+    fn from(rules: Rules) -> Self {
+        Segmenter::new(rules)
+    }
+}
@@ -0,0 +1,165 @@
+use std::{ops::Range, rc::Rc, sync::Arc};
+
+use super::{Rules, RulesView, SRX};
+
+/// The interface of a sentence splitter, so pipelines (e. g. the tokenizer of a grammar checker like nlprule)
+/// can be generic over the engine splitting their text instead of depending on [Rules] directly.
+///
+/// Implemented by [Rules] and [RulesView], and by references and smart pointers to implementors.
+pub trait SentenceSplitter {
+    /// Gets the byte ranges of the sentences. The ranges are sorted, at character bounds and cover the whole text.
+    fn sentence_ranges(&self, text: &str) -> Vec<Range<usize>>;
+
+    /// Splits the text into sentences.
+    fn sentences<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        self.sentence_ranges(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+}
+
+impl SentenceSplitter for Rules {
+    fn sentence_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        self.split_ranges(text)
+    }
+}
+
+impl SentenceSplitter for RulesView<'_> {
+    fn sentence_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        self.split_ranges(text)
+    }
+}
+
+impl<T: SentenceSplitter + ?Sized> SentenceSplitter for &T {
+    fn sentence_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        (**self).sentence_ranges(text)
+    }
+}
+
+impl<T: SentenceSplitter + ?Sized> SentenceSplitter for Box<T> {
+    fn sentence_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        (**self).sentence_ranges(text)
+    }
+}
+
+impl<T: SentenceSplitter + ?Sized> SentenceSplitter for Rc<T> {
+    fn sentence_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        (**self).sentence_ranges(text)
+    }
+}
+
+impl<T: SentenceSplitter + ?Sized> SentenceSplitter for Arc<T> {
+    fn sentence_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        (**self).sentence_ranges(text)
+    }
+}
+
+/// Splits with the rules of an [SRX] for a language if there are any, and with a fallback splitter
+/// (e. g. [IcuSentenceSplitter](crate::IcuSentenceSplitter)) otherwise.
+///
+/// Note that the rules are only missing if the `<languagemap>` does not map the language to any rules. Files which
+/// map every language to default rules (e. g. with a `.*` pattern) never fall back.
+#[derive(Debug, Clone)]
+pub enum WithFallback<F> {
+    Rules(Rules),
+    Fallback(F),
+}
+
+impl<F> WithFallback<F> {
+    pub fn new<S: AsRef<str>>(srx: &SRX, lang_code: S, fallback: F) -> Self {
+        let rules = srx.language_rules(lang_code);
+
+        if rules.is_empty() {
+            WithFallback::Fallback(fallback)
+        } else {
+            WithFallback::Rules(rules)
+        }
+    }
+}
+
+impl<F: SentenceSplitter> SentenceSplitter for WithFallback<F> {
+    fn sentence_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        match self {
+            WithFallback::Rules(rules) => rules.sentence_ranges(text),
+            WithFallback::Fallback(fallback) => fallback.sentence_ranges(text),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+    use crate::SRX;
+
+    fn sentences<S: SentenceSplitter>(splitter: S, text: &str) -> Vec<&str> {
+        splitter.sentences(text)
+    }
+
+    #[test]
+    fn implementors_agree() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let text = "Hello. Bye.";
+        let rules = srx.language_rules("en");
+
+        assert_eq!(sentences(&rules, text), vec!["Hello.", " Bye."]);
+        assert_eq!(
+            sentences(srx.rules_view("en"), text),
+            sentences(&rules, text)
+        );
+
+        let boxed: Box<dyn SentenceSplitter> = Box::new(Arc::new(rules));
+        assert_eq!(boxed.sentence_ranges(text), vec![0..6, 6..11]);
+    }
+
+    /// Does not split at all.
+    struct Unsplit;
+
+    impl SentenceSplitter for Unsplit {
+        fn sentence_ranges(&self, text: &str) -> Vec<Range<usize>> {
+            std::iter::once(0..text.len()).collect()
+        }
+    }
+
+    #[test]
+    fn missing_rules_fall_back() {
+        let srx = SRX::from_str(
+            r#"<?xml version="1.0"?>
+<srx version="2.0" xmlns="http://www.lisa.org/srx20">
+    <header cascade="yes"/>
+    <body>
+        <languagerules>
+            <languagerule languagerulename="English">
+                <rule break="yes">
+                    <beforebreak>\.</beforebreak>
+                    <afterbreak>\s</afterbreak>
+                </rule>
+            </languagerule>
+        </languagerules>
+        <maprules>
+            <languagemap languagepattern="[Ee][Nn].*" languagerulename="English"/>
+        </maprules>
+    </body>
+</srx>"#,
+        )
+        .expect("SRX is valid");
+        let text = "Hello. Bye.";
+
+        assert!(matches!(
+            WithFallback::new(&srx, "en", Unsplit),
+            WithFallback::Rules(_)
+        ));
+        assert_eq!(
+            WithFallback::new(&srx, "en", Unsplit).sentences(text),
+            vec!["Hello.", " Bye."]
+        );
+        assert_eq!(
+            WithFallback::new(&srx, "de", Unsplit).sentences(text),
+            vec![text]
+        );
+    }
+}
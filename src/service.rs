@@ -0,0 +1,78 @@
+//! The requests and responses shared by the services splitting texts for other processes.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Rules, SRX};
+
+/// Maximum number of languages whose compiled rules are kept between requests.
+const CACHE_SIZE: usize = 64;
+
+/// A request to split the text with the rules of a language.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "serde_crate", deny_unknown_fields)]
+pub(crate) struct SegmentRequest {
+    pub text: String,
+    pub lang: String,
+}
+
+/// A segment with its byte offsets in the text of the request.
+#[derive(Debug, Serialize)]
+#[serde(crate = "serde_crate")]
+pub(crate) struct Segment<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "serde_crate")]
+pub(crate) struct SegmentResponse<'a> {
+    segments: Vec<Segment<'a>>,
+}
+
+/// The rules and the compiled rules of the languages requested so far.
+#[derive(Debug)]
+pub(crate) struct Segmentation {
+    srx: SRX,
+    cache: Mutex<HashMap<String, Arc<Rules>>>,
+}
+
+impl Segmentation {
+    pub fn new(srx: SRX) -> Self {
+        Segmentation {
+            srx,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn language_rules(&self, lang: &str) -> Arc<Rules> {
+        let mut cache = self.cache.lock().expect("cache is not poisoned");
+        // languages are chosen by clients, so the cache is bounded
+        if cache.len() >= CACHE_SIZE && !cache.contains_key(lang) {
+            cache.clear();
+        }
+        cache
+            .entry(lang.to_owned())
+            .or_insert_with(|| Arc::new(self.srx.language_rules(lang)))
+            .clone()
+    }
+
+    /// Splits the text of the request. The rules are compiled once per language and shared by all requests.
+    pub fn segment<'a>(&self, request: &'a SegmentRequest) -> SegmentResponse<'a> {
+        let segments = self
+            .language_rules(&request.lang)
+            .split_ranges(&request.text)
+            .into_iter()
+            .map(|range| Segment {
+                text: &request.text[range.clone()],
+                start: range.start,
+                end: range.end,
+            })
+            .collect();
+        SegmentResponse { segments }
+    }
+}
@@ -0,0 +1,74 @@
+use std::ops::Range;
+
+use super::{
+    engine::{self, Scratch},
+    Rules,
+};
+
+/// Splits the text with all settings of the rules, reusing the buffers of the scratch.
+/// Shared by [Splitter] and [Segmenter](crate::Segmenter), see [Splitter::split_ranges_from] for `from`.
+pub(crate) fn split_ranges_into<'s>(
+    rules: &Rules,
+    text: &str,
+    from: usize,
+    scratch: &'s mut Scratch,
+) -> &'s [Range<usize>] {
+    engine::split_ranges_with(
+        &rules.rules,
+        rules.prefilter.as_ref(),
+        rules.ascii,
+        rules.recorded_metrics(),
+        rules.preprocessing,
+        rules.hook.as_ref(),
+        text,
+        from,
+        scratch,
+    );
+    &scratch.ranges
+}
+
+/// Splits many texts with the same rules. Created by [Rules::splitter].
+///
+/// Holds buffers (the break mask, prefilter candidates, capture locations and output ranges) which are reused
+/// between calls instead of being allocated for each text, which helps in line-oriented pipelines.
+#[derive(Debug, Clone)]
+pub struct Splitter<'r> {
+    rules: &'r Rules,
+    scratch: Scratch,
+}
+
+impl<'r> Splitter<'r> {
+    pub(crate) fn new(rules: &'r Rules) -> Self {
+        Splitter {
+            rules,
+            scratch: Scratch::new(),
+        }
+    }
+
+    /// The rules used by this splitter.
+    pub fn rules(&self) -> &'r Rules {
+        self.rules
+    }
+
+    /// Obtain the ranges for text segments. Guaranteed to be at character bounds.
+    /// The ranges are valid until the next call.
+    pub fn split_ranges(&mut self, text: &str) -> &[Range<usize>] {
+        self.split_ranges_from(text, 0)
+    }
+
+    /// Like [Splitter::split_ranges], but rules are only matched starting at `from` (a character boundary),
+    /// the text before it is context. So there are no boundaries before `from`.
+    pub(crate) fn split_ranges_from(&mut self, text: &str, from: usize) -> &[Range<usize>] {
+        split_ranges_into(self.rules, text, from, &mut self.scratch)
+    }
+
+    /// Split text into segments.
+    pub fn split<'s, 't>(&'s mut self, text: &'t str) -> impl Iterator<Item = &'t str> + 's
+    where
+        't: 's,
+    {
+        self.split_ranges(text)
+            .iter()
+            .map(move |range| &text[range.clone()])
+    }
+}
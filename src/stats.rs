@@ -0,0 +1,196 @@
+use std::{collections::BTreeMap, fmt};
+
+use super::Rules;
+
+/// The distribution of segment lengths, as the number of segments of each length. Part of [CorpusStats].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Lengths {
+    // number of segments by length
+    counts: BTreeMap<usize, usize>,
+}
+
+impl Lengths {
+    fn add(&mut self, length: usize) {
+        *self.counts.entry(length).or_insert(0) += 1;
+    }
+
+    /// The number of segments.
+    pub fn len(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Whether there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The sum of the lengths of all segments.
+    pub fn total(&self) -> usize {
+        self.counts
+            .iter()
+            .map(|(length, count)| length * count)
+            .sum()
+    }
+
+    /// The length of the shortest segment.
+    pub fn min(&self) -> Option<usize> {
+        self.counts.keys().next().copied()
+    }
+
+    /// The length of the longest segment.
+    pub fn max(&self) -> Option<usize> {
+        self.counts.keys().next_back().copied()
+    }
+
+    /// The average length of the segments, `0` if there are none.
+    pub fn mean(&self) -> f64 {
+        average(self.total(), self.len())
+    }
+
+    /// The length which `percentile` percent of the segments do not exceed, e. g. the median for `50.0`.
+    ///
+    /// # Panics
+    ///
+    /// If `percentile` is not between `0.0` and `100.0`.
+    pub fn percentile(&self, percentile: f64) -> Option<usize> {
+        assert!(
+            (0.0..=100.0).contains(&percentile),
+            "percentile must be between 0 and 100"
+        );
+        let rank = ((percentile / 100.0 * self.len() as f64).ceil() as usize).max(1);
+
+        let mut seen = 0;
+        self.counts.iter().find_map(|(&length, &count)| {
+            seen += count;
+            (seen >= rank).then_some(length)
+        })
+    }
+
+    /// The lengths with the number of segments of that length, shortest first.
+    pub fn counts(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.counts.iter().map(|(&length, &count)| (length, count))
+    }
+}
+
+fn average(total: usize, count: usize) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total as f64 / count as f64
+    }
+}
+
+/// Statistics of the segments of a corpus: the number of segments and the distribution of their lengths in
+/// characters and bytes. Created by [Rules::stats] to compare the segmentation of a corpus before and after
+/// a change of the rules.
+///
+/// Displayed as a summary with the counts, per-document averages and length percentiles.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CorpusStats {
+    /// The number of documents.
+    pub documents: usize,
+    /// The lengths of the segments in characters.
+    pub chars: Lengths,
+    /// The lengths of the segments in bytes.
+    pub bytes: Lengths,
+}
+
+impl CorpusStats {
+    /// The number of segments.
+    pub fn segments(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// The average number of segments per document.
+    pub fn segments_per_document(&self) -> f64 {
+        average(self.segments(), self.documents)
+    }
+
+    /// The average number of characters per document.
+    pub fn chars_per_document(&self) -> f64 {
+        average(self.chars.total(), self.documents)
+    }
+
+    /// The average number of bytes per document.
+    pub fn bytes_per_document(&self) -> f64 {
+        average(self.bytes.total(), self.documents)
+    }
+}
+
+impl fmt::Display for CorpusStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} documents, {} segments ({:.2} per document)",
+            self.documents,
+            self.segments(),
+            self.segments_per_document()
+        )?;
+        for (unit, lengths, per_document) in [
+            ("chars", &self.chars, self.chars_per_document()),
+            ("bytes", &self.bytes, self.bytes_per_document()),
+        ] {
+            writeln!(
+                f,
+                "{}: {:.2} per document, per segment min {}, median {}, p95 {}, max {}, mean {:.2}",
+                unit,
+                per_document,
+                lengths.min().unwrap_or(0),
+                lengths.percentile(50.0).unwrap_or(0),
+                lengths.percentile(95.0).unwrap_or(0),
+                lengths.max().unwrap_or(0),
+                lengths.mean()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Rules {
+    /// Splits the documents and computes statistics of their segments, see [CorpusStats].
+    pub fn stats<'a, I: IntoIterator<Item = &'a str>>(&self, documents: I) -> CorpusStats {
+        let mut splitter = self.splitter();
+        let mut stats = CorpusStats::default();
+
+        for document in documents {
+            stats.documents += 1;
+            for range in splitter.split_ranges(document) {
+                let segment = &document[range.clone()];
+                stats.chars.add(segment.chars().count());
+                stats.bytes.add(segment.len());
+            }
+        }
+
+        stats
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use crate::SRX;
+
+    #[test]
+    fn segments_measured() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let rules = srx.language_rules("de");
+
+        let stats = rules.stats(["Grüße. Tschüss.", "Hallo.", ""]);
+        assert_eq!(stats.documents, 3);
+        assert_eq!(stats.segments(), 3);
+        assert_eq!(stats.segments_per_document(), 1.0);
+        assert_eq!(
+            stats.chars.counts().collect::<Vec<_>>(),
+            vec![(6, 2), (9, 1)]
+        );
+        assert_eq!(stats.bytes.min(), Some(6));
+        assert_eq!(stats.bytes.max(), Some(10));
+        assert_eq!(stats.chars.percentile(50.0), Some(6));
+        assert_eq!(stats.chars.percentile(100.0), Some(9));
+        assert_eq!(stats.chars.total(), 21);
+        assert!(stats.to_string().starts_with("3 documents, 3 segments"));
+    }
+}
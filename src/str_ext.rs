@@ -0,0 +1,39 @@
+use std::ops::Range;
+
+use super::Rules;
+
+/// Splitting methods on `&str`, e. g. `text.split_sentences(&rules)` instead of `rules.split(text)`.
+///
+/// ```
+/// # #[cfg(feature = "from_xml")]
+/// # fn main() -> Result<(), srx::Error> {
+/// use std::{fs, str::FromStr};
+/// use srx::{StrSplitExt, SRX};
+///
+/// let srx = SRX::from_str(&fs::read_to_string("data/example.srx").unwrap())?;
+/// let rules = srx.language_rules("en");
+///
+/// assert_eq!("Hello. Bye.".split_sentences(&rules).collect::<Vec<_>>(), vec!["Hello.", " Bye."]);
+/// assert_eq!("Hello. Bye.".sentence_spans(&rules), vec![0..6, 6..11]);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "from_xml"))]
+/// # fn main() {}
+/// ```
+pub trait StrSplitExt {
+    /// Splits the text into segments, see [Rules::split].
+    fn split_sentences<'t>(&'t self, rules: &Rules) -> impl Iterator<Item = &'t str> + 't;
+
+    /// Gets the byte ranges of the segments, see [Rules::split_ranges].
+    fn sentence_spans(&self, rules: &Rules) -> Vec<Range<usize>>;
+}
+
+impl StrSplitExt for str {
+    fn split_sentences<'t>(&'t self, rules: &Rules) -> impl Iterator<Item = &'t str> + 't {
+        rules.split(self)
+    }
+
+    fn sentence_spans(&self, rules: &Rules) -> Vec<Range<usize>> {
+        rules.split_ranges(self)
+    }
+}
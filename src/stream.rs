@@ -0,0 +1,113 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use super::{
+    reader::{ChunkBuffer, DEFAULT_OVERLAP},
+    Rules,
+};
+
+/// Stream of the segments of text which arrives as a [Stream] of chunks, e. g. the body of a network response.
+/// Created by [Rules::segment_stream].
+///
+/// Chunks can be anything holding bytes (e. g. `String`, `Vec<u8>` or `bytes::Bytes`) and may end within a segment
+/// or even within a UTF-8 character. A segment is yielded as soon as enough text has arrived to make its end final,
+/// following the same strategy as [SplitReader](crate::SplitReader). Fallible streams must be mapped to their chunks
+/// first, an error is only returned if the text is not valid UTF-8.
+///
+/// The wrapped stream must be [Unpin], other streams can be pinned with `Box::pin`.
+pub struct SegmentStream<'r, S> {
+    chunks: ChunkBuffer<'r>,
+    stream: S,
+}
+
+impl<'r, S> SegmentStream<'r, S> {
+    pub(crate) fn new(rules: &'r Rules, stream: S) -> Self {
+        SegmentStream {
+            chunks: ChunkBuffer::new(rules, DEFAULT_OVERLAP),
+            stream,
+        }
+    }
+
+    /// Sets the number of bytes of context kept around chunk edges (default 1 KiB).
+    pub fn with_overlap(mut self, overlap: usize) -> Self {
+        self.chunks.overlap = overlap;
+        self
+    }
+}
+
+impl<'r, S, T> Stream for SegmentStream<'r, S>
+where
+    S: Stream<Item = T> + Unpin,
+    T: AsRef<[u8]>,
+{
+    type Item = io::Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(segment) = this.chunks.pop() {
+                return Poll::Ready(Some(Ok(segment)));
+            }
+
+            if this.chunks.eof {
+                return Poll::Ready(None);
+            }
+
+            let chunk = match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(chunk) => chunk,
+                Poll::Pending => return Poll::Pending,
+            };
+            if let Some(chunk) = &chunk {
+                this.chunks.pending.extend_from_slice(chunk.as_ref());
+            }
+
+            if let Err(error) = this.chunks.push_pending(chunk.is_none()) {
+                this.chunks.eof = true;
+                return Poll::Ready(Some(Err(error)));
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use futures::{executor::block_on, stream, StreamExt};
+
+    use crate::SRX;
+
+    #[test]
+    fn stream_matches_split() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let rules = srx.language_rules("en");
+        let text = "Hello. This is a test. Größe matters. Bye.";
+
+        // chunks end within segments and within the two-byte characters of "Größe"
+        let chunks: Vec<Vec<u8>> = text.as_bytes().chunks(5).map(<[u8]>::to_vec).collect();
+        let segments: Vec<String> = block_on(
+            rules
+                .segment_stream(stream::iter(chunks))
+                .with_overlap(8)
+                .map(|segment| segment.expect("text is valid UTF-8"))
+                .collect(),
+        );
+
+        assert_eq!(segments, rules.split(text).collect::<Vec<_>>());
+
+        let invalid = block_on(
+            rules
+                .segment_stream(stream::iter(vec![vec![b'a', 0xff]]))
+                .collect::<Vec<_>>(),
+        );
+        assert!(invalid.iter().any(|segment| segment.is_err()));
+    }
+}
@@ -0,0 +1,128 @@
+use super::Rules;
+
+impl Rules {
+    /// Adds a tag (e. g. `aggressive` or `legal-abbrev`) to the rule at the index, so it can be disabled together
+    /// with other rules of the same tag. Does nothing if there is no rule at the index.
+    ///
+    /// Rules loaded from SRX files are tagged with the `tags` extension attribute of `<rule>` (in any namespace,
+    /// e. g. `<rule break="no" srx-rs:tags="legal-abbrev">`), which contains whitespace-separated tags.
+    pub fn with_rule_tag<S: Into<String>>(mut self, index: usize, tag: S) -> Self {
+        if let Some(rule) = self.rules.get_mut(index) {
+            let tag = tag.into();
+            if !rule.tags.contains(&tag) {
+                rule.tags.push(tag);
+            }
+        }
+
+        self.update_disabled();
+        self
+    }
+
+    /// Disables all rules with the tag, they then never match. This lets one SRX file serve multiple
+    /// segmentation profiles. Rules keep their indices, so disabled rules are still reported by analyses.
+    pub fn with_tag_disabled<S: Into<String>>(mut self, tag: S) -> Self {
+        self.disabled_tags.insert(tag.into());
+        self.update_disabled();
+        self
+    }
+
+    /// Enables the rules with the tag again, unless they have another disabled tag.
+    pub fn with_tag_enabled(mut self, tag: &str) -> Self {
+        self.disabled_tags.remove(tag);
+        self.update_disabled();
+        self
+    }
+
+    /// Gets the tags of the rule at the index, in the order they were added.
+    pub fn rule_tags(&self, index: usize) -> Option<&[String]> {
+        self.rules.get(index).map(|rule| rule.tags.as_slice())
+    }
+
+    /// Whether the rule at the index is enabled, i. e. has no disabled tag.
+    pub fn is_rule_enabled(&self, index: usize) -> Option<bool> {
+        self.rules.get(index).map(|rule| !rule.disabled)
+    }
+
+    /// Gets the tags of all rules, sorted and without duplicates.
+    pub fn tags(&self) -> Vec<&str> {
+        let mut tags: Vec<&str> = self
+            .rules
+            .iter()
+            .flat_map(|rule| rule.tags.iter().map(String::as_str))
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+
+    fn update_disabled(&mut self) {
+        let disabled_tags = &self.disabled_tags;
+        for rule in &mut self.rules {
+            rule.disabled = rule.tags.iter().any(|tag| disabled_tags.contains(tag));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::SRX;
+
+    #[test]
+    fn disabled_tags_disable_rules() {
+        let content = r#"<?xml version="1.0"?>
+<srx version="2.0" xmlns="http://www.lisa.org/srx20" xmlns:srx-rs="https://github.com/bminixhofer/srx">
+    <header segmentsubflows="yes" cascade="yes"/>
+    <body>
+        <languagerules>
+            <languagerule languagerulename="Default">
+                <rule break="no" srx-rs:tags="legal-abbrev">
+                    <beforebreak>\bArt\.</beforebreak>
+                    <afterbreak>\s</afterbreak>
+                </rule>
+                <rule break="yes" srx-rs:tags="aggressive">
+                    <beforebreak>;</beforebreak>
+                    <afterbreak>\s</afterbreak>
+                </rule>
+                <rule break="yes">
+                    <beforebreak>\.</beforebreak>
+                    <afterbreak>\s</afterbreak>
+                </rule>
+            </languagerule>
+        </languagerules>
+        <maprules>
+            <languagemap languagepattern=".*" languagerulename="Default"/>
+        </maprules>
+    </body>
+</srx>"#;
+        let rules = SRX::from_str(content)
+            .expect("content is valid")
+            .language_rules("en");
+        let text = "See Art. 5; it applies. Done.";
+
+        assert_eq!(rules.tags(), vec!["aggressive", "legal-abbrev"]);
+        assert_eq!(
+            rules.split(text).collect::<Vec<_>>(),
+            vec!["See Art. 5;", " it applies.", " Done."]
+        );
+
+        let rules = rules
+            .with_tag_disabled("aggressive")
+            .with_tag_disabled("legal-abbrev");
+        assert_eq!(rules.is_rule_enabled(1), Some(false));
+        assert_eq!(
+            rules.split(text).collect::<Vec<_>>(),
+            vec!["See Art.", " 5; it applies.", " Done."]
+        );
+        // long enough to be split in parallel with the `rayon` feature
+        let long_text = vec![text; 200].join(" ");
+        assert_eq!(rules.split(&long_text).count(), 600);
+
+        let rules = rules
+            .with_rule_tag(2, "aggressive")
+            .with_tag_enabled("legal-abbrev");
+        assert_eq!(rules.rule_tags(2), Some(&["aggressive".to_owned()][..]));
+        assert_eq!(rules.split(text).collect::<Vec<_>>(), vec![text]);
+    }
+}
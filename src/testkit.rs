@@ -0,0 +1,386 @@
+//! Golden tests of segmentation rules: texts and their expected segments per language, run against an [SRX]
+//! with a report of the breaks which are missing or unexpected.
+//!
+//! Suites can be written in a line format (see [TestSuite::from_str]) or, with the `serde` feature,
+//! deserialized from any format mapping language codes to lists of cases, e. g. YAML:
+//!
+//! ```yaml
+//! en:
+//!   - name: abbreviations
+//!     input: "Hello Mr. Smith. How are you?"
+//!     segments: ["Hello Mr. Smith.", " How are you?"]
+//! ```
+//!
+//! In the test suite of a crate:
+//!
+//! ```
+//! # #[cfg(feature = "from_xml")]
+//! # fn main() -> Result<(), srx::Error> {
+//! use std::{fs, str::FromStr};
+//! use srx::{testkit::TestSuite, SRX};
+//!
+//! let srx = SRX::from_str(&fs::read_to_string("data/example.srx").unwrap())?;
+//! let suite = TestSuite::from_str("[en]\nAsk Mr. Smith.| Now.").unwrap();
+//!
+//! let report = suite.run(&srx);
+//! assert!(report.is_success(), "{}", report);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "from_xml"))]
+//! # fn main() {}
+//! ```
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    str::FromStr,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{trace::context, Rules, SRX};
+
+/// A test case: the input and the segments it is expected to be split into.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", deny_unknown_fields)
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: Option<String>,
+    pub input: String,
+    pub segments: Vec<String>,
+}
+
+/// Test cases by language code, ordered by language code.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TestSuite {
+    pub cases: BTreeMap<String, Vec<TestCase>>,
+}
+
+/// An error in a test suite in the line format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based number of the line.
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for TestSuite {
+    type Err = ParseError;
+
+    /// Parses a suite in the line format: a line `[lang]` starts the cases of a language code, every other line
+    /// is the input of a case with a `|` at each expected break. `\|` and `\\` are a literal `|` and `\`.
+    /// Empty lines and lines starting with `#` are ignored.
+    ///
+    /// ```text
+    /// [en]
+    /// # abbreviations
+    /// Hello Mr. Smith.| How are you?
+    /// ```
+    fn from_str(suite: &str) -> Result<Self, Self::Err> {
+        let mut cases = BTreeMap::new();
+        let mut lang: Option<String> = None;
+
+        for (i, line) in suite.lines().enumerate() {
+            let error = |reason: &str| ParseError {
+                line: i + 1,
+                reason: reason.to_owned(),
+            };
+
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line
+                .trim()
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                lang = Some(name.to_owned());
+                continue;
+            }
+
+            let lang = lang
+                .as_ref()
+                .ok_or_else(|| error("case before the first [lang] line"))?;
+            let mut segments = vec![String::new()];
+            let mut chars = line.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '|' => segments.push(String::new()),
+                    '\\' => match chars.next() {
+                        Some(c @ ('|' | '\\')) => {
+                            segments.last_mut().expect("there is a segment").push(c)
+                        }
+                        _ => return Err(error("`\\` must be followed by `|` or `\\`")),
+                    },
+                    c => segments.last_mut().expect("there is a segment").push(c),
+                }
+            }
+
+            cases
+                .entry(lang.clone())
+                .or_insert_with(Vec::new)
+                .push(TestCase {
+                    name: None,
+                    input: segments.concat(),
+                    segments,
+                });
+        }
+
+        Ok(TestSuite { cases })
+    }
+}
+
+/// A break which is missing or unexpected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundaryDiff {
+    /// Byte index of the break in the input.
+    pub position: usize,
+    /// The text around the position with a `|` at the position, with line breaks escaped.
+    pub context: String,
+    /// The index and pattern of the rule deciding at the position, if any.
+    pub rule: Option<(usize, String)>,
+}
+
+/// The outcome of a test case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    /// The segments do not add up to the input.
+    Invalid,
+    Failed {
+        missing: Vec<BoundaryDiff>,
+        unexpected: Vec<BoundaryDiff>,
+    },
+}
+
+/// The outcome of a case of a suite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseReport {
+    pub lang: String,
+    /// 0-based index of the case among the cases of the language.
+    pub index: usize,
+    pub name: Option<String>,
+    pub outcome: Outcome,
+}
+
+impl CaseReport {
+    /// The language code, 1-based number and name of the case, e. g. `en 2 (abbreviations)`.
+    pub fn title(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} {} ({})", self.lang, self.index + 1, name),
+            None => format!("{} {}", self.lang, self.index + 1),
+        }
+    }
+}
+
+/// The outcomes of all cases of a suite. Displayed as the failing cases with their diffs followed by the
+/// number of passed and failed cases.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+    pub cases: Vec<CaseReport>,
+}
+
+impl Report {
+    pub fn passed(&self) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| case.outcome == Outcome::Passed)
+            .count()
+    }
+
+    /// The number of failed and invalid cases.
+    pub fn failed(&self) -> usize {
+        self.cases.len() - self.passed()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failed() == 0
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for case in &self.cases {
+            let (missing, unexpected) = match &case.outcome {
+                Outcome::Passed => continue,
+                Outcome::Invalid => {
+                    writeln!(
+                        f,
+                        "INVALID {}: segments do not add up to the input",
+                        case.title()
+                    )?;
+                    continue;
+                }
+                Outcome::Failed {
+                    missing,
+                    unexpected,
+                } => (missing, unexpected),
+            };
+
+            writeln!(f, "FAIL {}", case.title())?;
+            for (kind, diffs) in [("missing", missing), ("unexpected", unexpected)] {
+                for diff in diffs {
+                    writeln!(
+                        f,
+                        "  {} break at {}: \"{}\"",
+                        kind, diff.position, diff.context
+                    )?;
+                    if let Some((rule, pattern)) = &diff.rule {
+                        writeln!(f, "    rule {}: {}", rule, pattern)?;
+                    }
+                }
+            }
+        }
+
+        writeln!(f, "{} passed, {} failed", self.passed(), self.failed())
+    }
+}
+
+/// Byte indices at which the text is split into the segments.
+fn boundaries<'a>(segments: impl IntoIterator<Item = &'a str>) -> BTreeSet<usize> {
+    let mut position = 0;
+    let mut boundaries: BTreeSet<_> = segments
+        .into_iter()
+        .map(|segment| {
+            position += segment.len();
+            position
+        })
+        .collect();
+    boundaries.remove(&position);
+    boundaries
+}
+
+fn diffs<'a>(
+    rules: &Rules,
+    input: &str,
+    positions: impl Iterator<Item = &'a usize>,
+) -> Vec<BoundaryDiff> {
+    let decisions = rules.trace(input);
+
+    positions
+        .map(|&position| BoundaryDiff {
+            position,
+            context: context(input, position),
+            rule: decisions
+                .iter()
+                .find(|decision| decision.position == position)
+                .map(|decision| {
+                    (
+                        decision.rule,
+                        rules
+                            .rule_pattern(decision.rule)
+                            .unwrap_or_default()
+                            .to_owned(),
+                    )
+                }),
+        })
+        .collect()
+}
+
+impl TestCase {
+    /// Splits the input with the rules and compares the breaks with the expected ones.
+    pub fn run(&self, rules: &Rules) -> Outcome {
+        if self.segments.concat() != self.input {
+            return Outcome::Invalid;
+        }
+
+        let expected = boundaries(self.segments.iter().map(String::as_str));
+        let actual = boundaries(rules.split(&self.input));
+        if expected == actual {
+            return Outcome::Passed;
+        }
+
+        Outcome::Failed {
+            missing: diffs(rules, &self.input, expected.difference(&actual)),
+            unexpected: diffs(rules, &self.input, actual.difference(&expected)),
+        }
+    }
+}
+
+impl TestSuite {
+    /// Runs every case with the rules of its language.
+    pub fn run(&self, srx: &SRX) -> Report {
+        let mut cases = Vec::new();
+
+        for (lang, lang_cases) in &self.cases {
+            let rules = srx.language_rules(lang);
+
+            cases.extend(
+                lang_cases
+                    .iter()
+                    .enumerate()
+                    .map(|(index, case)| CaseReport {
+                        lang: lang.clone(),
+                        index,
+                        name: case.name.clone(),
+                        outcome: case.run(&rules),
+                    }),
+            );
+        }
+
+        Report { cases }
+    }
+}
+
+#[cfg(all(test, feature = "from_xml"))]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn line_format_parsed() {
+        let suite = TestSuite::from_str("# comment\n[en]\nA.| B\\|C\\\\.\n\n[fr]\nOui.")
+            .expect("suite is valid");
+
+        assert_eq!(suite.cases["en"][0].segments, vec!["A.", " B|C\\."]);
+        assert_eq!(suite.cases["fr"][0].input, "Oui.");
+        assert_eq!(
+            TestSuite::from_str("A.").map_err(|error| error.line),
+            Err(1)
+        );
+    }
+
+    #[test]
+    fn failures_reported() {
+        let srx =
+            SRX::from_str(&fs::read_to_string("data/example.srx").expect("example file exists"))
+                .expect("example file is valid");
+        let mut suite =
+            TestSuite::from_str("[en]\nAsk Mr. Smith.| Now.\nOne. Two.").expect("suite is valid");
+        suite.cases.get_mut("en").expect("suite has English cases")[1].name = Some("wrong".into());
+        suite.cases.insert(
+            "fr".into(),
+            vec![TestCase {
+                name: None,
+                input: "Oui.".into(),
+                segments: vec!["Non.".into()],
+            }],
+        );
+
+        let report = suite.run(&srx);
+        assert_eq!(report.failed(), 2);
+        assert_eq!(
+            report.to_string(),
+            "FAIL en 2 (wrong)\n  unexpected break at 4: \"One.| Two.\"\n    rule 5: [\\.\\?!]+(\\s)\n\
+             INVALID fr 1: segments do not add up to the input\n1 passed, 2 failed\n"
+        );
+    }
+}
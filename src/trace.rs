@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use super::{
+    engine::{self, Candidates},
+    mask::Claims,
+    Rules,
+};
+
+/// Number of characters of context shown around positions in reports.
+const CONTEXT: usize = 20;
+
+/// Gets the text around the position with a `|` at the position, escaping line breaks.
+pub(crate) fn context(text: &str, position: usize) -> String {
+    let before: String = text[..position].chars().rev().take(CONTEXT).collect();
+    let before: String = before.chars().rev().collect();
+    let after: String = text[position..].chars().take(CONTEXT).collect();
+
+    format!("{}|{}", before, after).escape_debug().to_string()
+}
+
+/// A decision of the rules at a byte index of a text. Created by [Rules::trace].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decision {
+    /// The byte index in the text.
+    pub position: usize,
+    /// The index of the rule which decided, i. e. the first rule matching at the position.
+    pub rule: usize,
+    /// Whether the text is split at the position.
+    pub do_break: bool,
+    /// The indices of later rules which also match at the position but are shadowed by the deciding rule.
+    pub shadowed: Vec<usize>,
+}
+
+/// Records every rule matching at each index, in rule order.
+#[derive(Debug, Default)]
+struct Matches(BTreeMap<usize, Vec<usize>>);
+
+impl Claims for Matches {
+    type Value = usize;
+
+    fn claim(&mut self, index: usize, rule: usize) -> bool {
+        self.0.entry(index).or_default().push(rule);
+        // never report a new claim so that evaluation does not stop before shadowed rules are found
+        false
+    }
+}
+
+impl Rules {
+    /// Explains how the text is split: gets the decision at every byte index where any rule matches, in order.
+    ///
+    /// The text is split exactly at the positions of decisions with `do_break`. This evaluates every rule
+    /// on the whole text (or the prefilter windows), so it is slower than splitting.
+    pub fn trace(&self, text: &str) -> Vec<Decision> {
+        let mut positions = Vec::new();
+        let candidates = self.prefilter.as_ref().map(|prefilter| {
+            prefilter.candidates_into(text, &mut positions);
+            Candidates {
+                positions: &positions,
+                radius: prefilter.radius(),
+            }
+        });
+
+        let mut matches = Matches::default();
+        engine::evaluate(
+            &self.rules,
+            text,
+            0,
+            candidates,
+            &mut Vec::new(),
+            &mut engine::capture_locations(&self.rules),
+            &mut matches,
+            |i, _| i,
+        );
+
+        matches
+            .0
+            .into_iter()
+            .map(|(position, rules)| Decision {
+                position,
+                rule: rules[0],
+                do_break: self.rules[rules[0]].do_break(),
+                shadowed: rules[1..].to_vec(),
+            })
+            .collect()
+    }
+
+    /// Gets the pattern of the rule at the index. Consists of the `before_break` and `after_break` patterns
+    /// in the form `before_break(after_break)`.
+    pub fn rule_pattern(&self, index: usize) -> Option<&str> {
+        self.rules.get(index).map(|rule| rule.regex.as_str())
+    }
+
+    /// Gets the `before_break` and `after_break` patterns of the rule at the index exactly as they were written.
+    /// A pattern is `None` if the rule does not have it.
+    pub fn rule_source(&self, index: usize) -> Option<(Option<&str>, Option<&str>)> {
+        self.rules
+            .get(index)
+            .map(|rule| (rule.before_break.as_deref(), rule.after_break.as_deref()))
+    }
+
+    /// Gets whether the rule at the index splits the text where it matches.
+    pub fn rule_breaks(&self, index: usize) -> Option<bool> {
+        self.rules.get(index).map(|rule| rule.do_break())
+    }
+}
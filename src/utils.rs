@@ -1,7 +1,51 @@
+#[cfg(any(feature = "wasm", feature = "jni"))]
+use std::ops::Range;
+
 use regex::{self, Regex};
 
-pub fn full_regex<S: AsRef<str>>(re: S) -> Result<Regex, regex::Error> {
-    let pattern = format!("^{}$", re.as_ref());
+/// Anchors the pattern so that it only matches the entire text.
+pub fn full_pattern<S: AsRef<str>>(re: S) -> String {
+    format!("^{}$", re.as_ref())
+}
+
+/// Checks that the pattern is a valid regex without compiling it.
+pub fn validate_pattern(pattern: &str) -> Result<(), regex::Error> {
+    match regex_syntax::parse(pattern) {
+        Ok(_) => Ok(()),
+        // get the error `regex` reports for this pattern
+        Err(_) => Regex::new(pattern).map(|_| ()),
+    }
+}
+
+/// Converts byte ranges in the text to UTF-16 offsets, flattened to `[start, end, start, end, ...]`.
+/// The ranges must be sorted and lie on character boundaries.
+#[cfg(any(feature = "wasm", feature = "jni"))]
+pub fn utf16_spans(text: &str, ranges: &[Range<usize>]) -> Vec<u32> {
+    let mut spans = Vec::with_capacity(ranges.len() * 2);
+    // byte offset and UTF-16 offset of the last converted position
+    let (mut byte, mut utf16) = (0, 0);
+
+    for range in ranges {
+        for offset in [range.start, range.end] {
+            utf16 += text[byte..offset].encode_utf16().count();
+            byte = offset;
+            spans.push(utf16 as u32);
+        }
+    }
+    spans
+}
+
+#[cfg(all(test, any(feature = "wasm", feature = "jni")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_counted_in_utf16() {
+        let text = "Größe 😀. Next.";
+        let spans = utf16_spans(text, &[0..13, 13..19]);
+        let utf16: Vec<u16> = text.encode_utf16().collect();
 
-    Regex::new(&pattern)
+        assert_eq!(spans, vec![0, 9, 9, 15]);
+        assert_eq!(String::from_utf16_lossy(&utf16[9..15]), " Next.");
+    }
 }
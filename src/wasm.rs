@@ -0,0 +1,65 @@
+//! JavaScript bindings with `wasm-bindgen`, built as a `cdylib` for `wasm32-unknown-unknown`:
+//!
+//! ```text
+//! cargo rustc --release --lib --features wasm --target wasm32-unknown-unknown --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/srx.wasm
+//! ```
+//!
+//! Offsets are returned in UTF-16 code units so they can be used with `String.prototype.slice` directly:
+//!
+//! ```js
+//! const srx = new SRX(xml);
+//! const rules = srx.languageRules("en");
+//! const spans = rules.splitSpans(text); // [start, end, start, end, ...]
+//! ```
+use std::str::FromStr;
+
+use wasm_bindgen::prelude::*;
+
+use super::{utils::utf16_spans, Rules, SRX};
+
+/// SRX rules loaded from an XML string.
+#[wasm_bindgen(js_name = SRX)]
+pub struct JsSRX(SRX);
+
+#[wasm_bindgen(js_class = SRX)]
+impl JsSRX {
+    /// Loads the rules from an XML string in SRX format.
+    #[wasm_bindgen(constructor)]
+    pub fn new(xml: &str) -> Result<JsSRX, JsError> {
+        Ok(JsSRX(SRX::from_str(xml)?))
+    }
+
+    /// Gets the rules for a language code, see [SRX::language_rules].
+    #[wasm_bindgen(js_name = languageRules)]
+    pub fn language_rules(&self, lang_code: &str) -> JsRules {
+        JsRules(self.0.language_rules(lang_code))
+    }
+
+    /// Gets the names of the languages with rules.
+    pub fn languages(&self) -> Vec<String> {
+        self.0
+            .languages()
+            .into_iter()
+            .map(|language| language.0.clone())
+            .collect()
+    }
+}
+
+/// The rules of one language.
+#[wasm_bindgen(js_name = Rules)]
+pub struct JsRules(Rules);
+
+#[wasm_bindgen(js_class = Rules)]
+impl JsRules {
+    /// Splits the text into segments.
+    pub fn split(&self, text: &str) -> Vec<String> {
+        self.0.split(text).map(str::to_owned).collect()
+    }
+
+    /// Splits the text and returns the UTF-16 offsets of the segments as `[start, end, start, end, ...]`.
+    #[wasm_bindgen(js_name = splitSpans)]
+    pub fn split_spans(&self, text: &str) -> Vec<u32> {
+        utf16_spans(text, &self.0.split_ranges(text))
+    }
+}